@@ -0,0 +1,100 @@
+use tauri::{command, State};
+use serde::Deserialize;
+
+use crate::error::AppResult;
+use crate::state::{AppState, AuthedUser};
+use crate::db::Database;
+use crate::services::app_lock_service;
+use crate::models::app_lock::AppLockStatus;
+
+#[derive(Deserialize)]
+pub struct EnableAppLockInput {
+    pub pin: String,
+    pub current_password: String,
+    pub idle_timeout_minutes: u32,
+}
+
+#[command]
+pub fn get_app_lock_status(
+    state: State<AppState>,
+    db: State<Database>,
+) -> AppResult<AppLockStatus> {
+
+    let user = state.signed_in_user()?;
+    let (enabled, locked) = state.lock_status();
+
+    let conn = db.conn.lock().unwrap();
+
+    Ok(AppLockStatus {
+        enabled,
+        locked,
+        idle_timeout_minutes: app_lock_service::get_idle_timeout(&conn, user.id)?,
+    })
+}
+
+#[command]
+pub fn enable_app_lock(
+    user: AuthedUser,
+    state: State<AppState>,
+    db: State<Database>,
+    input: EnableAppLockInput,
+) -> AppResult<()> {
+
+    let conn = db.conn.lock().unwrap();
+
+    app_lock_service::enable_app_lock(
+        &conn,
+        user.id,
+        &input.pin,
+        &input.current_password,
+        input.idle_timeout_minutes,
+    )?;
+
+    state.configure_lock(Some(input.idle_timeout_minutes), false);
+
+    Ok(())
+}
+
+#[command]
+pub fn disable_app_lock(
+    user: AuthedUser,
+    state: State<AppState>,
+    db: State<Database>,
+    current_password: String,
+) -> AppResult<()> {
+
+    let conn = db.conn.lock().unwrap();
+
+    app_lock_service::disable_app_lock(&conn, user.id, &current_password)?;
+
+    state.configure_lock(None, false);
+
+    Ok(())
+}
+
+#[command]
+pub fn lock_app(
+    state: State<AppState>,
+) -> AppResult<()> {
+
+    state.signed_in_user()?;
+    state.lock()
+}
+
+#[command]
+pub fn unlock_app(
+    state: State<AppState>,
+    db: State<Database>,
+    pin: String,
+) -> AppResult<()> {
+
+    let user = state.signed_in_user()?;
+
+    let conn = db.conn.lock().unwrap();
+
+    app_lock_service::verify_pin(&conn, user.id, &pin)?;
+
+    state.unlock();
+
+    Ok(())
+}
@@ -1,10 +1,22 @@
 use tauri::{command, State};
 use serde::Deserialize;
+use std::path::PathBuf;
 
-use crate::state::AppState;
+use crate::error::{AppError, AppResult};
+use crate::state::{AppState, AuthedUser};
 use crate::db::Database;
-use crate::services::user_service;
-use crate::models::user::PublicUser;
+use crate::mail::Mailer;
+use crate::services::{
+    account_service,
+    app_lock_service,
+    login_attempt_service,
+    recovery_service,
+    user_service,
+    verification_service,
+};
+use crate::services::password_policy_service::PasswordPolicy;
+use crate::models::user::{AccountSummary, LoginAttempt, LoginSessionInfo, PublicUser, RegisterResponse};
+use crate::models::password::PasswordReport;
 
 /* ===========================
    INPUT TYPES
@@ -20,6 +32,23 @@ pub struct RegisterInput {
 pub struct LoginInput {
     pub email: String,
     pub password: String,
+    /// Keep the login for this many days instead of the short default.
+    #[serde(default)]
+    pub remember_me_days: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub struct ResetPasswordInput {
+    pub email: String,
+    pub code: String,
+    pub new_password: String,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteAccountInput {
+    pub current_password: String,
+    /// Absolute path for a final backup written before deleting.
+    pub export_path: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -37,20 +66,44 @@ pub struct UpdateProfileInput {
 #[command]
 pub fn register_user(
     db: State<Database>,
+    mailer: State<Mailer>,
+    policy: State<PasswordPolicy>,
     input: RegisterInput,
-) -> Result<PublicUser, String> {
+) -> AppResult<RegisterResponse> {
 
-    if input.password.len() < 6 {
-        return Err("Password too short".into());
-    }
+    let email = input.email.trim().to_lowercase();
+
+    policy.enforce("password", &input.password, Some(&email))?;
 
     let conn = db.conn.lock().unwrap();
 
-    user_service::create_user(
-        &conn,
-        &input.email.trim().to_lowercase(),
-        &input.password,
-    )
+    let user = user_service::create_user(&conn, &email, &input.password)?;
+
+    let recovery_codes = recovery_service::generate_recovery_codes(&conn, user.id)?;
+
+    let verification = verification_service::verification_email(&conn, user.id);
+
+    drop(conn);
+
+    // The account is usable either way; the user can resend later.
+    if let Err(e) = verification.and_then(|mail| mailer.send(&mail)) {
+        log::warn!("Verification email not sent: {}", e);
+    }
+
+    Ok(RegisterResponse { user, recovery_codes })
+}
+
+/// Lets the UI show strength and policy feedback while the user types.
+#[command]
+pub fn check_password_strength(
+    policy: State<PasswordPolicy>,
+    password: String,
+    email: Option<String>,
+) -> PasswordReport {
+
+    let email = email.map(|e| e.trim().to_lowercase());
+
+    policy.evaluate(&password, email.as_deref())
 }
 
 /* ===========================
@@ -62,7 +115,7 @@ pub fn login(
     state: State<AppState>,
     db: State<Database>,
     input: LoginInput,
-) -> Result<PublicUser, String> {
+) -> AppResult<PublicUser> {
 
     let conn = db.conn.lock().unwrap();
 
@@ -72,10 +125,10 @@ pub fn login(
         &input.password,
     )?;
 
-    {
-        let mut current = state.current_user_id.lock().unwrap();
-        *current = Some(user.id);
-    }
+    let expires_at = account_service::issue_login(&conn, user.id, input.remember_me_days)?;
+
+    state.sign_in(user.id, Some(expires_at));
+    state.configure_lock(app_lock_service::get_idle_timeout(&conn, user.id)?, false);
 
     Ok(user.into())
 }
@@ -86,19 +139,13 @@ pub fn login(
 
 #[command]
 pub fn get_current_user(
-    state: State<AppState>,
+    user: AuthedUser,
     db: State<Database>,
-) -> Result<PublicUser, String> {
-
-    let user_id = state
-        .current_user_id
-        .lock()
-        .unwrap()
-        .ok_or("Not authenticated")?;
+) -> AppResult<PublicUser> {
 
     let conn = db.conn.lock().unwrap();
 
-    user_service::get_user_by_id(&conn, user_id)
+    user_service::get_user_by_id(&conn, user.id)
 }
 
 /* ===========================
@@ -107,27 +154,39 @@ pub fn get_current_user(
 
 #[command]
 pub fn update_user_profile(
-    state: State<AppState>,
+    user: AuthedUser,
     db: State<Database>,
+    mailer: State<Mailer>,
+    policy: State<PasswordPolicy>,
     input: UpdateProfileInput,
-) -> Result<PublicUser, String> {
-
-    let user_id = state
-        .current_user_id
-        .lock()
-        .unwrap()
-        .ok_or("Not authenticated")?;
+) -> AppResult<PublicUser> {
 
     let conn = db.conn.lock().unwrap();
 
-    user_service::update_user_profile(
+    let previous_email = user_service::get_user_by_id(&conn, user.id)?.email;
+
+    let updated = user_service::update_user_profile(
         &conn,
-        user_id,
+        &policy,
+        user.id,
         input.display_name,
         input.email.map(|e| e.trim().to_lowercase()),
         input.password,
         input.current_password,
-    )
+    )?;
+
+    // Only a new address needs a new token; other edits don't mail one.
+    if updated.email != previous_email && !updated.email_verified {
+        let verification = verification_service::verification_email(&conn, user.id);
+
+        drop(conn);
+
+        if let Err(e) = verification.and_then(|mail| mailer.send(&mail)) {
+            log::warn!("Verification email not sent: {}", e);
+        }
+    }
+
+    Ok(updated)
 }
 
 /* ===========================
@@ -138,18 +197,52 @@ pub fn update_user_profile(
 pub fn logout_user_command(
     state: State<AppState>,
     db: State<Database>,
-) -> Result<(), String> {
+) -> AppResult<()> {
 
 
-    let conn = db.conn.lock().unwrap();
+    // Logging out without a session is a no-op rather than an error.
+    // Logging out is allowed while locked, so skip the lock check.
+    if let Ok(user) = state.signed_in_user() {
+        let conn = db.conn.lock().unwrap();
+        user_service::logout_user(&conn, user.id)?;
+    }
 
-    user_service::logout_user(&conn)?;
+    state.sign_out();
 
-    {
-        let mut current = state.current_user_id.lock().unwrap();
-        *current = None;
+
+    Ok(())
+}
+
+/* ===========================
+   DELETE ACCOUNT
+=========================== */
+
+#[command]
+pub fn delete_account(
+    app: tauri::AppHandle,
+    user: AuthedUser,
+    state: State<AppState>,
+    db: State<Database>,
+    input: DeleteAccountInput,
+) -> AppResult<()> {
+
+    let export_path = input.export_path.map(PathBuf::from);
+
+    if export_path.as_ref().is_some_and(|p| !p.is_absolute()) {
+        return Err(AppError::field("export_path", "Export path must be absolute"));
     }
 
+    let conn = db.conn.lock().unwrap();
+
+    user_service::delete_account(
+        &conn,
+        &app,
+        user.id,
+        &input.current_password,
+        export_path.as_deref(),
+    )?;
+
+    state.sign_out();
 
     Ok(())
 }
@@ -161,23 +254,187 @@ pub fn logout_user_command(
 #[command]
 pub fn upload_avatar(
     app: tauri::AppHandle,
-    state: State<AppState>,
+    user: AuthedUser,
     db: State<Database>,
     bytes: Vec<u8>,
-) -> Result<String, String> {
-
-    let user_id = state
-        .current_user_id
-        .lock()
-        .unwrap()
-        .ok_or("Not authenticated")?;
+) -> AppResult<String> {
 
     let conn = db.conn.lock().unwrap();
 
     user_service::upload_avatar(
         &conn,
         &app,
-        user_id,
+        user.id,
         bytes,
     )
-}
\ No newline at end of file
+}
+
+/* ===========================
+   ACCOUNTS
+=========================== */
+
+#[command]
+pub fn list_accounts(
+    state: State<AppState>,
+    db: State<Database>,
+) -> AppResult<Vec<AccountSummary>> {
+
+    state.ensure_unlocked()?;
+
+    let conn = db.conn.lock().unwrap();
+
+    account_service::list_accounts(&conn)
+}
+
+#[command]
+pub fn switch_account(
+    state: State<AppState>,
+    db: State<Database>,
+    user_id: i64,
+) -> AppResult<PublicUser> {
+
+    state.ensure_unlocked()?;
+
+    let conn = db.conn.lock().unwrap();
+
+    let (user, expires_at) = account_service::switch_account(&conn, user_id)?;
+
+    // A PIN-protected account asks for its PIN right after switching.
+    state.sign_in(user.id, Some(expires_at));
+    state.configure_lock(app_lock_service::get_idle_timeout(&conn, user.id)?, true);
+
+    Ok(user)
+}
+
+/* ===========================
+   LOGIN SESSIONS
+=========================== */
+
+#[command]
+pub fn list_login_sessions(
+    user: AuthedUser,
+    db: State<Database>,
+) -> AppResult<Vec<LoginSessionInfo>> {
+
+    let conn = db.conn.lock().unwrap();
+
+    account_service::list_login_sessions(&conn, user.id)
+}
+
+#[command]
+pub fn revoke_login_session(
+    user: AuthedUser,
+    state: State<AppState>,
+    db: State<Database>,
+    id: i64,
+) -> AppResult<()> {
+
+    let conn = db.conn.lock().unwrap();
+
+    if account_service::revoke_login_session(&conn, user.id, id)? {
+        state.sign_out();
+    }
+
+    Ok(())
+}
+
+/* ===========================
+   LOGIN ATTEMPTS
+=========================== */
+
+#[command]
+pub fn list_login_attempts(
+    user: AuthedUser,
+    db: State<Database>,
+    limit: Option<u32>,
+) -> AppResult<Vec<LoginAttempt>> {
+
+    let conn = db.conn.lock().unwrap();
+
+    login_attempt_service::list_login_attempts(&conn, user.id, limit)
+}
+
+/* ===========================
+   RECOVERY CODES
+=========================== */
+
+#[command]
+pub fn generate_recovery_codes(
+    user: AuthedUser,
+    db: State<Database>,
+    current_password: String,
+) -> AppResult<Vec<String>> {
+
+    let conn = db.conn.lock().unwrap();
+
+    user_service::confirm_password(&conn, user.id, &current_password)?;
+
+    recovery_service::generate_recovery_codes(&conn, user.id)
+}
+
+#[command]
+pub fn get_recovery_codes_remaining(
+    user: AuthedUser,
+    db: State<Database>,
+) -> AppResult<i64> {
+
+    let conn = db.conn.lock().unwrap();
+
+    recovery_service::count_remaining_codes(&conn, user.id)
+}
+
+#[command]
+pub fn reset_password_with_recovery_code(
+    state: State<AppState>,
+    db: State<Database>,
+    policy: State<PasswordPolicy>,
+    input: ResetPasswordInput,
+) -> AppResult<()> {
+
+    let conn = db.conn.lock().unwrap();
+
+    let user_id = recovery_service::reset_password_with_recovery_code(
+        &conn,
+        &policy,
+        &input.email.trim().to_lowercase(),
+        &input.code,
+        &input.new_password,
+    )?;
+
+    // The reset revoked every login, including this device's.
+    if state.signed_in_user().is_ok_and(|u| u.id == user_id) {
+        state.sign_out();
+    }
+
+    Ok(())
+}
+
+/* ===========================
+   EMAIL VERIFICATION
+=========================== */
+
+#[command]
+pub fn send_verification_email(
+    user: AuthedUser,
+    db: State<Database>,
+    mailer: State<Mailer>,
+) -> AppResult<()> {
+
+    let mail = {
+        let conn = db.conn.lock().unwrap();
+        verification_service::verification_email(&conn, user.id)?
+    };
+
+    mailer.send(&mail)
+}
+
+#[command]
+pub fn verify_email(
+    db: State<Database>,
+    token: String,
+) -> AppResult<PublicUser> {
+
+    let conn = db.conn.lock().unwrap();
+
+    verification_service::verify_email(&conn, &token)
+}
@@ -0,0 +1,59 @@
+use tauri::{command, State};
+use std::path::PathBuf;
+
+use crate::error::{AppError, AppResult};
+use crate::state::AuthedUser;
+use crate::db::Database;
+use crate::services::backup_service;
+use crate::models::backup::{RestoreMode, RestoreSummary};
+
+#[command]
+pub fn export_account_backup(
+    user: AuthedUser,
+    db: State<Database>,
+    path: String,
+) -> AppResult<String> {
+
+    let path = PathBuf::from(path);
+
+    if !path.is_absolute() {
+        return Err(AppError::field("path", "Backup path must be absolute"));
+    }
+
+    let backup = {
+        let conn = db.conn.lock().unwrap();
+        backup_service::export_account_backup(&conn, user.id)?
+    };
+
+    backup_service::write_backup(&backup, &path)?;
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[command]
+pub fn import_account_backup(
+    app: tauri::AppHandle,
+    user: AuthedUser,
+    db: State<Database>,
+    path: String,
+    mode: RestoreMode,
+) -> AppResult<RestoreSummary> {
+
+    let path = PathBuf::from(path);
+
+    if !path.is_absolute() {
+        return Err(AppError::field("path", "Backup path must be absolute"));
+    }
+
+    let content = std::fs::read_to_string(&path)?;
+
+    let mut conn = db.conn.lock().unwrap();
+
+    backup_service::import_account_backup(
+        &mut conn,
+        &app,
+        user.id,
+        &content,
+        mode,
+    )
+}
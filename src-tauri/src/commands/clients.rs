@@ -0,0 +1,74 @@
+use tauri::{command, State};
+use serde::Deserialize;
+
+use crate::error::AppResult;
+use crate::state::AuthedUser;
+use crate::db::Database;
+use crate::services::client_service;
+use crate::models::client::Client;
+
+#[derive(Deserialize)]
+pub struct CreateClientInput {
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateClientInput {
+    pub id: i64,
+    pub name: Option<String>,
+    pub color: Option<String>,
+}
+
+#[command]
+pub fn get_clients(
+    user: AuthedUser,
+    db: State<Database>,
+) -> AppResult<Vec<Client>> {
+
+    let conn = db.conn.lock().unwrap();
+
+    client_service::get_clients(&conn, user.id)
+}
+
+#[command]
+pub fn create_client(
+    user: AuthedUser,
+    db: State<Database>,
+    input: CreateClientInput,
+) -> AppResult<Client> {
+
+    let conn = db.conn.lock().unwrap();
+
+    client_service::create_client(&conn, user.id, &input.name, input.color.as_deref())
+}
+
+#[command]
+pub fn update_client(
+    user: AuthedUser,
+    db: State<Database>,
+    input: UpdateClientInput,
+) -> AppResult<Client> {
+
+    let conn = db.conn.lock().unwrap();
+
+    client_service::update_client(
+        &conn,
+        user.id,
+        input.id,
+        input.name,
+        input.color,
+    )
+}
+
+#[command]
+pub fn delete_client(
+    user: AuthedUser,
+    db: State<Database>,
+    id: i64,
+) -> AppResult<()> {
+
+    let conn = db.conn.lock().unwrap();
+
+    client_service::delete_client(&conn, user.id, id)
+}
@@ -0,0 +1,27 @@
+use tauri::{command, State};
+use std::path::PathBuf;
+
+use crate::error::{AppError, AppResult};
+use crate::state::AuthedUser;
+use crate::db::Database;
+use crate::services::export_service;
+use crate::models::export::{CsvExportOptions, ExportSummary};
+
+#[command]
+pub fn export_sessions_csv(
+    user: AuthedUser,
+    db: State<Database>,
+    path: String,
+    options: CsvExportOptions,
+) -> AppResult<ExportSummary> {
+
+    let path = PathBuf::from(path);
+
+    if !path.is_absolute() {
+        return Err(AppError::field("path", "Export path must be absolute"));
+    }
+
+    let conn = db.conn.lock().unwrap();
+
+    export_service::export_sessions_csv(&conn, user.id, &options, &path)
+}
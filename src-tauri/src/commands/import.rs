@@ -0,0 +1,29 @@
+use tauri::{command, State};
+use std::path::PathBuf;
+
+use crate::error::{AppError, AppResult};
+use crate::state::AuthedUser;
+use crate::db::Database;
+use crate::services::import_service;
+use crate::models::import::{ImportOptions, ImportReport};
+
+#[command]
+pub fn import_sessions(
+    user: AuthedUser,
+    db: State<Database>,
+    path: String,
+    options: ImportOptions,
+) -> AppResult<ImportReport> {
+
+    let path = PathBuf::from(path);
+
+    if !path.is_absolute() {
+        return Err(AppError::field("path", "Import path must be absolute"));
+    }
+
+    let content = std::fs::read_to_string(&path)?;
+
+    let mut conn = db.conn.lock().unwrap();
+
+    import_service::import_sessions(&mut conn, user.id, &content, &options)
+}
@@ -0,0 +1,91 @@
+use tauri::{command, State};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::error::{AppError, AppResult};
+use crate::state::AuthedUser;
+use crate::db::Database;
+use crate::services::invoice_service;
+use crate::models::invoice::{Invoice, InvoiceDraft, InvoiceRequest};
+
+#[derive(Deserialize)]
+pub struct CreateInvoiceInput {
+    #[serde(flatten)]
+    pub request: InvoiceRequest,
+    /// Absolute folder for the HTML and PDF files. Defaults to the
+    /// account's invoice folder in the app data directory.
+    pub output_dir: Option<String>,
+}
+
+#[command]
+pub fn preview_invoice(
+    user: AuthedUser,
+    db: State<Database>,
+    request: InvoiceRequest,
+) -> AppResult<InvoiceDraft> {
+
+    let conn = db.conn.lock().unwrap();
+
+    invoice_service::draft_invoice(&conn, user.id, &request)
+}
+
+#[command]
+pub fn create_invoice(
+    app: tauri::AppHandle,
+    user: AuthedUser,
+    db: State<Database>,
+    input: CreateInvoiceInput,
+) -> AppResult<Invoice> {
+
+    let output_dir = match input.output_dir {
+        Some(dir) => {
+            let dir = PathBuf::from(dir);
+
+            if !dir.is_absolute() {
+                return Err(AppError::field("output_dir", "Output folder must be absolute"));
+            }
+
+            dir
+        }
+        None => invoice_service::invoice_dir(&app, user.id)?,
+    };
+
+    let conn = db.conn.lock().unwrap();
+
+    invoice_service::create_invoice(&conn, user.id, &input.request, &output_dir)
+}
+
+#[command]
+pub fn get_invoices(
+    user: AuthedUser,
+    db: State<Database>,
+) -> AppResult<Vec<Invoice>> {
+
+    let conn = db.conn.lock().unwrap();
+
+    invoice_service::get_invoices(&conn, user.id)
+}
+
+#[command]
+pub fn get_invoice(
+    user: AuthedUser,
+    db: State<Database>,
+    id: i64,
+) -> AppResult<Invoice> {
+
+    let conn = db.conn.lock().unwrap();
+
+    invoice_service::get_invoice(&conn, user.id, id)
+}
+
+#[command]
+pub fn void_invoice(
+    user: AuthedUser,
+    db: State<Database>,
+    id: i64,
+) -> AppResult<Invoice> {
+
+    let conn = db.conn.lock().unwrap();
+
+    invoice_service::void_invoice(&conn, user.id, id)
+}
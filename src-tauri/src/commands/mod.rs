@@ -1,3 +1,11 @@
 pub mod auth;
+pub mod app_lock;
+pub mod clients;
 pub mod projects;
-pub mod sessions;
\ No newline at end of file
+pub mod rates;
+pub mod invoices;
+pub mod sessions;
+pub mod stats;
+pub mod export;
+pub mod import;
+pub mod backup;
\ No newline at end of file
@@ -1,14 +1,26 @@
 use tauri::{command, State};
 use serde::Deserialize;
 
-use crate::state::AppState;
+use crate::error::AppResult;
+use crate::state::AuthedUser;
 use crate::db::Database;
 use crate::services::project_service;
+use crate::models::project::{Project, ProjectListing, ProjectSessionsOnDelete};
 
 #[derive(Deserialize)]
 pub struct CreateProjectInput {
     pub name: String,
     pub color: String,
+    pub parent_id: Option<i64>,
+    pub client_id: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct DeleteProjectInput {
+    pub id: i64,
+    /// Must repeat the project's name.
+    pub confirm_name: String,
+    pub sessions: ProjectSessionsOnDelete,
 }
 
 #[derive(Deserialize)]
@@ -16,81 +28,133 @@ pub struct UpdateProjectInput {
     pub id: i64,
     pub name: Option<String>,
     pub color: Option<String>,
+    pub billable: Option<bool>,
 }
 
+/// With `tree`, projects come grouped by client with their sub-projects.
 #[command]
 pub fn get_projects(
-    state: State<AppState>,
+    user: AuthedUser,
     db: State<Database>,
-) -> Result<Vec<crate::models::project::Project>, String> {
-
-    let user_id = state
-        .current_user_id
-        .lock()
-        .unwrap()
-        .ok_or("Not authenticated")?;
+    include_archived: Option<bool>,
+    tree: Option<bool>,
+) -> AppResult<ProjectListing> {
 
     let conn = db.conn.lock().unwrap();
 
-    project_service::get_projects(&conn, user_id)
+    let include_archived = include_archived.unwrap_or(false);
+
+    if tree.unwrap_or(false) {
+        return project_service::get_project_tree(&conn, user.id, include_archived)
+            .map(ProjectListing::Tree);
+    }
+
+    project_service::get_projects(&conn, user.id, include_archived)
+        .map(ProjectListing::Flat)
 }
 
 #[command]
 pub fn create_project(
-    state: State<AppState>,
+    user: AuthedUser,
     db: State<Database>,
     input: CreateProjectInput,
-) -> Result<crate::models::project::Project, String> {
-
-    let user_id = state
-        .current_user_id
-        .lock()
-        .unwrap()
-        .ok_or("Not authenticated")?;
+) -> AppResult<Project> {
 
     let conn = db.conn.lock().unwrap();
 
-    project_service::create_project(&conn, user_id, &input.name, &input.color)
+    project_service::create_project(
+        &conn,
+        user.id,
+        &input.name,
+        &input.color,
+        input.parent_id,
+        input.client_id,
+    )
 }
 
 #[command]
 pub fn update_project(
-    state: State<AppState>,
+    user: AuthedUser,
     db: State<Database>,
     input: UpdateProjectInput,
-) -> Result<(), String> {
-
-    let user_id = state
-        .current_user_id
-        .lock()
-        .unwrap()
-        .ok_or("Not authenticated")?;
+) -> AppResult<()> {
 
     let conn = db.conn.lock().unwrap();
 
     project_service::update_project(
         &conn,
-        user_id,
+        user.id,
         input.id,
         input.name,
         input.color,
+        input.billable,
     )
 }
 
 #[command]
-pub fn delete_project(
-    state: State<AppState>,
+pub fn set_project_parent(
+    user: AuthedUser,
     db: State<Database>,
     id: i64,
-) -> Result<(), String> {
+    parent_id: Option<i64>,
+) -> AppResult<Project> {
 
-    let user_id = state
-        .current_user_id
-        .lock()
-        .unwrap()
-        .ok_or("Not authenticated")?;
+    let conn = db.conn.lock().unwrap();
+
+    project_service::set_project_parent(&conn, user.id, id, parent_id)
+}
+
+#[command]
+pub fn set_project_client(
+    user: AuthedUser,
+    db: State<Database>,
+    id: i64,
+    client_id: Option<i64>,
+) -> AppResult<Project> {
 
     let conn = db.conn.lock().unwrap();
 
-    project_service::delete_project(&conn, user_id, id)
-}
\ No newline at end of file
+    project_service::set_project_client(&conn, user.id, id, client_id)
+}
+
+#[command]
+pub fn archive_project(
+    user: AuthedUser,
+    db: State<Database>,
+    id: i64,
+) -> AppResult<Project> {
+
+    let conn = db.conn.lock().unwrap();
+
+    project_service::archive_project(&conn, user.id, id)
+}
+
+#[command]
+pub fn unarchive_project(
+    user: AuthedUser,
+    db: State<Database>,
+    id: i64,
+) -> AppResult<Project> {
+
+    let conn = db.conn.lock().unwrap();
+
+    project_service::unarchive_project(&conn, user.id, id)
+}
+
+#[command]
+pub fn delete_project(
+    user: AuthedUser,
+    db: State<Database>,
+    input: DeleteProjectInput,
+) -> AppResult<()> {
+
+    let conn = db.conn.lock().unwrap();
+
+    project_service::delete_project(
+        &conn,
+        user.id,
+        input.id,
+        &input.confirm_name,
+        input.sessions,
+    )
+}
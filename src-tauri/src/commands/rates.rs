@@ -0,0 +1,60 @@
+use tauri::{command, State};
+use serde::Deserialize;
+
+use crate::error::AppResult;
+use crate::state::AuthedUser;
+use crate::db::Database;
+use crate::services::rate_service;
+use crate::models::rate::{HourlyRate, RateScope};
+
+#[derive(Deserialize)]
+pub struct SetRateInput {
+    #[serde(flatten)]
+    pub scope: RateScope,
+    pub amount_cents: i64,
+    pub currency: String,
+    /// RFC 3339; defaults to now.
+    pub effective_from: Option<String>,
+}
+
+#[command]
+pub fn get_rates(
+    user: AuthedUser,
+    db: State<Database>,
+) -> AppResult<Vec<HourlyRate>> {
+
+    let conn = db.conn.lock().unwrap();
+
+    rate_service::get_rates(&conn, user.id)
+}
+
+#[command]
+pub fn set_rate(
+    user: AuthedUser,
+    db: State<Database>,
+    input: SetRateInput,
+) -> AppResult<HourlyRate> {
+
+    let conn = db.conn.lock().unwrap();
+
+    rate_service::set_rate(
+        &conn,
+        user.id,
+        input.scope,
+        input.amount_cents,
+        &input.currency,
+        input.effective_from.as_deref(),
+    )
+}
+
+#[command]
+pub fn delete_rate(
+    user: AuthedUser,
+    db: State<Database>,
+    id: i64,
+) -> AppResult<()> {
+
+    let conn = db.conn.lock().unwrap();
+
+    rate_service::delete_rate(&conn, user.id, id)
+}
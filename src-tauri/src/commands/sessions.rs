@@ -1,103 +1,216 @@
 use tauri::{command, State};
-use crate::state::AppState;
+use serde::Deserialize;
+use crate::error::AppResult;
+use crate::state::AuthedUser;
 use crate::db::Database;
-use crate::services::session_service;
+use crate::services::{search_service, session_service};
 use crate::models::session::{
     ActiveSessionResponse,
     FinishedSessionResponse,
+    SearchHit,
+    SessionPage,
+    SessionQuery,
 };
 
+#[derive(Deserialize)]
+pub struct ManualSessionInput {
+    pub project_id: Option<i64>,
+    pub start_time: String,
+    pub end_time: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub allow_overlap: bool,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateSessionInput {
+    pub id: i64,
+    pub project_id: Option<i64>,
+    pub start_time: String,
+    pub end_time: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub allow_overlap: bool,
+}
+
 #[command]
 pub fn get_active_session(
-    state: State<AppState>,
+    user: AuthedUser,
     db: State<Database>,
-) -> Result<Option<ActiveSessionResponse>, String> {
-
-    let user_id = state
-        .current_user_id
-        .lock()
-        .unwrap()
-        .ok_or("Not authenticated")?;
+) -> AppResult<Option<ActiveSessionResponse>> {
 
     let conn = db.conn.lock().unwrap();
 
-    session_service::get_active_session(&conn, user_id)
+    session_service::get_active_session(&conn, user.id)
 }
 
 #[command]
 pub fn start_session(
-    state: State<AppState>,
+    user: AuthedUser,
     db: State<Database>,
     project_id: Option<i64>,
-) -> Result<ActiveSessionResponse, String> {
-
-    let user_id = state
-        .current_user_id
-        .lock()
-        .unwrap()
-        .ok_or("Not authenticated")?;
+) -> AppResult<ActiveSessionResponse> {
 
     let conn = db.conn.lock().unwrap();
 
-    session_service::start_session(&conn, user_id, project_id)
+    session_service::start_session(&conn, user.id, project_id)
 }
 
 #[command]
 pub fn finalize_session(
-    state: State<AppState>,
+    user: AuthedUser,
     db: State<Database>,
     session_id: i64,
     description: String,
     tags: Vec<String>,
-) -> Result<(), String> {
-
-    let user_id = state
-        .current_user_id
-        .lock()
-        .unwrap()
-        .ok_or("Not authenticated")?;
+) -> AppResult<()> {
 
     let mut conn = db.conn.lock().unwrap();
 
     session_service::finalize_session(
         &mut conn,
-        user_id,
+        user.id,
         session_id,
         description,
         tags,
     )
 }
 
+#[command]
+pub fn pause_session(
+    user: AuthedUser,
+    db: State<Database>,
+) -> AppResult<ActiveSessionResponse> {
+
+    let mut conn = db.conn.lock().unwrap();
+
+    session_service::pause_session(&mut conn, user.id)
+}
+
+#[command]
+pub fn resume_session(
+    user: AuthedUser,
+    db: State<Database>,
+) -> AppResult<ActiveSessionResponse> {
+
+    let mut conn = db.conn.lock().unwrap();
+
+    session_service::resume_session(&mut conn, user.id)
+}
+
 #[command]
 pub fn cancel_session(
-    state: State<AppState>,
+    user: AuthedUser,
     db: State<Database>,
-) -> Result<(), String> {
+) -> AppResult<()> {
 
-    let user_id = state
-        .current_user_id
-        .lock()
-        .unwrap()
-        .ok_or("Not authenticated")?;
+    let mut conn = db.conn.lock().unwrap();
+
+    session_service::cancel_session(&mut conn, user.id)
+}
+
+#[command]
+pub fn get_finished_sessions(
+    user: AuthedUser,
+    db: State<Database>,
+) -> AppResult<Vec<FinishedSessionResponse>> {
 
     let conn = db.conn.lock().unwrap();
 
-    session_service::cancel_session(&conn, user_id)
+    session_service::get_finished_sessions(&conn, user.id)
 }
 
 #[command]
-pub fn get_finished_sessions(
-    state: State<AppState>,
+pub fn query_sessions(
+    user: AuthedUser,
     db: State<Database>,
-) -> Result<Vec<FinishedSessionResponse>, String> {
+    query: SessionQuery,
+) -> AppResult<SessionPage> {
+
+    let conn = db.conn.lock().unwrap();
 
-    let user_id = state
-        .current_user_id
-        .lock()
-        .unwrap()
-        .ok_or("Not authenticated")?;
+    session_service::query_sessions(&conn, user.id, &query)
+}
+
+#[command]
+pub fn search_sessions(
+    user: AuthedUser,
+    db: State<Database>,
+    query: String,
+    limit: Option<u32>,
+) -> AppResult<Vec<SearchHit>> {
 
     let conn = db.conn.lock().unwrap();
 
-    session_service::get_finished_sessions(&conn, user_id)
+    search_service::search_sessions(&conn, user.id, &query, limit)
+}
+
+#[command]
+pub fn create_manual_session(
+    user: AuthedUser,
+    db: State<Database>,
+    input: ManualSessionInput,
+) -> AppResult<FinishedSessionResponse> {
+
+    let mut conn = db.conn.lock().unwrap();
+
+    session_service::create_manual_session(
+        &mut conn,
+        user.id,
+        input.project_id,
+        &input.start_time,
+        &input.end_time,
+        input.description,
+        input.tags,
+        input.allow_overlap,
+    )
+}
+
+#[command]
+pub fn update_session(
+    user: AuthedUser,
+    db: State<Database>,
+    input: UpdateSessionInput,
+) -> AppResult<FinishedSessionResponse> {
+
+    let mut conn = db.conn.lock().unwrap();
+
+    session_service::update_session(
+        &mut conn,
+        user.id,
+        input.id,
+        input.project_id,
+        &input.start_time,
+        &input.end_time,
+        input.description,
+        input.tags,
+        input.allow_overlap,
+    )
+}
+
+#[command]
+pub fn set_session_billable(
+    user: AuthedUser,
+    db: State<Database>,
+    session_id: i64,
+    billable: Option<bool>,
+) -> AppResult<()> {
+
+    let conn = db.conn.lock().unwrap();
+
+    session_service::set_session_billable(&conn, user.id, session_id, billable)
+}
+
+#[command]
+pub fn delete_session(
+    user: AuthedUser,
+    db: State<Database>,
+    session_id: i64,
+) -> AppResult<()> {
+
+    let mut conn = db.conn.lock().unwrap();
+
+    session_service::delete_session(&mut conn, user.id, session_id)
 }
\ No newline at end of file
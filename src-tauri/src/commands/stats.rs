@@ -0,0 +1,104 @@
+use tauri::{command, State};
+use crate::error::AppResult;
+use crate::state::AuthedUser;
+use crate::db::Database;
+use crate::services::stats_service;
+use crate::models::stats::{
+    ClientDuration,
+    DurationTotal,
+    Granularity,
+    PeriodDuration,
+    PeriodEarnings,
+    ProjectDuration,
+    ProjectEarnings,
+    StatsFilter,
+    TagDuration,
+};
+
+#[command]
+pub fn get_total_duration(
+    user: AuthedUser,
+    db: State<Database>,
+    filter: StatsFilter,
+) -> AppResult<DurationTotal> {
+
+    let conn = db.conn.lock().unwrap();
+
+    stats_service::get_total_duration(&conn, user.id, &filter)
+}
+
+#[command]
+pub fn get_duration_by_project(
+    user: AuthedUser,
+    db: State<Database>,
+    filter: StatsFilter,
+    roll_up: Option<bool>,
+) -> AppResult<Vec<ProjectDuration>> {
+
+    let conn = db.conn.lock().unwrap();
+
+    stats_service::get_duration_by_project(&conn, user.id, &filter, roll_up.unwrap_or(false))
+}
+
+#[command]
+pub fn get_duration_by_client(
+    user: AuthedUser,
+    db: State<Database>,
+    filter: StatsFilter,
+) -> AppResult<Vec<ClientDuration>> {
+
+    let conn = db.conn.lock().unwrap();
+
+    stats_service::get_duration_by_client(&conn, user.id, &filter)
+}
+
+#[command]
+pub fn get_duration_by_tag(
+    user: AuthedUser,
+    db: State<Database>,
+    filter: StatsFilter,
+) -> AppResult<Vec<TagDuration>> {
+
+    let conn = db.conn.lock().unwrap();
+
+    stats_service::get_duration_by_tag(&conn, user.id, &filter)
+}
+
+#[command]
+pub fn get_duration_by_period(
+    user: AuthedUser,
+    db: State<Database>,
+    filter: StatsFilter,
+    granularity: Granularity,
+) -> AppResult<Vec<PeriodDuration>> {
+
+    let conn = db.conn.lock().unwrap();
+
+    stats_service::get_duration_by_period(&conn, user.id, &filter, granularity)
+}
+
+#[command]
+pub fn get_earnings_by_project(
+    user: AuthedUser,
+    db: State<Database>,
+    filter: StatsFilter,
+    roll_up: Option<bool>,
+) -> AppResult<Vec<ProjectEarnings>> {
+
+    let conn = db.conn.lock().unwrap();
+
+    stats_service::get_earnings_by_project(&conn, user.id, &filter, roll_up.unwrap_or(false))
+}
+
+#[command]
+pub fn get_earnings_by_period(
+    user: AuthedUser,
+    db: State<Database>,
+    filter: StatsFilter,
+    granularity: Granularity,
+) -> AppResult<Vec<PeriodEarnings>> {
+
+    let conn = db.conn.lock().unwrap();
+
+    stats_service::get_earnings_by_period(&conn, user.id, &filter, granularity)
+}
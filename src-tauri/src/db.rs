@@ -1,71 +1,128 @@
-use rusqlite::Connection;
+pub(crate) mod migrations;
+pub(crate) mod integrity;
+
+use rusqlite::{params, Connection};
+use std::path::Path;
 use std::sync::Mutex;
 use tauri::AppHandle;
 use tauri::Manager;
 
+use crate::error::{AppError, AppResult};
+
 pub struct Database {
     pub conn: Mutex<Connection>,
 }
 
 impl Database {
-    pub fn new(app: &AppHandle) -> Self {
-        let mut path = app.path().app_data_dir().expect("no app data dir");
-        std::fs::create_dir_all(&path).expect("failed to create app data dir");
-        path.push("trackly.db");
-
-        let conn = Connection::open(path)
-            .expect("failed to open database");
-
-        conn.execute_batch(
-            "
-            PRAGMA journal_mode = WAL;
-            PRAGMA foreign_keys = ON;
-
-            CREATE TABLE IF NOT EXISTS users (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                email TEXT NOT NULL UNIQUE,
-                password_hash TEXT NOT NULL,
-                display_name TEXT,
-                avatar_url TEXT,
-                email_verified INTEGER NOT NULL DEFAULT 0,
-                created_at TEXT DEFAULT CURRENT_TIMESTAMP
-            );
-
-            CREATE TABLE IF NOT EXISTS projects (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                user_id INTEGER NOT NULL,
-                name TEXT NOT NULL,
-                color TEXT NOT NULL DEFAULT '#2e86ab',
-                created_at TEXT DEFAULT CURRENT_TIMESTAMP
-            );
-
-            CREATE TABLE IF NOT EXISTS sessions (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                user_id INTEGER NOT NULL,
-                project_id INTEGER,
-                start_time TEXT NOT NULL,
-                end_time TEXT,
-                description TEXT,
-                status TEXT NOT NULL CHECK (
-                    status IN ('running','finished','cancelled')
-                ),
-                created_at TEXT DEFAULT CURRENT_TIMESTAMP
-            );
-
-            CREATE TABLE IF NOT EXISTS session_tags (
-                session_id INTEGER NOT NULL,
-                tag TEXT NOT NULL
-            );
-
-            CREATE TABLE IF NOT EXISTS app_session (
-                id INTEGER PRIMARY KEY CHECK (id = 1),
-                user_id INTEGER NOT NULL
-            );
-            "
-        ).expect("failed to create schema");
-
-        Self {
+    pub fn new(app: &AppHandle) -> AppResult<Self> {
+        let data_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        std::fs::create_dir_all(&data_dir)?;
+
+        let path = data_dir.join("trackly.db");
+
+        let conn = Connection::open(&path)
+            .map_err(AppError::Database)?;
+
+        conn.execute_batch("PRAGMA journal_mode = WAL;")?;
+
+        let secrets = data_dir.join(SECRETS_FILE);
+        create_private_file(&secrets)?;
+        attach_secrets(&conn, &secrets.to_string_lossy())?;
+
+        let conn = prepare(conn, &data_dir.join("backups"))?;
+
+        Ok(Self {
             conn: Mutex::new(conn),
-        }
+        })
+    }
+}
+
+/// Raw login tokens are kept out of `trackly.db`, so copies of it (backups,
+/// pre-migration snapshots) can't be used to sign in.
+const SECRETS_FILE: &str = "login_tokens.db";
+
+/// Attaches the token store as `secrets`.
+fn attach_secrets(conn: &Connection, location: &str) -> AppResult<()> {
+    conn.execute("ATTACH DATABASE ?1 AS secrets", params![location])?;
+
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS secrets.login_tokens (
+             user_id INTEGER PRIMARY KEY,
+             token TEXT NOT NULL
+         );",
+    )?;
+
+    Ok(())
+}
+
+/// Creates `path` if needed and restricts it to the current user where
+/// the platform supports it. On Windows the per-user app data folder
+/// already does that.
+fn create_private_file(path: &Path) -> AppResult<()> {
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    Ok(())
+}
+
+/// Migrates and repairs a freshly opened connection, leaving foreign
+/// keys enforced.
+fn prepare(mut conn: Connection, backup_dir: &Path) -> AppResult<Connection> {
+    // The bundled SQLite enables foreign keys by default. Keep them off
+    // while migrating and repairing so that table rebuilds and orphan
+    // cleanup don't trip constraints halfway through.
+    conn.execute_batch("PRAGMA foreign_keys = OFF;")?;
+
+    migrations::run(&mut conn, backup_dir)?;
+
+    let report = integrity::repair(&mut conn)?;
+
+    for (problem, count) in &report.repaired {
+        log::warn!("Integrity check repaired {} {}", count, problem);
     }
-}
\ No newline at end of file
+
+    if report.unresolved > 0 {
+        log::warn!("Integrity check left {} foreign key violations unrepaired", report.unresolved);
+    }
+
+    conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+
+    Ok(conn)
+}
+
+/// A migrated, empty database for unit tests.
+#[cfg(test)]
+pub(crate) fn open_in_memory() -> Connection {
+    let conn = Connection::open_in_memory().expect("open in-memory database");
+    attach_secrets(&conn, ":memory:").expect("attach in-memory token store");
+
+    // A fresh database has no tables, so nothing is backed up.
+    prepare(conn, &std::env::temp_dir()).expect("migrate in-memory database")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_memory_database_is_migrated_with_foreign_keys_on() {
+        let conn = open_in_memory();
+
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        let foreign_keys: i64 = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0)).unwrap();
+
+        assert_eq!(version, migrations::latest_version());
+        assert_eq!(foreign_keys, 1);
+    }
+}
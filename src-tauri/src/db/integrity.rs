@@ -0,0 +1,197 @@
+use rusqlite::Connection;
+
+use crate::error::AppResult;
+
+/* ===========================
+   INTEGRITY
+=========================== */
+
+/// One repair step: `sql` fixes every row matching the problem described
+/// by `label`, and reports how many it touched.
+struct Repair {
+    label: &'static str,
+    sql: &'static str,
+}
+
+// Parents before children, so rows orphaned by an earlier step are
+// picked up by a later one in the same pass.
+const REPAIRS: &[Repair] = &[
+    Repair {
+        label: "clients without a user",
+        sql: "DELETE FROM clients
+              WHERE user_id NOT IN (SELECT id FROM users)",
+    },
+    Repair {
+        label: "projects without a user",
+        sql: "DELETE FROM projects
+              WHERE user_id NOT IN (SELECT id FROM users)",
+    },
+    Repair {
+        label: "projects pointing at a missing or foreign client",
+        sql: "UPDATE projects SET client_id = NULL
+              WHERE client_id IS NOT NULL
+                AND NOT EXISTS (
+                    SELECT 1 FROM clients c
+                    WHERE c.id = projects.client_id
+                      AND c.user_id = projects.user_id
+                )",
+    },
+    Repair {
+        label: "projects pointing at a missing or foreign parent",
+        sql: "UPDATE projects SET parent_id = NULL
+              WHERE parent_id IS NOT NULL
+                AND NOT EXISTS (
+                    SELECT 1 FROM projects p
+                    WHERE p.id = projects.parent_id
+                      AND p.id != projects.id
+                      AND p.user_id = projects.user_id
+                )",
+    },
+    // Only one level of nesting is allowed; this also breaks cycles.
+    Repair {
+        label: "sub-projects nested under another sub-project",
+        sql: "UPDATE projects SET parent_id = NULL
+              WHERE parent_id IN (
+                    SELECT id FROM projects WHERE parent_id IS NOT NULL
+                )",
+    },
+    Repair {
+        label: "sub-projects with their own client",
+        sql: "UPDATE projects SET client_id = NULL
+              WHERE parent_id IS NOT NULL AND client_id IS NOT NULL",
+    },
+    Repair {
+        label: "sessions without a user",
+        sql: "DELETE FROM sessions
+              WHERE user_id NOT IN (SELECT id FROM users)",
+    },
+    // Also covers what a foreign key can't: a project that exists but
+    // belongs to someone else.
+    Repair {
+        label: "sessions pointing at a missing or foreign project",
+        sql: "UPDATE sessions SET project_id = NULL
+              WHERE project_id IS NOT NULL
+                AND NOT EXISTS (
+                    SELECT 1 FROM projects p
+                    WHERE p.id = sessions.project_id
+                      AND p.user_id = sessions.user_id
+                )",
+    },
+    Repair {
+        label: "rates without a user, or for a missing or foreign client or project",
+        sql: "DELETE FROM hourly_rates
+              WHERE user_id NOT IN (SELECT id FROM users)
+                 OR (client_id IS NOT NULL AND NOT EXISTS (
+                        SELECT 1 FROM clients c
+                        WHERE c.id = hourly_rates.client_id
+                          AND c.user_id = hourly_rates.user_id
+                    ))
+                 OR (project_id IS NOT NULL AND NOT EXISTS (
+                        SELECT 1 FROM projects p
+                        WHERE p.id = hourly_rates.project_id
+                          AND p.user_id = hourly_rates.user_id
+                    ))",
+    },
+    Repair {
+        label: "invoices without a user",
+        sql: "DELETE FROM invoices
+              WHERE user_id NOT IN (SELECT id FROM users)",
+    },
+    Repair {
+        label: "invoice lines without an invoice",
+        sql: "DELETE FROM invoice_lines
+              WHERE invoice_id NOT IN (SELECT id FROM invoices)",
+    },
+    Repair {
+        label: "sessions pointing at a missing, foreign or voided invoice",
+        sql: "UPDATE sessions SET invoice_id = NULL
+              WHERE invoice_id IS NOT NULL
+                AND NOT EXISTS (
+                    SELECT 1 FROM invoices i
+                    WHERE i.id = sessions.invoice_id
+                      AND i.user_id = sessions.user_id
+                      AND i.voided_at IS NULL
+                )",
+    },
+    Repair {
+        label: "tags without a session",
+        sql: "DELETE FROM session_tags
+              WHERE session_id NOT IN (SELECT id FROM sessions)",
+    },
+    Repair {
+        label: "pauses without a session",
+        sql: "DELETE FROM session_pauses
+              WHERE session_id NOT IN (SELECT id FROM sessions)",
+    },
+    Repair {
+        label: "search entries without a session",
+        sql: "DELETE FROM session_search
+              WHERE rowid NOT IN (SELECT id FROM sessions)",
+    },
+    Repair {
+        label: "login sessions without a user",
+        sql: "DELETE FROM login_sessions
+              WHERE user_id NOT IN (SELECT id FROM users)",
+    },
+    Repair {
+        label: "remembered accounts without a user or login",
+        sql: "DELETE FROM app_session
+              WHERE user_id NOT IN (SELECT id FROM users)
+                 OR login_session_id NOT IN (SELECT id FROM login_sessions)",
+    },
+    Repair {
+        label: "login tokens without a remembered account",
+        sql: "DELETE FROM secrets.login_tokens
+              WHERE user_id NOT IN (SELECT user_id FROM app_session)",
+    },
+    Repair {
+        label: "app locks without a user",
+        sql: "DELETE FROM app_lock
+              WHERE user_id NOT IN (SELECT id FROM users)",
+    },
+    Repair {
+        label: "recovery codes without a user",
+        sql: "DELETE FROM recovery_codes
+              WHERE user_id NOT IN (SELECT id FROM users)",
+    },
+    Repair {
+        label: "login attempts for a deleted user",
+        sql: "UPDATE login_attempts SET user_id = NULL
+              WHERE user_id IS NOT NULL
+                AND user_id NOT IN (SELECT id FROM users)",
+    },
+];
+
+pub struct IntegrityReport {
+    /// `(problem, rows fixed)` for each repair that changed something.
+    pub repaired: Vec<(&'static str, usize)>,
+    /// Foreign key violations the repairs don't cover.
+    pub unresolved: i64,
+}
+
+/// Finds and fixes rows that break the schema's references, e.g. left
+/// behind by builds that predate the foreign keys. All repairs run in a
+/// single transaction.
+pub fn repair(conn: &mut Connection) -> AppResult<IntegrityReport> {
+    let tx = conn.transaction()?;
+
+    let mut repaired = Vec::new();
+
+    for repair in REPAIRS {
+        let count = tx.execute(repair.sql, [])?;
+
+        if count > 0 {
+            repaired.push((repair.label, count));
+        }
+    }
+
+    let unresolved = tx.query_row(
+        "SELECT COUNT(*) FROM pragma_foreign_key_check",
+        [],
+        |row| row.get(0),
+    )?;
+
+    tx.commit()?;
+
+    Ok(IntegrityReport { repaired, unresolved })
+}
@@ -0,0 +1,862 @@
+use rusqlite::{params, Connection};
+use chrono::Utc;
+use std::path::Path;
+
+use crate::error::{AppError, AppResult};
+
+/* ===========================
+   MIGRATIONS
+=========================== */
+
+/// A single schema step. `version` must be unique and strictly
+/// increasing; the applied version is tracked in `PRAGMA user_version`.
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+/// Ordered list of every schema change. Never edit a migration that has
+/// shipped — append a new one instead.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        // IF NOT EXISTS keeps this safe for installs created before
+        // migrations were tracked (user_version = 0 with tables present).
+        sql: "
+            CREATE TABLE IF NOT EXISTS users (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                email TEXT NOT NULL UNIQUE,
+                password_hash TEXT NOT NULL,
+                display_name TEXT,
+                avatar_url TEXT,
+                email_verified INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE TABLE IF NOT EXISTS projects (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                color TEXT NOT NULL DEFAULT '#2e86ab',
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE TABLE IF NOT EXISTS sessions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL,
+                project_id INTEGER,
+                start_time TEXT NOT NULL,
+                end_time TEXT,
+                description TEXT,
+                status TEXT NOT NULL CHECK (
+                    status IN ('running','finished','cancelled')
+                ),
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE TABLE IF NOT EXISTS session_tags (
+                session_id INTEGER NOT NULL,
+                tag TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS app_session (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                user_id INTEGER NOT NULL
+            );
+        ",
+    },
+    Migration {
+        version: 2,
+        name: "session_pauses",
+        // SQLite can't alter a CHECK constraint, so `sessions` is rebuilt
+        // to admit the new 'paused' status.
+        sql: "
+            CREATE TABLE sessions_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL,
+                project_id INTEGER,
+                start_time TEXT NOT NULL,
+                end_time TEXT,
+                description TEXT,
+                status TEXT NOT NULL CHECK (
+                    status IN ('running','paused','finished','cancelled')
+                ),
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP
+            );
+
+            INSERT INTO sessions_new
+                (id, user_id, project_id, start_time, end_time, description, status, created_at)
+            SELECT id, user_id, project_id, start_time, end_time, description, status, created_at
+            FROM sessions;
+
+            DROP TABLE sessions;
+            ALTER TABLE sessions_new RENAME TO sessions;
+
+            CREATE TABLE session_pauses (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id INTEGER NOT NULL,
+                start_time TEXT NOT NULL,
+                end_time TEXT
+            );
+
+            CREATE INDEX idx_session_pauses_session
+                ON session_pauses (session_id);
+        ",
+    },
+    Migration {
+        version: 3,
+        name: "stats_indexes",
+        sql: "
+            CREATE INDEX IF NOT EXISTS idx_sessions_user_status_start
+                ON sessions (user_id, status, start_time);
+
+            CREATE INDEX IF NOT EXISTS idx_session_tags_session
+                ON session_tags (session_id);
+        ",
+    },
+    Migration {
+        version: 4,
+        name: "session_search_fts",
+        // rowid mirrors sessions.id; `tags` holds the session's tags joined
+        // by spaces. Triggers keep the index in step with both tables.
+        sql: "
+            CREATE VIRTUAL TABLE session_search USING fts5(
+                description,
+                tags,
+                tokenize = 'unicode61 remove_diacritics 2'
+            );
+
+            INSERT INTO session_search (rowid, description, tags)
+            SELECT s.id,
+                   COALESCE(s.description, ''),
+                   COALESCE((SELECT group_concat(t.tag, ' ')
+                             FROM session_tags t
+                             WHERE t.session_id = s.id), '')
+            FROM sessions s;
+
+            CREATE TRIGGER sessions_search_insert AFTER INSERT ON sessions
+            BEGIN
+                INSERT INTO session_search (rowid, description, tags)
+                VALUES (new.id, COALESCE(new.description, ''), '');
+            END;
+
+            CREATE TRIGGER sessions_search_update AFTER UPDATE OF description ON sessions
+            BEGIN
+                UPDATE session_search
+                SET description = COALESCE(new.description, '')
+                WHERE rowid = new.id;
+            END;
+
+            CREATE TRIGGER sessions_search_delete AFTER DELETE ON sessions
+            BEGIN
+                DELETE FROM session_search WHERE rowid = old.id;
+            END;
+
+            CREATE TRIGGER session_tags_search_insert AFTER INSERT ON session_tags
+            BEGIN
+                UPDATE session_search
+                SET tags = COALESCE((SELECT group_concat(tag, ' ')
+                                     FROM session_tags
+                                     WHERE session_id = new.session_id), '')
+                WHERE rowid = new.session_id;
+            END;
+
+            CREATE TRIGGER session_tags_search_delete AFTER DELETE ON session_tags
+            BEGIN
+                UPDATE session_search
+                SET tags = COALESCE((SELECT group_concat(tag, ' ')
+                                     FROM session_tags
+                                     WHERE session_id = old.session_id), '')
+                WHERE rowid = old.session_id;
+            END;
+        ",
+    },
+    Migration {
+        version: 5,
+        name: "multi_account_sessions",
+        // One row per remembered account instead of a single pinned row;
+        // the partial unique index allows at most one current account.
+        sql: "
+            CREATE TABLE app_session_new (
+                user_id INTEGER PRIMARY KEY,
+                is_current INTEGER NOT NULL DEFAULT 0,
+                signed_in_at TEXT NOT NULL,
+                last_used_at TEXT NOT NULL
+            );
+
+            INSERT INTO app_session_new (user_id, is_current, signed_in_at, last_used_at)
+            SELECT user_id, 1, strftime('%Y-%m-%dT%H:%M:%S+00:00', 'now'),
+                   strftime('%Y-%m-%dT%H:%M:%S+00:00', 'now')
+            FROM app_session;
+
+            DROP TABLE app_session;
+            ALTER TABLE app_session_new RENAME TO app_session;
+
+            CREATE UNIQUE INDEX idx_app_session_current
+                ON app_session (is_current) WHERE is_current = 1;
+        ",
+    },
+    Migration {
+        version: 6,
+        name: "login_sessions",
+        // Remembered accounts now hold a token checked against
+        // `login_sessions`. Rows from before this version carry no token,
+        // so those accounts sign in once more.
+        sql: "
+            CREATE TABLE login_sessions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL,
+                token_hash TEXT NOT NULL UNIQUE,
+                created_at TEXT NOT NULL,
+                last_used_at TEXT NOT NULL,
+                expires_at TEXT NOT NULL,
+                revoked_at TEXT
+            );
+
+            CREATE INDEX idx_login_sessions_user
+                ON login_sessions (user_id);
+
+            DROP TABLE app_session;
+
+            CREATE TABLE app_session (
+                user_id INTEGER PRIMARY KEY,
+                login_session_id INTEGER NOT NULL,
+                token TEXT NOT NULL,
+                is_current INTEGER NOT NULL DEFAULT 0
+            );
+
+            CREATE UNIQUE INDEX idx_app_session_current
+                ON app_session (is_current) WHERE is_current = 1;
+        ",
+    },
+    Migration {
+        version: 7,
+        name: "app_lock",
+        sql: "
+            CREATE TABLE app_lock (
+                user_id INTEGER PRIMARY KEY,
+                pin_hash TEXT NOT NULL,
+                idle_timeout_minutes INTEGER NOT NULL DEFAULT 5,
+                failed_attempts INTEGER NOT NULL DEFAULT 0,
+                locked_until TEXT
+            );
+        ",
+    },
+    Migration {
+        version: 8,
+        name: "login_attempts",
+        // Throttling is keyed by email whether or not an account exists,
+        // so responses can't be used to probe for accounts.
+        sql: "
+            CREATE TABLE login_attempts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                email TEXT NOT NULL,
+                user_id INTEGER,
+                outcome TEXT NOT NULL CHECK (
+                    outcome IN ('succeeded','invalid_credentials','throttled')
+                ),
+                attempted_at TEXT NOT NULL
+            );
+
+            CREATE INDEX idx_login_attempts_user
+                ON login_attempts (user_id, attempted_at);
+
+            CREATE TABLE login_throttle (
+                email TEXT PRIMARY KEY,
+                failed_count INTEGER NOT NULL DEFAULT 0,
+                locked_until TEXT
+            );
+        ",
+    },
+    Migration {
+        version: 9,
+        name: "recovery_codes",
+        sql: "
+            CREATE TABLE recovery_codes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL,
+                code_hash TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                used_at TEXT
+            );
+
+            CREATE INDEX idx_recovery_codes_user
+                ON recovery_codes (user_id);
+        ",
+    },
+    Migration {
+        version: 10,
+        name: "app_secrets",
+        // Per-install keys, e.g. for signing email verification tokens.
+        sql: "
+            CREATE TABLE app_secrets (
+                name TEXT PRIMARY KEY,
+                value BLOB NOT NULL
+            );
+        ",
+    },
+    Migration {
+        version: 11,
+        name: "project_archiving",
+        sql: "
+            ALTER TABLE projects ADD COLUMN archived_at TEXT;
+        ",
+    },
+    Migration {
+        version: 12,
+        name: "foreign_keys",
+        // SQLite can't add constraints to existing tables, so every table
+        // that points at a user, project or session is rebuilt. Rows are
+        // copied as-is; `integrity::repair` fixes orphans on startup.
+        // Dropping `sessions` and `session_tags` drops their search
+        // triggers, so those are recreated as in version 4.
+        sql: "
+            CREATE TABLE projects_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL
+                    REFERENCES users (id) ON DELETE CASCADE,
+                name TEXT NOT NULL,
+                color TEXT NOT NULL DEFAULT '#2e86ab',
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                archived_at TEXT
+            );
+
+            INSERT INTO projects_new (id, user_id, name, color, created_at, archived_at)
+            SELECT id, user_id, name, color, created_at, archived_at
+            FROM projects;
+
+            CREATE TABLE sessions_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL
+                    REFERENCES users (id) ON DELETE CASCADE,
+                project_id INTEGER
+                    REFERENCES projects (id) ON DELETE SET NULL,
+                start_time TEXT NOT NULL,
+                end_time TEXT,
+                description TEXT,
+                status TEXT NOT NULL CHECK (
+                    status IN ('running','paused','finished','cancelled')
+                ),
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP
+            );
+
+            INSERT INTO sessions_new
+                (id, user_id, project_id, start_time, end_time, description, status, created_at)
+            SELECT id, user_id, project_id, start_time, end_time, description, status, created_at
+            FROM sessions;
+
+            CREATE TABLE session_tags_new (
+                session_id INTEGER NOT NULL
+                    REFERENCES sessions (id) ON DELETE CASCADE,
+                tag TEXT NOT NULL
+            );
+
+            INSERT INTO session_tags_new (session_id, tag)
+            SELECT session_id, tag FROM session_tags;
+
+            CREATE TABLE session_pauses_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id INTEGER NOT NULL
+                    REFERENCES sessions (id) ON DELETE CASCADE,
+                start_time TEXT NOT NULL,
+                end_time TEXT
+            );
+
+            INSERT INTO session_pauses_new (id, session_id, start_time, end_time)
+            SELECT id, session_id, start_time, end_time FROM session_pauses;
+
+            CREATE TABLE login_sessions_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL
+                    REFERENCES users (id) ON DELETE CASCADE,
+                token_hash TEXT NOT NULL UNIQUE,
+                created_at TEXT NOT NULL,
+                last_used_at TEXT NOT NULL,
+                expires_at TEXT NOT NULL,
+                revoked_at TEXT
+            );
+
+            INSERT INTO login_sessions_new
+                (id, user_id, token_hash, created_at, last_used_at, expires_at, revoked_at)
+            SELECT id, user_id, token_hash, created_at, last_used_at, expires_at, revoked_at
+            FROM login_sessions;
+
+            CREATE TABLE app_session_new (
+                user_id INTEGER PRIMARY KEY
+                    REFERENCES users (id) ON DELETE CASCADE,
+                login_session_id INTEGER NOT NULL
+                    REFERENCES login_sessions (id) ON DELETE CASCADE,
+                token TEXT NOT NULL,
+                is_current INTEGER NOT NULL DEFAULT 0
+            );
+
+            INSERT INTO app_session_new (user_id, login_session_id, token, is_current)
+            SELECT user_id, login_session_id, token, is_current FROM app_session;
+
+            CREATE TABLE app_lock_new (
+                user_id INTEGER PRIMARY KEY
+                    REFERENCES users (id) ON DELETE CASCADE,
+                pin_hash TEXT NOT NULL,
+                idle_timeout_minutes INTEGER NOT NULL DEFAULT 5,
+                failed_attempts INTEGER NOT NULL DEFAULT 0,
+                locked_until TEXT
+            );
+
+            INSERT INTO app_lock_new
+                (user_id, pin_hash, idle_timeout_minutes, failed_attempts, locked_until)
+            SELECT user_id, pin_hash, idle_timeout_minutes, failed_attempts, locked_until
+            FROM app_lock;
+
+            CREATE TABLE login_attempts_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                email TEXT NOT NULL,
+                user_id INTEGER
+                    REFERENCES users (id) ON DELETE SET NULL,
+                outcome TEXT NOT NULL CHECK (
+                    outcome IN ('succeeded','invalid_credentials','throttled')
+                ),
+                attempted_at TEXT NOT NULL
+            );
+
+            INSERT INTO login_attempts_new (id, email, user_id, outcome, attempted_at)
+            SELECT id, email, user_id, outcome, attempted_at FROM login_attempts;
+
+            CREATE TABLE recovery_codes_new (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL
+                    REFERENCES users (id) ON DELETE CASCADE,
+                code_hash TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                used_at TEXT
+            );
+
+            INSERT INTO recovery_codes_new (id, user_id, code_hash, created_at, used_at)
+            SELECT id, user_id, code_hash, created_at, used_at FROM recovery_codes;
+
+            DROP TABLE session_tags;
+            DROP TABLE session_pauses;
+            DROP TABLE sessions;
+            DROP TABLE projects;
+            DROP TABLE app_session;
+            DROP TABLE login_sessions;
+            DROP TABLE app_lock;
+            DROP TABLE login_attempts;
+            DROP TABLE recovery_codes;
+
+            ALTER TABLE projects_new RENAME TO projects;
+            ALTER TABLE sessions_new RENAME TO sessions;
+            ALTER TABLE session_tags_new RENAME TO session_tags;
+            ALTER TABLE session_pauses_new RENAME TO session_pauses;
+            ALTER TABLE login_sessions_new RENAME TO login_sessions;
+            ALTER TABLE app_session_new RENAME TO app_session;
+            ALTER TABLE app_lock_new RENAME TO app_lock;
+            ALTER TABLE login_attempts_new RENAME TO login_attempts;
+            ALTER TABLE recovery_codes_new RENAME TO recovery_codes;
+
+            CREATE INDEX idx_projects_user
+                ON projects (user_id);
+
+            CREATE INDEX idx_sessions_user_status_start
+                ON sessions (user_id, status, start_time);
+
+            CREATE INDEX idx_sessions_project
+                ON sessions (project_id);
+
+            CREATE INDEX idx_session_tags_session
+                ON session_tags (session_id);
+
+            CREATE INDEX idx_session_pauses_session
+                ON session_pauses (session_id);
+
+            CREATE INDEX idx_login_sessions_user
+                ON login_sessions (user_id);
+
+            CREATE UNIQUE INDEX idx_app_session_current
+                ON app_session (is_current) WHERE is_current = 1;
+
+            CREATE INDEX idx_app_session_login
+                ON app_session (login_session_id);
+
+            CREATE INDEX idx_login_attempts_user
+                ON login_attempts (user_id, attempted_at);
+
+            CREATE INDEX idx_recovery_codes_user
+                ON recovery_codes (user_id);
+
+            CREATE TRIGGER sessions_search_insert AFTER INSERT ON sessions
+            BEGIN
+                INSERT INTO session_search (rowid, description, tags)
+                VALUES (new.id, COALESCE(new.description, ''), '');
+            END;
+
+            CREATE TRIGGER sessions_search_update AFTER UPDATE OF description ON sessions
+            BEGIN
+                UPDATE session_search
+                SET description = COALESCE(new.description, '')
+                WHERE rowid = new.id;
+            END;
+
+            CREATE TRIGGER sessions_search_delete AFTER DELETE ON sessions
+            BEGIN
+                DELETE FROM session_search WHERE rowid = old.id;
+            END;
+
+            CREATE TRIGGER session_tags_search_insert AFTER INSERT ON session_tags
+            BEGIN
+                UPDATE session_search
+                SET tags = COALESCE((SELECT group_concat(tag, ' ')
+                                     FROM session_tags
+                                     WHERE session_id = new.session_id), '')
+                WHERE rowid = new.session_id;
+            END;
+
+            CREATE TRIGGER session_tags_search_delete AFTER DELETE ON session_tags
+            BEGIN
+                UPDATE session_search
+                SET tags = COALESCE((SELECT group_concat(tag, ' ')
+                                     FROM session_tags
+                                     WHERE session_id = old.session_id), '')
+                WHERE rowid = old.session_id;
+            END;
+        ",
+    },
+    Migration {
+        version: 13,
+        name: "clients",
+        // One level of nesting: a sub-project's parent is always a
+        // top-level project, and the client is set on the top level only.
+        sql: "
+            CREATE TABLE clients (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL
+                    REFERENCES users (id) ON DELETE CASCADE,
+                name TEXT NOT NULL,
+                color TEXT NOT NULL DEFAULT '#6c757d',
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP
+            );
+
+            CREATE INDEX idx_clients_user
+                ON clients (user_id);
+
+            ALTER TABLE projects ADD COLUMN client_id INTEGER
+                REFERENCES clients (id) ON DELETE SET NULL;
+
+            ALTER TABLE projects ADD COLUMN parent_id INTEGER
+                REFERENCES projects (id) ON DELETE SET NULL;
+
+            CREATE INDEX idx_projects_client
+                ON projects (client_id);
+
+            CREATE INDEX idx_projects_parent
+                ON projects (parent_id);
+        ",
+    },
+    Migration {
+        version: 14,
+        name: "billing",
+        // A rate applies to the whole account, a client or a project, and
+        // each change is a new row effective from a point in time, so past
+        // sessions keep the rate they were worked at. `sessions.billable`
+        // is NULL when the session follows its project.
+        sql: "
+            CREATE TABLE hourly_rates (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL
+                    REFERENCES users (id) ON DELETE CASCADE,
+                client_id INTEGER
+                    REFERENCES clients (id) ON DELETE CASCADE,
+                project_id INTEGER
+                    REFERENCES projects (id) ON DELETE CASCADE,
+                amount_cents INTEGER NOT NULL CHECK (amount_cents >= 0),
+                currency TEXT NOT NULL,
+                effective_from TEXT NOT NULL,
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                CHECK (client_id IS NULL OR project_id IS NULL)
+            );
+
+            CREATE UNIQUE INDEX idx_hourly_rates_scope
+                ON hourly_rates (
+                    user_id,
+                    COALESCE(client_id, 0),
+                    COALESCE(project_id, 0),
+                    effective_from
+                );
+
+            CREATE INDEX idx_hourly_rates_client
+                ON hourly_rates (client_id);
+
+            CREATE INDEX idx_hourly_rates_project
+                ON hourly_rates (project_id);
+
+            ALTER TABLE projects ADD COLUMN billable INTEGER NOT NULL DEFAULT 1;
+
+            ALTER TABLE sessions ADD COLUMN billable INTEGER;
+        ",
+    },
+    Migration {
+        version: 15,
+        name: "invoices",
+        // Invoices keep a copy of the client name and of every line, so
+        // they read the same after sessions, projects or rates change.
+        // `sequence` numbers each user's invoices without gaps.
+        sql: "
+            CREATE TABLE invoices (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL
+                    REFERENCES users (id) ON DELETE CASCADE,
+                client_id INTEGER
+                    REFERENCES clients (id) ON DELETE SET NULL,
+                sequence INTEGER NOT NULL,
+                number TEXT NOT NULL,
+                client_name TEXT NOT NULL,
+                period_from TEXT NOT NULL,
+                period_to TEXT NOT NULL,
+                currency TEXT NOT NULL,
+                total_cents INTEGER NOT NULL,
+                notes TEXT,
+                issued_at TEXT NOT NULL,
+                voided_at TEXT,
+                html_path TEXT,
+                pdf_path TEXT,
+                UNIQUE (user_id, sequence)
+            );
+
+            CREATE TABLE invoice_lines (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                invoice_id INTEGER NOT NULL
+                    REFERENCES invoices (id) ON DELETE CASCADE,
+                position INTEGER NOT NULL,
+                description TEXT NOT NULL,
+                session_count INTEGER NOT NULL,
+                seconds INTEGER NOT NULL,
+                rate_cents INTEGER NOT NULL,
+                amount_cents INTEGER NOT NULL
+            );
+
+            CREATE INDEX idx_invoice_lines_invoice
+                ON invoice_lines (invoice_id);
+
+            ALTER TABLE sessions ADD COLUMN invoice_id INTEGER
+                REFERENCES invoices (id) ON DELETE SET NULL;
+
+            CREATE INDEX idx_sessions_invoice
+                ON sessions (invoice_id);
+        ",
+    },
+    Migration {
+        version: 16,
+        name: "login_tokens_out_of_db",
+        // Raw tokens now live in the separate `login_tokens.db`. The ones
+        // stored here so far are in every earlier copy of this file, so
+        // they're revoked rather than moved: remembered accounts sign in
+        // once more.
+        sql: "
+            UPDATE login_sessions
+            SET revoked_at = strftime('%Y-%m-%dT%H:%M:%S+00:00', 'now')
+            WHERE revoked_at IS NULL
+              AND id IN (SELECT login_session_id FROM app_session);
+
+            DELETE FROM app_session;
+
+            ALTER TABLE app_session DROP COLUMN token;
+        ",
+    },
+];
+
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+/* ===========================
+   RUN
+=========================== */
+
+/// Brings the schema up to `latest_version()`.
+///
+/// Refuses to touch a database written by a newer build, and snapshots
+/// an existing database into `backup_dir` before applying anything.
+pub fn run(
+    conn: &mut Connection,
+    backup_dir: &Path,
+) -> AppResult<()> {
+
+    let current = current_version(conn)?;
+    let latest = latest_version();
+
+    if current > latest {
+        return Err(AppError::Internal(format!(
+            "Database schema version {} is newer than this build supports ({}). Please update Trackly.",
+            current, latest
+        )));
+    }
+
+    let pending: Vec<&Migration> = MIGRATIONS
+        .iter()
+        .filter(|m| m.version > current)
+        .collect();
+
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    if has_user_tables(conn)? {
+        backup(conn, backup_dir, current)?;
+    }
+
+    for migration in pending {
+        apply(conn, migration)?;
+    }
+
+    Ok(())
+}
+
+/* ===========================
+   HELPERS
+=========================== */
+
+fn current_version(conn: &Connection) -> AppResult<i64> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(AppError::from)
+}
+
+fn has_user_tables(conn: &Connection) -> AppResult<bool> {
+    conn.query_row(
+        "SELECT EXISTS (
+             SELECT 1 FROM sqlite_master
+             WHERE type = 'table' AND name NOT LIKE 'sqlite_%'
+         )",
+        [],
+        |row| row.get(0),
+    )
+    .map_err(AppError::from)
+}
+
+fn backup(
+    conn: &Connection,
+    backup_dir: &Path,
+    version: i64,
+) -> AppResult<()> {
+
+    std::fs::create_dir_all(backup_dir)?;
+
+    let filename = format!(
+        "trackly-v{}-{}.db",
+        version,
+        Utc::now().format("%Y%m%d%H%M%S")
+    );
+
+    let target = backup_dir.join(filename);
+
+    // VACUUM INTO produces a consistent copy even with pending WAL frames.
+    conn.execute(
+        "VACUUM INTO ?1",
+        params![target.to_string_lossy()],
+    )
+    .map_err(|e| AppError::Internal(format!("pre-migration backup failed: {}", e)))?;
+
+    Ok(())
+}
+
+fn apply(
+    conn: &mut Connection,
+    migration: &Migration,
+) -> AppResult<()> {
+
+    let tx = conn.transaction()?;
+
+    tx.execute_batch(migration.sql)
+        .map_err(|e| AppError::Internal(format!(
+            "migration {} ({}) failed: {}",
+            migration.version, migration.name, e
+        )))?;
+
+    // PRAGMA doesn't accept bound parameters; version is a trusted constant.
+    tx.execute_batch(&format!("PRAGMA user_version = {}", migration.version))?;
+
+    tx.commit()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    fn scratch_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("trackly-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn versions_strictly_increase() {
+        assert!(MIGRATIONS.windows(2).all(|pair| pair[0].version < pair[1].version));
+        assert_eq!(MIGRATIONS[0].version, 1);
+    }
+
+    #[test]
+    fn migrates_a_baseline_database_to_latest() {
+        let mut conn = Connection::open_in_memory().unwrap();
+
+        // Installs from before migrations existed: baseline tables at
+        // user_version 0.
+        conn.execute_batch(MIGRATIONS[0].sql).unwrap();
+        conn.execute_batch(
+            "INSERT INTO users (id, email, password_hash) VALUES (1, 'a@example.com', 'x');
+             INSERT INTO projects (id, user_id, name) VALUES (1, 1, 'Web');
+             INSERT INTO sessions (id, user_id, project_id, start_time, end_time, description, status)
+             VALUES (1, 1, 1, '2026-01-05T08:00:00+00:00', '2026-01-05T09:00:00+00:00', 'Landing page', 'finished'),
+                    (2, 1, NULL, '2026-01-06T08:00:00+00:00', NULL, NULL, 'running');
+             INSERT INTO session_tags (session_id, tag) VALUES (1, 'design');",
+        )
+        .unwrap();
+
+        let backups = scratch_dir("migrate");
+
+        conn.execute_batch("PRAGMA foreign_keys = OFF;").unwrap();
+        run(&mut conn, &backups).unwrap();
+
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+        assert!(latest_version() >= 16);
+
+        // Data survives every table rebuild.
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM sessions"), 2);
+        assert_eq!(count(&conn, "SELECT project_id FROM sessions WHERE id = 1"), 1);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM session_tags WHERE session_id = 1"), 1);
+        assert_eq!(
+            count(&conn, "SELECT rowid FROM session_search WHERE session_search MATCH 'landing'"),
+            1
+        );
+
+        // New columns get their defaults.
+        assert_eq!(count(&conn, "SELECT billable FROM projects WHERE id = 1"), 1);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM sessions WHERE invoice_id IS NOT NULL"), 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM pragma_foreign_key_check"), 0);
+
+        // The existing database was copied before anything ran.
+        assert_eq!(std::fs::read_dir(&backups).unwrap().count(), 1);
+
+        // Running again is a no-op.
+        run(&mut conn, &backups).unwrap();
+        assert_eq!(std::fs::read_dir(&backups).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(&backups).ok();
+    }
+
+    #[test]
+    fn refuses_a_newer_schema() {
+        let mut conn = Connection::open_in_memory().unwrap();
+
+        conn.execute_batch(&format!("PRAGMA user_version = {}", latest_version() + 1))
+            .unwrap();
+
+        assert!(run(&mut conn, &scratch_dir("newer")).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde_json::{json, Value};
+use std::fmt;
+
+use crate::models::password::PasswordViolation;
+
+/* ===========================
+   APP ERROR
+=========================== */
+
+/// Error returned by every service and command.
+///
+/// Commands serialize it as `{ code, message, details }` so the frontend
+/// can branch on `code` instead of matching message text.
+#[derive(Debug)]
+pub enum AppError {
+    NotAuthenticated,
+    Locked,
+    RateLimited {
+        retry_after_seconds: u64,
+    },
+    NotFound(&'static str),
+    Validation {
+        field: Option<&'static str>,
+        message: String,
+    },
+    /// A new password that breaks the password policy. Reported as a
+    /// validation error whose details list every violation.
+    PasswordPolicy {
+        field: &'static str,
+        violations: Vec<PasswordViolation>,
+    },
+    Conflict(String),
+    Database(rusqlite::Error),
+    Io(std::io::Error),
+    Internal(String),
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    /// Invalid value for a specific input field.
+    pub fn field(field: &'static str, message: impl Into<String>) -> Self {
+        Self::Validation {
+            field: Some(field),
+            message: message.into(),
+        }
+    }
+
+    /// Invalid input that isn't tied to a single field.
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Self::Validation {
+            field: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotAuthenticated => "not_authenticated",
+            Self::Locked => "locked",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::NotFound(_) => "not_found",
+            Self::Validation { .. } | Self::PasswordPolicy { .. } => "validation",
+            Self::Conflict(_) => "conflict",
+            Self::Database(_) => "database",
+            Self::Io(_) => "io",
+            Self::Internal(_) => "internal",
+        }
+    }
+
+    fn details(&self) -> Value {
+        match self {
+            Self::RateLimited { retry_after_seconds } => {
+                json!({ "retry_after_seconds": retry_after_seconds })
+            }
+            Self::NotFound(entity) => json!({ "entity": entity }),
+            Self::Validation { field, .. } => json!({ "field": field }),
+            Self::PasswordPolicy { field, violations } => {
+                json!({ "field": field, "violations": violations })
+            }
+            _ => Value::Null,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAuthenticated => write!(f, "Not authenticated"),
+            Self::Locked => write!(f, "App is locked"),
+            Self::RateLimited { retry_after_seconds } => write!(
+                f,
+                "Too many attempts. Try again in {} seconds",
+                retry_after_seconds
+            ),
+            Self::NotFound(entity) => write!(f, "{} not found", entity),
+            Self::Validation { message, .. } => write!(f, "{}", message),
+            Self::PasswordPolicy { violations, .. } => match violations.first() {
+                Some(first) => write!(f, "{}", first.message()),
+                None => write!(f, "Password does not meet the policy"),
+            },
+            Self::Conflict(message) => write!(f, "{}", message),
+            Self::Database(e) => write!(f, "Database error: {}", e),
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::Internal(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Database(e) => Some(e),
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
+    }
+}
+
+/* ===========================
+   CONVERSIONS
+=========================== */
+
+impl From<rusqlite::Error> for AppError {
+    fn from(e: rusqlite::Error) -> Self {
+        Self::Database(e)
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// CSV failures while writing are I/O; readers map parse errors to
+/// `Validation` themselves.
+impl From<csv::Error> for AppError {
+    fn from(e: csv::Error) -> Self {
+        Self::Io(e.into())
+    }
+}
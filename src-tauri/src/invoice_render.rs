@@ -0,0 +1,437 @@
+use chrono::{DateTime, Duration};
+use chrono_tz::Tz;
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+
+use crate::models::export::DurationFormat;
+use crate::models::invoice::Invoice;
+use crate::services::export_service::{format_duration, format_time};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/* ===========================
+   HTML
+=========================== */
+
+/// A self-contained page, styled for printing.
+pub fn html(invoice: &Invoice, tz: &Tz) -> String {
+    let draft = &invoice.draft;
+
+    let rows: String = draft
+        .lines
+        .iter()
+        .map(|line| {
+            format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td>\
+                 <td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                escape(&line.description),
+                line.session_count,
+                format_duration(line.seconds, DurationFormat::DecimalHours),
+                escape(&money(line.rate_cents, &draft.currency)),
+                escape(&money(line.amount_cents, &draft.currency)),
+            )
+        })
+        .collect();
+
+    let notes = match &invoice.notes {
+        Some(notes) => format!(
+            "<section class=\"notes\"><h2>Notes</h2><p>{}</p></section>\n",
+            escape(notes).replace('\n', "<br>")
+        ),
+        None => String::new(),
+    };
+
+    format!(
+        "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<title>Invoice {number}</title>
+<style>
+body {{ font-family: Helvetica, Arial, sans-serif; color: #212529; max-width: 800px; margin: 40px auto; padding: 0 24px; }}
+header {{ display: flex; justify-content: space-between; align-items: baseline; }}
+h1 {{ margin: 0; font-size: 28px; }}
+h2 {{ font-size: 14px; margin-bottom: 4px; }}
+dl {{ display: grid; grid-template-columns: max-content auto; gap: 4px 16px; margin: 24px 0; }}
+dt {{ color: #6c757d; }}
+dd {{ margin: 0; }}
+table {{ width: 100%; border-collapse: collapse; }}
+th, td {{ padding: 6px 8px; text-align: left; border-bottom: 1px solid #dee2e6; }}
+.num {{ text-align: right; white-space: nowrap; }}
+tfoot th, tfoot td {{ font-weight: bold; border-bottom: none; border-top: 2px solid #212529; }}
+.notes p {{ margin-top: 0; }}
+</style>
+</head>
+<body>
+<header><h1>Invoice</h1><strong>{number}</strong></header>
+<dl>
+<dt>Issued</dt><dd>{issued}</dd>
+<dt>Period</dt><dd>{from} &ndash; {to}</dd>
+<dt>Bill to</dt><dd>{client}</dd>
+</dl>
+<table>
+<thead><tr><th>Description</th><th class=\"num\">Sessions</th><th class=\"num\">Hours</th><th class=\"num\">Rate</th><th class=\"num\">Amount</th></tr></thead>
+<tbody>
+{rows}</tbody>
+<tfoot><tr><th colspan=\"4\">Total</th><td class=\"num\">{total}</td></tr></tfoot>
+</table>
+{notes}</body>
+</html>
+",
+        number = escape(&invoice.number),
+        issued = format_time(&invoice.issued_at, tz, DATE_FORMAT),
+        from = format_time(&draft.period_from, tz, DATE_FORMAT),
+        to = last_day(&draft.period_to, tz),
+        client = escape(&draft.client_name),
+        rows = rows,
+        total = escape(&money(draft.total_cents, &draft.currency)),
+        notes = notes,
+    )
+}
+
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
+
+/* ===========================
+   PDF
+=========================== */
+
+// A4 in points.
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const RIGHT: f32 = PAGE_WIDTH - MARGIN;
+const LINE_HEIGHT: f32 = 16.0;
+
+// Right edges of the numeric columns.
+const SESSIONS_RIGHT: f32 = 330.0;
+const HOURS_RIGHT: f32 = 390.0;
+const RATE_RIGHT: f32 = 465.0;
+const DESCRIPTION_WIDTH: f32 = 210.0;
+
+const REGULAR: Name = Name(b"F1");
+const BOLD: Name = Name(b"F2");
+
+/// A plain A4 document using the standard Helvetica fonts, so nothing
+/// has to be embedded. Text outside WinAnsi shows as `?`.
+pub fn pdf(invoice: &Invoice, tz: &Tz) -> Vec<u8> {
+    let draft = &invoice.draft;
+    let mut doc = PdfDocument::new();
+
+    doc.text(MARGIN, 22.0, true, "Invoice");
+    doc.text_right(RIGHT, 14.0, true, &invoice.number);
+    doc.advance(36.0);
+
+    let details = [
+        ("Issued", format_time(&invoice.issued_at, tz, DATE_FORMAT)),
+        (
+            "Period",
+            format!(
+                "{} \u{2013} {}",
+                format_time(&draft.period_from, tz, DATE_FORMAT),
+                last_day(&draft.period_to, tz)
+            ),
+        ),
+        ("Bill to", draft.client_name.clone()),
+    ];
+
+    for (label, value) in &details {
+        doc.text(MARGIN, 10.0, false, label);
+        doc.text(MARGIN + 70.0, 10.0, false, value);
+        doc.advance(LINE_HEIGHT);
+    }
+
+    doc.advance(LINE_HEIGHT);
+    doc.table_header();
+
+    for line in &draft.lines {
+        if doc.ensure(LINE_HEIGHT) {
+            doc.table_header();
+        }
+
+        doc.text(MARGIN, 10.0, false, &truncate(&line.description, 10.0, DESCRIPTION_WIDTH));
+        doc.text_right(SESSIONS_RIGHT, 10.0, false, &line.session_count.to_string());
+        doc.text_right(
+            HOURS_RIGHT,
+            10.0,
+            false,
+            &format_duration(line.seconds, DurationFormat::DecimalHours),
+        );
+        doc.text_right(RATE_RIGHT, 10.0, false, &money(line.rate_cents, &draft.currency));
+        doc.text_right(RIGHT, 10.0, false, &money(line.amount_cents, &draft.currency));
+        doc.advance(LINE_HEIGHT);
+    }
+
+    doc.ensure(LINE_HEIGHT * 2.0);
+    doc.rule(1.0);
+    doc.advance(LINE_HEIGHT);
+    doc.text(MARGIN, 11.0, true, "Total");
+    doc.text_right(RIGHT, 11.0, true, &money(draft.total_cents, &draft.currency));
+    doc.advance(LINE_HEIGHT);
+
+    if let Some(notes) = &invoice.notes {
+        doc.advance(LINE_HEIGHT);
+        doc.ensure(LINE_HEIGHT * 2.0);
+        doc.text(MARGIN, 10.0, true, "Notes");
+        doc.advance(LINE_HEIGHT);
+
+        for line in wrap(notes, 10.0, RIGHT - MARGIN) {
+            doc.ensure(LINE_HEIGHT);
+            doc.text(MARGIN, 10.0, false, &line);
+            doc.advance(LINE_HEIGHT);
+        }
+    }
+
+    doc.finish(&format!("Invoice {}", invoice.number))
+}
+
+/// Pages laid out top to bottom with a moving baseline.
+struct PdfDocument {
+    pages: Vec<Content>,
+    y: f32,
+}
+
+impl PdfDocument {
+    fn new() -> Self {
+        let mut doc = Self { pages: Vec::new(), y: 0.0 };
+        doc.new_page();
+        doc
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(Content::new());
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    /// Starts a new page unless `height` still fits. Returns whether it did.
+    fn ensure(&mut self, height: f32) -> bool {
+        if self.y - height < MARGIN {
+            self.new_page();
+            return true;
+        }
+
+        false
+    }
+
+    fn advance(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn page(&mut self) -> &mut Content {
+        self.pages.last_mut().expect("document has a page")
+    }
+
+    fn text(&mut self, x: f32, size: f32, bold: bool, value: &str) {
+        let y = self.y;
+        let font = if bold { BOLD } else { REGULAR };
+        let bytes = win_ansi(value);
+
+        self.page()
+            .begin_text()
+            .set_font(font, size)
+            .next_line(x, y)
+            .show(Str(&bytes))
+            .end_text();
+    }
+
+    fn text_right(&mut self, right: f32, size: f32, bold: bool, value: &str) {
+        self.text(right - text_width(value, size, bold), size, bold, value);
+    }
+
+    fn rule(&mut self, width: f32) {
+        let y = self.y + LINE_HEIGHT - 4.0;
+
+        self.page()
+            .set_line_width(width)
+            .move_to(MARGIN, y)
+            .line_to(RIGHT, y)
+            .stroke();
+    }
+
+    fn table_header(&mut self) {
+        self.text(MARGIN, 10.0, true, "Description");
+        self.text_right(SESSIONS_RIGHT, 10.0, true, "Sessions");
+        self.text_right(HOURS_RIGHT, 10.0, true, "Hours");
+        self.text_right(RATE_RIGHT, 10.0, true, "Rate");
+        self.text_right(RIGHT, 10.0, true, "Amount");
+        self.advance(LINE_HEIGHT);
+        self.rule(0.5);
+    }
+
+    fn finish(self, title: &str) -> Vec<u8> {
+        let catalog_id = Ref::new(1);
+        let tree_id = Ref::new(2);
+        let regular_id = Ref::new(3);
+        let bold_id = Ref::new(4);
+        let info_id = Ref::new(5);
+
+        // Each page takes two ids: the page and its content stream.
+        let page_ids: Vec<(Ref, Ref)> = (0..self.pages.len() as i32)
+            .map(|i| (Ref::new(6 + i * 2), Ref::new(7 + i * 2)))
+            .collect();
+
+        let mut pdf = Pdf::new();
+
+        pdf.catalog(catalog_id).pages(tree_id);
+        pdf.pages(tree_id)
+            .kids(page_ids.iter().map(|(page, _)| *page))
+            .count(page_ids.len() as i32);
+
+        for (id, font) in [(regular_id, "Helvetica"), (bold_id, "Helvetica-Bold")] {
+            pdf.type1_font(id)
+                .base_font(Name(font.as_bytes()))
+                .encoding_predefined(Name(b"WinAnsiEncoding"));
+        }
+
+        for ((page_id, content_id), content) in page_ids.iter().zip(self.pages) {
+            let mut page = pdf.page(*page_id);
+            page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT));
+            page.parent(tree_id);
+            page.contents(*content_id);
+
+            page.resources()
+                .fonts()
+                .pair(REGULAR, regular_id)
+                .pair(BOLD, bold_id);
+
+            page.finish();
+
+            pdf.stream(*content_id, &content.finish());
+        }
+
+        pdf.document_info(info_id)
+            .title(TextStr(title))
+            .producer(TextStr("Trackly"));
+
+        pdf.finish()
+    }
+}
+
+/// Maps to Windows-1252, the encoding of the standard fonts.
+fn win_ansi(value: &str) -> Vec<u8> {
+    value
+        .chars()
+        .map(|c| match c {
+            ' '..='~' | '\u{A0}'..='\u{FF}' => c as u8,
+            '\u{20AC}' => 0x80,
+            '\u{2026}' => 0x85,
+            '\u{2018}' => 0x91,
+            '\u{2019}' => 0x92,
+            '\u{201C}' => 0x93,
+            '\u{201D}' => 0x94,
+            '\u{2022}' => 0x95,
+            '\u{2013}' => 0x96,
+            '\u{2014}' => 0x97,
+            _ => b'?',
+        })
+        .collect()
+}
+
+/// Approximate Helvetica advance widths, good enough to right-align
+/// numbers and keep descriptions inside their column.
+fn text_width(value: &str, size: f32, bold: bool) -> f32 {
+    let units: u32 = value
+        .chars()
+        .map(|c| match c {
+            '0'..='9' | '$' => 556,
+            ' ' | ',' | '.' | ':' | ';' | '!' | '/' | 'i' | 'j' | 'l' | '\'' => 278,
+            'f' | 't' | 'r' | '(' | ')' | '-' => 333,
+            'm' | 'w' | 'M' | 'W' => 833,
+            'A'..='Z' => 667,
+            _ => 556,
+        })
+        .sum();
+
+    let width = units as f32 * size / 1000.0;
+
+    if bold { width * 1.05 } else { width }
+}
+
+fn truncate(value: &str, size: f32, max_width: f32) -> String {
+    if text_width(value, size, false) <= max_width {
+        return value.to_string();
+    }
+
+    let mut out = String::new();
+
+    for c in value.chars() {
+        out.push(c);
+
+        if text_width(&out, size, false) + text_width("\u{2026}", size, false) > max_width {
+            out.pop();
+            break;
+        }
+    }
+
+    out.push('\u{2026}');
+    out
+}
+
+/// Greedy word wrap that keeps the author's line breaks.
+fn wrap(value: &str, size: f32, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for paragraph in value.lines() {
+        let mut line = String::new();
+
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", line, word)
+            };
+
+            if !line.is_empty() && text_width(&candidate, size, false) > max_width {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            } else {
+                line = candidate;
+            }
+        }
+
+        lines.push(line);
+    }
+
+    lines
+}
+
+/* ===========================
+   FORMATTING
+=========================== */
+
+/// `1,234.50 EUR`
+fn money(cents: i64, currency: &str) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let units = (cents.abs() / 100).to_string();
+
+    let mut groups: Vec<&str> = units
+        .as_bytes()
+        .rchunks(3)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+        .collect();
+    groups.reverse();
+
+    let grouped = groups.join(",");
+
+    format!("{}{}.{:02} {}", sign, grouped, cents.abs() % 100, currency)
+}
+
+/// The period's end is exclusive; print the last day it covers.
+fn last_day(period_to: &str, tz: &Tz) -> String {
+    match DateTime::parse_from_rfc3339(period_to) {
+        Ok(t) => (t - Duration::seconds(1)).with_timezone(tz).format(DATE_FORMAT).to_string(),
+        Err(_) => period_to.to_string(),
+    }
+}
@@ -0,0 +1,205 @@
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+
+/* ===========================
+   MAIL TRANSPORT
+=========================== */
+
+pub struct OutgoingMail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Delivers outgoing mail. Implementations must be thread-safe since the
+/// mailer is shared as Tauri state.
+pub trait MailTransport: Send + Sync {
+    fn send(&self, mail: &OutgoingMail) -> AppResult<()>;
+}
+
+/// Managed state wrapping the transport chosen at startup.
+pub struct Mailer {
+    transport: Box<dyn MailTransport>,
+}
+
+impl Mailer {
+    pub fn new(transport: impl MailTransport + 'static) -> Self {
+        Self {
+            transport: Box::new(transport),
+        }
+    }
+
+    /// Uses SMTP when `TRACKLY_SMTP_HOST` is set. Otherwise mail goes to
+    /// stdout if `TRACKLY_MAIL_STDOUT` is set, or, in debug builds only,
+    /// to `.eml` files in `outbox_dir`. Release builds without either
+    /// refuse to send.
+    pub fn from_env(outbox_dir: &Path) -> AppResult<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+
+        if let Some(host) = var("TRACKLY_SMTP_HOST") {
+            let port = match var("TRACKLY_SMTP_PORT") {
+                Some(port) => Some(port.parse().map_err(|_| {
+                    AppError::Internal(format!("Invalid TRACKLY_SMTP_PORT: {}", port))
+                })?),
+                None => None,
+            };
+
+            let credentials = var("TRACKLY_SMTP_USERNAME")
+                .map(|user| (user, var("TRACKLY_SMTP_PASSWORD").unwrap_or_default()));
+
+            let from = var("TRACKLY_MAIL_FROM")
+                .unwrap_or_else(|| "Trackly <no-reply@trackly.local>".into());
+
+            return Ok(Self::new(SmtpMailTransport::new(&host, port, credentials, &from)?));
+        }
+
+        if var("TRACKLY_MAIL_STDOUT").is_some() {
+            return Ok(Self::new(FileMailTransport::stdout()));
+        }
+
+        if cfg!(debug_assertions) {
+            log::info!("SMTP not configured, writing mail to {}", outbox_dir.display());
+            return Ok(Self::new(FileMailTransport::directory(outbox_dir)));
+        }
+
+        log::warn!("SMTP not configured (TRACKLY_SMTP_HOST unset), emails will not be sent");
+
+        Ok(Self::new(UnconfiguredMailTransport))
+    }
+
+    pub fn send(&self, mail: &OutgoingMail) -> AppResult<()> {
+        self.transport.send(mail)
+    }
+}
+
+/* ===========================
+   SMTP
+=========================== */
+
+pub struct SmtpMailTransport {
+    transport: SmtpTransport,
+    from: Mailbox,
+}
+
+impl SmtpMailTransport {
+    /// Connects with implicit TLS on port 465 and STARTTLS otherwise
+    /// (587 by default).
+    pub fn new(
+        host: &str,
+        port: Option<u16>,
+        credentials: Option<(String, String)>,
+        from: &str,
+    ) -> AppResult<Self> {
+
+        let mut builder = match port {
+            Some(465) => SmtpTransport::relay(host),
+            _ => SmtpTransport::starttls_relay(host),
+        }
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        if let Some(port) = port {
+            builder = builder.port(port);
+        }
+
+        if let Some((user, password)) = credentials {
+            builder = builder.credentials(Credentials::new(user, password));
+        }
+
+        let from = from
+            .parse()
+            .map_err(|_| AppError::Internal(format!("Invalid sender address: {}", from)))?;
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+impl MailTransport for SmtpMailTransport {
+    fn send(&self, mail: &OutgoingMail) -> AppResult<()> {
+        let to: Mailbox = mail
+            .to
+            .parse()
+            .map_err(|_| AppError::field("email", "Invalid email address"))?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(&mail.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(mail.body.clone())
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        self.transport
+            .send(&message)
+            .map_err(|e| AppError::Internal(format!("Could not send email: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+/* ===========================
+   FILE / STDOUT
+=========================== */
+
+/// For local testing: writes each mail as a plain-text `.eml` file, or
+/// prints it when no directory is set.
+pub struct FileMailTransport {
+    dir: Option<PathBuf>,
+}
+
+impl FileMailTransport {
+    pub fn directory(dir: &Path) -> Self {
+        Self {
+            dir: Some(dir.to_path_buf()),
+        }
+    }
+
+    pub fn stdout() -> Self {
+        Self { dir: None }
+    }
+}
+
+impl MailTransport for FileMailTransport {
+    fn send(&self, mail: &OutgoingMail) -> AppResult<()> {
+        let content = format!(
+            "To: {}\nSubject: {}\n\n{}\n",
+            mail.to, mail.subject, mail.body
+        );
+
+        match &self.dir {
+            Some(dir) => {
+                std::fs::create_dir_all(dir)?;
+                std::fs::write(dir.join(format!("{}.eml", Uuid::new_v4())), content)?;
+            }
+            None => {
+                std::io::stdout().lock().write_all(content.as_bytes())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/* ===========================
+   UNCONFIGURED
+=========================== */
+
+/// Release builds without a configured transport: every send fails, so
+/// callers report it instead of mail silently piling up on disk.
+pub struct UnconfiguredMailTransport;
+
+impl MailTransport for UnconfiguredMailTransport {
+    fn send(&self, _mail: &OutgoingMail) -> AppResult<()> {
+        Err(AppError::Internal(
+            "Email delivery is not configured; set TRACKLY_SMTP_HOST".into(),
+        ))
+    }
+}
@@ -2,12 +2,14 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod db;
+mod error;
 mod models;
 mod services;
 mod commands;
 mod state;
+mod mail;
+mod invoice_render;
 
-use std::sync::Mutex;
 use state::AppState;
 
 /* ===========================
@@ -16,11 +18,46 @@ use state::AppState;
 
 use commands::auth::{
     register_user,
+    check_password_strength,
     login,
     get_current_user,
     update_user_profile,
     upload_avatar,
     logout_user_command,
+    delete_account,
+    list_accounts,
+    switch_account,
+    list_login_sessions,
+    revoke_login_session,
+    list_login_attempts,
+    generate_recovery_codes,
+    get_recovery_codes_remaining,
+    reset_password_with_recovery_code,
+    send_verification_email,
+    verify_email,
+};
+
+/* ===========================
+   APP LOCK COMMANDS
+=========================== */
+
+use commands::app_lock::{
+    get_app_lock_status,
+    enable_app_lock,
+    disable_app_lock,
+    lock_app,
+    unlock_app,
+};
+
+/* ===========================
+   CLIENT COMMANDS
+=========================== */
+
+use commands::clients::{
+    get_clients,
+    create_client,
+    update_client,
+    delete_client,
 };
 
 /* ===========================
@@ -32,6 +69,32 @@ use commands::projects::{
     create_project,
     update_project,
     delete_project,
+    archive_project,
+    unarchive_project,
+    set_project_parent,
+    set_project_client,
+};
+
+/* ===========================
+   RATE COMMANDS
+=========================== */
+
+use commands::rates::{
+    get_rates,
+    set_rate,
+    delete_rate,
+};
+
+/* ===========================
+   INVOICE COMMANDS
+=========================== */
+
+use commands::invoices::{
+    preview_invoice,
+    create_invoice,
+    get_invoices,
+    get_invoice,
+    void_invoice,
 };
 
 /* ===========================
@@ -42,41 +105,102 @@ use commands::sessions::{
     get_active_session,
     start_session,
     finalize_session,
+    pause_session,
+    resume_session,
     cancel_session,
     get_finished_sessions,
+    query_sessions,
+    search_sessions,
+    create_manual_session,
+    update_session,
+    set_session_billable,
+    delete_session,
+};
+
+/* ===========================
+   STATS COMMANDS
+=========================== */
+
+use commands::stats::{
+    get_total_duration,
+    get_duration_by_project,
+    get_duration_by_client,
+    get_duration_by_tag,
+    get_duration_by_period,
+    get_earnings_by_project,
+    get_earnings_by_period,
+};
+
+/* ===========================
+   EXPORT COMMANDS
+=========================== */
+
+use commands::export::{
+    export_sessions_csv,
+};
+
+/* ===========================
+   IMPORT COMMANDS
+=========================== */
+
+use commands::import::{
+    import_sessions,
+};
+
+/* ===========================
+   BACKUP COMMANDS
+=========================== */
+
+use commands::backup::{
+    export_account_backup,
+    import_account_backup,
 };
 use tauri::Manager;
 
 fn main() {
     tauri::Builder::default()
-        .manage(AppState {
-            current_user_id: Mutex::new(None),
-        })
+        .plugin(
+            tauri_plugin_log::Builder::default()
+                .level(log::LevelFilter::Info)
+                .build(),
+        )
+        .manage(AppState::default())
         .setup(|app| {
             // Inicializar base de datos en el directorio correcto del sistema
-            let database = crate::db::Database::new(&app.handle());
+            let database = crate::db::Database::new(&app.handle())?;
             app.manage(database);
 
+            // Mail goes to the outbox folder unless SMTP is configured.
+            let outbox_dir = app
+                .path()
+                .app_data_dir()
+                .map_err(|e| crate::error::AppError::Internal(e.to_string()))?
+                .join("outbox");
+            app.manage(crate::mail::Mailer::from_env(&outbox_dir)?);
+
+            app.manage(
+                crate::services::password_policy_service::PasswordPolicy::from_env()?,
+            );
+
             // Restaurar sesión si existe
             let state = app.state::<AppState>();
 
             let db = app.state::<crate::db::Database>();
             let conn = db.conn.lock().unwrap();
 
-            let result: Result<i64, _> = conn.query_row(
-                "SELECT user_id FROM app_session WHERE id = 1",
-                [],
-                |row| row.get(0),
-            );
+            match crate::services::account_service::restore_current(&conn) {
+                Ok(Some((user_id, expires_at))) => {
+                    state.sign_in(user_id, Some(expires_at));
 
-            match result {
-                Ok(user_id) => {
-                    let mut current = state.current_user_id.lock().unwrap();
-                    *current = Some(user_id);
-                    println!("SESSION RESTORED FOR USER {}", user_id);
+                    // Accounts with an app lock start locked.
+                    let idle_timeout =
+                        crate::services::app_lock_service::get_idle_timeout(&conn, user_id)?;
+                    state.configure_lock(idle_timeout, true);
+
+                    log::info!("Session restored for user {}", user_id);
                 }
-                Err(_) => {
-                    println!("NO PREVIOUS SESSION FOUND");
+                _ => {
+                    log::info!("No previous session found");
                 }
             }
 
@@ -85,24 +209,92 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             // AUTH
             register_user,
+            check_password_strength,
             login,
             get_current_user,
             update_user_profile,
             upload_avatar,
             logout_user_command,
+            delete_account,
+            list_accounts,
+            switch_account,
+            list_login_sessions,
+            revoke_login_session,
+            list_login_attempts,
+            generate_recovery_codes,
+            get_recovery_codes_remaining,
+            reset_password_with_recovery_code,
+            send_verification_email,
+            verify_email,
+
+            // APP LOCK
+            get_app_lock_status,
+            enable_app_lock,
+            disable_app_lock,
+            lock_app,
+            unlock_app,
+
+            // CLIENTS
+            get_clients,
+            create_client,
+            update_client,
+            delete_client,
 
             // PROJECTS
             get_projects,
             create_project,
             update_project,
             delete_project,
+            archive_project,
+            unarchive_project,
+            set_project_parent,
+            set_project_client,
+
+            // RATES
+            get_rates,
+            set_rate,
+            delete_rate,
+
+            // INVOICES
+            preview_invoice,
+            create_invoice,
+            get_invoices,
+            get_invoice,
+            void_invoice,
 
             // SESSIONS
             get_active_session,
             start_session,
             finalize_session,
+            pause_session,
+            resume_session,
             cancel_session,
             get_finished_sessions,
+            query_sessions,
+            search_sessions,
+            create_manual_session,
+            update_session,
+            set_session_billable,
+            delete_session,
+
+            // STATS
+            get_total_duration,
+            get_duration_by_project,
+            get_duration_by_client,
+            get_duration_by_tag,
+            get_duration_by_period,
+            get_earnings_by_project,
+            get_earnings_by_period,
+
+            // EXPORT
+            export_sessions_csv,
+
+            // IMPORT
+            import_sessions,
+
+            // BACKUP
+            export_account_backup,
+            import_account_backup,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri app");
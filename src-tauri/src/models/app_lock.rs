@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct AppLockStatus {
+    pub enabled: bool,
+    pub locked: bool,
+    /// `0` means the app only locks on startup, account switch or
+    /// an explicit lock.
+    pub idle_timeout_minutes: Option<u32>,
+}
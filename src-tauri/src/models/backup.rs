@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::rate::RateScope;
+
+/* ===========================
+   ARCHIVE
+=========================== */
+
+/// Bumped whenever the archive layout changes incompatibly.
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct AccountBackup {
+    pub format_version: u32,
+    /// `PRAGMA user_version` of the database that produced the archive.
+    pub schema_version: i64,
+    pub exported_at: String,
+    pub profile: BackupProfile,
+    pub avatar: Option<BackupAvatar>,
+    #[serde(default)]
+    pub clients: Vec<BackupClient>,
+    pub projects: Vec<BackupProject>,
+    pub sessions: Vec<BackupSession>,
+    /// Scopes refer to the archive's client and project ids.
+    #[serde(default)]
+    pub rates: Vec<BackupRate>,
+    #[serde(default)]
+    pub invoices: Vec<BackupInvoice>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BackupProfile {
+    pub email: String,
+    pub display_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BackupAvatar {
+    pub extension: String,
+    pub data_base64: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BackupClient {
+    pub id: i64,
+    pub name: String,
+    pub color: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BackupProject {
+    pub id: i64,
+    pub name: String,
+    pub color: String,
+    #[serde(default)]
+    pub archived_at: Option<String>,
+    #[serde(default)]
+    pub client_id: Option<i64>,
+    #[serde(default)]
+    pub parent_id: Option<i64>,
+    /// Missing in older archives, where everything was billable.
+    #[serde(default)]
+    pub billable: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BackupSession {
+    pub project_id: Option<i64>,
+    pub start_time: String,
+    pub end_time: Option<String>,
+    pub description: Option<String>,
+    pub status: String,
+    #[serde(default)]
+    pub billable: Option<bool>,
+    /// Archive id of the invoice the session was billed on.
+    #[serde(default)]
+    pub invoice_id: Option<i64>,
+    pub tags: Vec<String>,
+    pub pauses: Vec<BackupPause>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BackupPause {
+    pub start_time: String,
+    pub end_time: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BackupRate {
+    #[serde(flatten)]
+    pub scope: RateScope,
+    pub amount_cents: i64,
+    pub currency: String,
+    pub effective_from: String,
+}
+
+/// Issued invoices keep their number; the HTML and PDF files aren't
+/// part of the archive.
+#[derive(Serialize, Deserialize)]
+pub struct BackupInvoice {
+    pub id: i64,
+    pub client_id: Option<i64>,
+    pub sequence: i64,
+    pub number: String,
+    pub client_name: String,
+    pub period_from: String,
+    pub period_to: String,
+    pub currency: String,
+    pub total_cents: i64,
+    pub notes: Option<String>,
+    pub issued_at: String,
+    pub voided_at: Option<String>,
+    pub lines: Vec<BackupInvoiceLine>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BackupInvoiceLine {
+    pub description: String,
+    pub session_count: i64,
+    pub seconds: i64,
+    pub rate_cents: i64,
+    pub amount_cents: i64,
+}
+
+/* ===========================
+   RESTORE (API)
+=========================== */
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RestoreMode {
+    /// Keep existing data; match projects by name, and skip sessions
+    /// and invoices that already exist.
+    Merge,
+    /// Delete the account's projects, sessions and invoices first.
+    Replace,
+}
+
+#[derive(Serialize, Default)]
+pub struct RestoreSummary {
+    pub clients_created: usize,
+    pub clients_matched: usize,
+    pub projects_created: usize,
+    pub projects_matched: usize,
+    pub sessions_imported: usize,
+    pub sessions_skipped: usize,
+    /// Merged sessions left out because they overlap existing ones.
+    pub sessions_conflicting: Vec<RestoreConflict>,
+    pub rates_imported: usize,
+    pub invoices_imported: usize,
+    pub invoices_matched: usize,
+}
+
+#[derive(Serialize)]
+pub struct RestoreConflict {
+    pub start_time: String,
+    pub end_time: String,
+    /// The existing session it overlaps.
+    pub session_id: i64,
+}
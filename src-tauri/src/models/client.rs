@@ -0,0 +1,8 @@
+use serde::Serialize;
+
+#[derive(Serialize, Clone)]
+pub struct Client {
+    pub id: i64,
+    pub name: String,
+    pub color: String,
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+/* ===========================
+   CSV OPTIONS (API INPUT)
+=========================== */
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvColumn {
+    Project,
+    /// Client of the session's top-level project.
+    Client,
+    Start,
+    End,
+    Duration,
+    Description,
+    Tags,
+}
+
+impl CsvColumn {
+    pub fn header(&self) -> &'static str {
+        match self {
+            CsvColumn::Project => "Project",
+            CsvColumn::Client => "Client",
+            CsvColumn::Start => "Start",
+            CsvColumn::End => "End",
+            CsvColumn::Duration => "Duration",
+            CsvColumn::Description => "Description",
+            CsvColumn::Tags => "Tags",
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DurationFormat {
+    /// `1.50`
+    #[default]
+    DecimalHours,
+    /// `1:30`
+    HoursMinutes,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct CsvExportOptions {
+    /// Inclusive lower bound on `start_time` (RFC 3339).
+    pub from: Option<String>,
+    /// Exclusive upper bound on `start_time` (RFC 3339).
+    pub to: Option<String>,
+    /// Also matches the projects' sub-projects.
+    pub project_ids: Vec<i64>,
+    /// Sessions carrying any of these tags.
+    pub tags: Vec<String>,
+    pub columns: Vec<CsvColumn>,
+    pub tag_separator: String,
+    /// IANA name such as `Europe/Madrid`. Defaults to UTC.
+    pub timezone: Option<String>,
+    /// chrono `strftime` pattern.
+    pub date_format: String,
+    pub duration_format: DurationFormat,
+    /// Report sub-project sessions under their top-level project.
+    pub roll_up: bool,
+}
+
+impl Default for CsvExportOptions {
+    fn default() -> Self {
+        Self {
+            from: None,
+            to: None,
+            project_ids: Vec::new(),
+            tags: Vec::new(),
+            columns: vec![
+                CsvColumn::Project,
+                CsvColumn::Start,
+                CsvColumn::End,
+                CsvColumn::Duration,
+                CsvColumn::Description,
+                CsvColumn::Tags,
+            ],
+            tag_separator: ", ".into(),
+            timezone: None,
+            date_format: "%Y-%m-%d %H:%M".into(),
+            duration_format: DurationFormat::DecimalHours,
+            roll_up: false,
+        }
+    }
+}
+
+/* ===========================
+   RESPONSE MODELS (API)
+=========================== */
+
+#[derive(Serialize)]
+pub struct ExportSummary {
+    pub path: String,
+    pub rows: usize,
+}
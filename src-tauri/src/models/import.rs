@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+/* ===========================
+   IMPORT OPTIONS (API INPUT)
+=========================== */
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Csv,
+    Json,
+}
+
+/// Source column (CSV header or JSON key) for each session field.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ColumnMapping {
+    pub project: Option<String>,
+    pub start: String,
+    pub end: String,
+    pub description: Option<String>,
+    pub tags: Option<String>,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        Self {
+            project: Some("Project".into()),
+            start: "Start".into(),
+            end: "End".into(),
+            description: Some("Description".into()),
+            tags: Some("Tags".into()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ImportOptions {
+    pub format: ImportFormat,
+    pub mapping: ColumnMapping,
+    pub tag_separator: String,
+    /// IANA zone used for timestamps without an offset. Defaults to UTC.
+    pub timezone: Option<String>,
+    /// chrono `strftime` pattern for timestamps that aren't RFC 3339.
+    pub date_format: Option<String>,
+    pub create_missing_projects: bool,
+    pub allow_overlap: bool,
+    pub dry_run: bool,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            format: ImportFormat::Csv,
+            mapping: ColumnMapping::default(),
+            tag_separator: ",".into(),
+            timezone: None,
+            date_format: None,
+            create_missing_projects: true,
+            allow_overlap: false,
+            dry_run: true,
+        }
+    }
+}
+
+/* ===========================
+   RESPONSE MODELS (API)
+=========================== */
+
+#[derive(Serialize)]
+pub struct RowIssue {
+    /// 1-based index of the data record (the CSV header isn't counted).
+    pub row: usize,
+    pub message: String,
+}
+
+#[derive(Serialize, Default)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub total_rows: usize,
+    /// Rows that would be (or were) written.
+    pub valid_rows: usize,
+    pub imported: usize,
+    pub projects_to_create: Vec<String>,
+    /// Unparseable rows. Any error blocks the import.
+    pub errors: Vec<RowIssue>,
+    /// Rows overlapping existing sessions or each other. Blocking unless
+    /// `allow_overlap` is set.
+    pub overlaps: Vec<RowIssue>,
+    /// Exact repeats of an existing session or an earlier row; skipped.
+    pub duplicates: Vec<RowIssue>,
+}
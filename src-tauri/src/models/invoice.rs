@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+/* ===========================
+   REQUEST (API INPUT)
+=========================== */
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum InvoiceGrouping {
+    /// One line per project and rate.
+    #[default]
+    Project,
+    /// One line per tag and rate. A session with several tags is billed
+    /// once, under its alphabetically first tag.
+    Tag,
+}
+
+#[derive(Deserialize)]
+pub struct InvoiceRequest {
+    pub client_id: i64,
+    /// Inclusive lower bound on `start_time` (RFC 3339).
+    pub from: String,
+    /// Exclusive upper bound on `start_time` (RFC 3339).
+    pub to: String,
+    #[serde(default)]
+    pub group_by: InvoiceGrouping,
+    pub notes: Option<String>,
+    /// IANA name used for the dates printed on the invoice. Defaults to UTC.
+    pub timezone: Option<String>,
+}
+
+/* ===========================
+   RESPONSE MODELS (API)
+=========================== */
+
+#[derive(Serialize, Clone)]
+pub struct InvoiceLine {
+    pub description: String,
+    pub session_count: i64,
+    pub seconds: i64,
+    /// Per hour, in the currency's minor unit.
+    pub rate_cents: i64,
+    pub amount_cents: i64,
+}
+
+/// What an invoice for the request would contain.
+#[derive(Serialize)]
+pub struct InvoiceDraft {
+    pub client_id: Option<i64>,
+    pub client_name: String,
+    pub period_from: String,
+    pub period_to: String,
+    pub currency: String,
+    pub lines: Vec<InvoiceLine>,
+    pub total_cents: i64,
+    #[serde(skip)]
+    pub session_ids: Vec<i64>,
+}
+
+#[derive(Serialize)]
+pub struct Invoice {
+    pub id: i64,
+    /// Sequential per user, e.g. `INV-0007`.
+    pub number: String,
+    pub issued_at: String,
+    pub notes: Option<String>,
+    /// Voided invoices keep their number but no longer hold sessions.
+    pub voided_at: Option<String>,
+    pub html_path: Option<String>,
+    pub pdf_path: Option<String>,
+    #[serde(flatten)]
+    pub draft: InvoiceDraft,
+}
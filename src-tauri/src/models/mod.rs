@@ -1,3 +1,12 @@
 pub mod user;
+pub mod client;
 pub mod project;
-pub mod session;
\ No newline at end of file
+pub mod rate;
+pub mod session;
+pub mod stats;
+pub mod export;
+pub mod import;
+pub mod backup;
+pub mod app_lock;
+pub mod password;
+pub mod invoice;
@@ -0,0 +1,43 @@
+use serde::Serialize;
+
+/// One rule a candidate password breaks. Serialized with a `code` tag so
+/// the UI can render its own message for each rule.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum PasswordViolation {
+    TooShort { min_length: usize },
+    TooLong { max_length: usize },
+    TooFewCharacterClasses { required: usize, found: usize },
+    Common,
+    ContainsEmail,
+    TooWeak { score: u8, min_score: u8 },
+}
+
+impl PasswordViolation {
+    pub fn message(&self) -> String {
+        match self {
+            Self::TooShort { min_length } => {
+                format!("Password must be at least {} characters", min_length)
+            }
+            Self::TooLong { max_length } => {
+                format!("Password must be at most {} characters", max_length)
+            }
+            Self::TooFewCharacterClasses { required, .. } => format!(
+                "Password must mix at least {} of: lowercase, uppercase, digits, symbols",
+                required
+            ),
+            Self::Common => "Password is too common".into(),
+            Self::ContainsEmail => "Password must not contain your email".into(),
+            Self::TooWeak { .. } => "Password is too easy to guess".into(),
+        }
+    }
+}
+
+/// Strength estimate and policy result for a candidate password.
+/// `score` runs from 0 (trivial) to 4 (strong).
+#[derive(Serialize)]
+pub struct PasswordReport {
+    pub score: u8,
+    pub entropy_bits: f64,
+    pub violations: Vec<PasswordViolation>,
+}
@@ -1,4 +1,6 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+use crate::models::client::Client;
 
 #[derive(Debug)]
 pub struct DbProject {
@@ -7,13 +9,24 @@ pub struct DbProject {
     pub user_id: i64,
     pub name: String,
     pub color: String,
+    pub archived_at: Option<String>,
+    pub client_id: Option<i64>,
+    pub parent_id: Option<i64>,
+    pub billable: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct Project {
     pub id: i64,
     pub name: String,
     pub color: String,
+    pub archived_at: Option<String>,
+    /// Set on top-level projects only; sub-projects belong to their
+    /// parent's client.
+    pub client_id: Option<i64>,
+    pub parent_id: Option<i64>,
+    /// Default for the project's sessions.
+    pub billable: bool,
 }
 
 impl From<DbProject> for Project {
@@ -22,6 +35,48 @@ impl From<DbProject> for Project {
             id: p.id,
             name: p.name,
             color: p.color,
+            archived_at: p.archived_at,
+            client_id: p.client_id,
+            parent_id: p.parent_id,
+            billable: p.billable,
         }
     }
-}
\ No newline at end of file
+}
+
+/* ===========================
+   TREE (API)
+=========================== */
+
+#[derive(Serialize)]
+pub struct ProjectNode {
+    #[serde(flatten)]
+    pub project: Project,
+    pub children: Vec<Project>,
+}
+
+/// Top-level projects of one client, or of no client when `client` is
+/// `None`.
+#[derive(Serialize)]
+pub struct ProjectGroup {
+    pub client: Option<Client>,
+    pub projects: Vec<ProjectNode>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum ProjectListing {
+    Flat(Vec<Project>),
+    Tree(Vec<ProjectGroup>),
+}
+
+/// What a hard delete does with the project's sessions.
+#[derive(Deserialize, Clone, Copy)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum ProjectSessionsOnDelete {
+    /// Keep the sessions without a project.
+    Unassign,
+    /// Delete the sessions along with their tags and pauses.
+    Delete,
+    /// Move the sessions to another of the user's projects.
+    Reassign { project_id: i64 },
+}
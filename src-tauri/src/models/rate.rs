@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// What a rate applies to. For a session, a rate on its project beats one
+/// on the parent project, which beats the client's, which beats the
+/// account-wide rate.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(tag = "level", rename_all = "snake_case")]
+pub enum RateScope {
+    User,
+    Client { client_id: i64 },
+    Project { project_id: i64 },
+}
+
+impl RateScope {
+    /// `(client_id, project_id)` as stored.
+    pub fn columns(&self) -> (Option<i64>, Option<i64>) {
+        match *self {
+            RateScope::User => (None, None),
+            RateScope::Client { client_id } => (Some(client_id), None),
+            RateScope::Project { project_id } => (None, Some(project_id)),
+        }
+    }
+
+    pub fn from_columns(client_id: Option<i64>, project_id: Option<i64>) -> Self {
+        match (client_id, project_id) {
+            (_, Some(project_id)) => RateScope::Project { project_id },
+            (Some(client_id), None) => RateScope::Client { client_id },
+            (None, None) => RateScope::User,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct HourlyRate {
+    pub id: i64,
+    #[serde(flatten)]
+    pub scope: RateScope,
+    /// Per hour, in the currency's minor unit.
+    pub amount_cents: i64,
+    /// ISO 4217 code such as `EUR`.
+    pub currency: String,
+    /// Applies to sessions starting at or after this time (RFC 3339),
+    /// until the scope's next rate takes over.
+    pub effective_from: String,
+}
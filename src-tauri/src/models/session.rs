@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /* ===========================
    DB MODEL (INTERNO)
@@ -14,20 +14,42 @@ pub struct DbSession {
     pub end_time: Option<String>,
     pub description: Option<String>,
     pub status: String,
+    pub invoice_id: Option<i64>,
 }
 
 /* ===========================
    RESPONSE MODELS (API)
 =========================== */
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum SessionStatus {
     Running,
+    Paused,
     Finished,
     Cancelled,
 }
 
+impl SessionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionStatus::Running => "running",
+            SessionStatus::Paused => "paused",
+            SessionStatus::Finished => "finished",
+            SessionStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "paused" => SessionStatus::Paused,
+            "finished" => SessionStatus::Finished,
+            "cancelled" => SessionStatus::Cancelled,
+            _ => SessionStatus::Running,
+        }
+    }
+}
+
 #[derive(Serialize)]
 pub struct ActiveSessionResponse {
     pub id: i64,
@@ -36,6 +58,10 @@ pub struct ActiveSessionResponse {
     pub end_time: Option<String>,
     pub description: Option<String>,
     pub status: SessionStatus,
+    /// Wall-clock seconds from start to end (or now).
+    pub gross_seconds: i64,
+    /// Gross time minus pauses.
+    pub net_seconds: i64,
 }
 
 #[derive(Serialize)]
@@ -46,4 +72,82 @@ pub struct FinishedSessionResponse {
     pub end_time: String,
     pub description: Option<String>,
     pub tags: Vec<String>,
+    /// Wall-clock seconds from start to end (or now).
+    pub gross_seconds: i64,
+    /// Gross time minus pauses.
+    pub net_seconds: i64,
+}
+
+#[derive(Serialize)]
+pub struct SessionListItem {
+    pub id: i64,
+    pub project_id: Option<i64>,
+    pub start_time: String,
+    pub end_time: Option<String>,
+    pub description: Option<String>,
+    pub status: SessionStatus,
+    pub tags: Vec<String>,
+    pub gross_seconds: i64,
+    pub net_seconds: i64,
+    /// `None` when the session follows its project.
+    pub billable: Option<bool>,
+    /// Set once the session is billed; it can't be changed after that.
+    pub invoice_id: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct SessionPage {
+    pub items: Vec<SessionListItem>,
+    /// Pass back as `SessionQuery::cursor` to fetch the next page.
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct SearchHit {
+    pub session: SessionListItem,
+    /// Matched terms are wrapped in `\u{2}` … `\u{3}` so the UI can
+    /// highlight them without rendering user text as HTML.
+    pub description_snippet: String,
+    pub tags_snippet: String,
+    /// bm25 score; lower is a better match.
+    pub rank: f64,
+}
+
+/* ===========================
+   QUERY (API INPUT)
+=========================== */
+
+#[derive(Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TagMatch {
+    #[default]
+    Any,
+    All,
+}
+
+#[derive(Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct SessionQuery {
+    /// Inclusive lower bound on `start_time` (RFC 3339).
+    pub from: Option<String>,
+    /// Exclusive upper bound on `start_time` (RFC 3339).
+    pub to: Option<String>,
+    pub project_ids: Vec<i64>,
+    pub tags: Vec<String>,
+    pub tag_match: TagMatch,
+    /// Case-insensitive substring match on the description.
+    pub text: Option<String>,
+    /// Empty means every status.
+    pub statuses: Vec<SessionStatus>,
+    pub sort: SortOrder,
+    pub limit: Option<u32>,
+    pub cursor: Option<String>,
 }
\ No newline at end of file
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+/* ===========================
+   FILTER (API INPUT)
+=========================== */
+
+#[derive(Deserialize, Default)]
+pub struct StatsFilter {
+    /// Inclusive lower bound on `start_time` (RFC 3339).
+    pub from: Option<String>,
+    /// Exclusive upper bound on `start_time` (RFC 3339).
+    pub to: Option<String>,
+    /// Also matches the project's sub-projects.
+    pub project_id: Option<i64>,
+    /// Projects of the client, including their sub-projects.
+    #[serde(default)]
+    pub client_id: Option<i64>,
+    /// Offset applied before bucketing by period, so days and weeks
+    /// follow the user's local calendar.
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    Day,
+    Week,
+    Month,
+}
+
+/* ===========================
+   RESPONSE MODELS (API)
+=========================== */
+
+#[derive(Serialize)]
+pub struct DurationTotal {
+    pub total_seconds: i64,
+    pub session_count: i64,
+}
+
+#[derive(Serialize)]
+pub struct ProjectDuration {
+    pub project_id: Option<i64>,
+    pub project_name: Option<String>,
+    pub color: Option<String>,
+    pub seconds: i64,
+}
+
+#[derive(Serialize)]
+pub struct ClientDuration {
+    pub client_id: Option<i64>,
+    pub client_name: Option<String>,
+    pub color: Option<String>,
+    pub seconds: i64,
+}
+
+#[derive(Serialize)]
+pub struct TagDuration {
+    pub tag: String,
+    pub seconds: i64,
+}
+
+#[derive(Serialize)]
+pub struct PeriodDuration {
+    /// `YYYY-MM-DD` for days and weeks (the week's Monday), `YYYY-MM` for months.
+    pub period: String,
+    pub seconds: i64,
+}
+
+/// Billable time and earnings. Amounts in different currencies are never
+/// added up, so there is one row per currency; `currency` is `None` for
+/// billable time that no rate covers.
+#[derive(Serialize)]
+pub struct ProjectEarnings {
+    pub project_id: Option<i64>,
+    pub project_name: Option<String>,
+    pub color: Option<String>,
+    pub currency: Option<String>,
+    pub billable_seconds: i64,
+    pub amount_cents: i64,
+}
+
+#[derive(Serialize)]
+pub struct PeriodEarnings {
+    /// Same buckets as `PeriodDuration`.
+    pub period: String,
+    pub currency: Option<String>,
+    pub billable_seconds: i64,
+    pub amount_cents: i64,
+}
@@ -29,4 +29,61 @@ impl From<DbUser> for PublicUser {
             email_verified: user.email_verified == 1,
         }
     }
-}
\ No newline at end of file
+}
+/// A remembered login on this device, as listed by `list_accounts`.
+#[derive(Serialize)]
+pub struct AccountSummary {
+    pub user: PublicUser,
+    pub is_current: bool,
+    pub last_used_at: String,
+}
+
+#[derive(Serialize)]
+pub struct LoginSessionInfo {
+    pub id: i64,
+    pub created_at: String,
+    pub last_used_at: String,
+    pub expires_at: String,
+    /// The login this device is currently signed in with.
+    pub is_current: bool,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LoginOutcome {
+    Succeeded,
+    InvalidCredentials,
+    Throttled,
+}
+
+impl LoginOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoginOutcome::Succeeded => "succeeded",
+            LoginOutcome::InvalidCredentials => "invalid_credentials",
+            LoginOutcome::Throttled => "throttled",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "succeeded" => LoginOutcome::Succeeded,
+            "throttled" => LoginOutcome::Throttled,
+            _ => LoginOutcome::InvalidCredentials,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct LoginAttempt {
+    pub id: i64,
+    pub outcome: LoginOutcome,
+    pub attempted_at: String,
+}
+
+/// Returned once at registration: the recovery codes are never shown again.
+#[derive(Serialize)]
+pub struct RegisterResponse {
+    pub user: PublicUser,
+    pub recovery_codes: Vec<String>,
+}
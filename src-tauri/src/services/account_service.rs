@@ -0,0 +1,453 @@
+use rusqlite::{params, Connection};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+use crate::error::{AppError, AppResult};
+use crate::models::user::{AccountSummary, DbUser, LoginSessionInfo, PublicUser};
+use crate::services::user_service;
+
+/// Lifetime of a login without "remember me".
+const DEFAULT_LOGIN_HOURS: i64 = 12;
+const MAX_REMEMBER_DAYS: u32 = 365;
+
+/* ===========================
+   ISSUE / FORGET
+=========================== */
+
+/// Creates a login session for the user and makes the account current on
+/// this device. Returns when the login expires.
+///
+/// Only the SHA-256 of the token is stored in `login_sessions`. The raw
+/// token lives in the separate `secrets` store (see `db::attach_secrets`)
+/// and is re-checked on every restore or switch.
+pub fn issue_login(
+    conn: &Connection,
+    user_id: i64,
+    remember_days: Option<u32>,
+) -> AppResult<DateTime<Utc>> {
+
+    let now = Utc::now();
+
+    let expires_at = match remember_days {
+        Some(0) | None => now + Duration::hours(DEFAULT_LOGIN_HOURS),
+        Some(days) => now + Duration::days(days.min(MAX_REMEMBER_DAYS).into()),
+    };
+
+    let token = generate_token();
+
+    let tx = conn.unchecked_transaction()?;
+
+    // Replacing the device's token for this account ends the old login.
+    tx.execute(
+        "UPDATE login_sessions SET revoked_at = ?2
+         WHERE id IN (SELECT login_session_id FROM app_session WHERE user_id = ?1)
+           AND revoked_at IS NULL",
+        params![user_id, now.to_rfc3339()],
+    )?;
+
+    tx.execute(
+        "INSERT INTO login_sessions (user_id, token_hash, created_at, last_used_at, expires_at)
+         VALUES (?1, ?2, ?3, ?3, ?4)",
+        params![user_id, hash_token(&token), now.to_rfc3339(), expires_at.to_rfc3339()],
+    )?;
+
+    let login_session_id = tx.last_insert_rowid();
+
+    tx.execute("UPDATE app_session SET is_current = 0 WHERE is_current = 1", [])?;
+
+    tx.execute(
+        "INSERT INTO app_session (user_id, login_session_id, is_current)
+         VALUES (?1, ?2, 1)
+         ON CONFLICT(user_id) DO UPDATE
+         SET login_session_id = excluded.login_session_id,
+             is_current = 1",
+        params![user_id, login_session_id],
+    )?;
+
+    tx.execute(
+        "INSERT OR REPLACE INTO secrets.login_tokens (user_id, token)
+         VALUES (?1, ?2)",
+        params![user_id, token],
+    )?;
+
+    tx.commit()?;
+
+    Ok(expires_at)
+}
+
+/// Ends the account's login on this device and removes it from the
+/// remembered accounts.
+pub fn forget_account(
+    conn: &Connection,
+    user_id: i64,
+) -> AppResult<()> {
+
+    let tx = conn.unchecked_transaction()?;
+
+    tx.execute(
+        "UPDATE login_sessions SET revoked_at = ?2
+         WHERE id IN (SELECT login_session_id FROM app_session WHERE user_id = ?1)
+           AND revoked_at IS NULL",
+        params![user_id, Utc::now().to_rfc3339()],
+    )?;
+
+    tx.execute(
+        "DELETE FROM app_session WHERE user_id = ?1",
+        params![user_id],
+    )?;
+
+    tx.execute(
+        "DELETE FROM secrets.login_tokens WHERE user_id = ?1",
+        params![user_id],
+    )?;
+
+    tx.commit()?;
+
+    Ok(())
+}
+
+/* ===========================
+   RESTORE
+=========================== */
+
+/// Validates the current account's stored token. An expired or revoked
+/// token drops the account from this device and restores nothing.
+pub fn restore_current(
+    conn: &Connection,
+) -> AppResult<Option<(i64, DateTime<Utc>)>> {
+
+    let result = conn.query_row(
+        "SELECT user_id FROM app_session WHERE is_current = 1",
+        [],
+        |row| row.get(0),
+    );
+
+    let user_id = match result {
+        Ok(id) => id,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    match check_stored_login(conn, user_id)? {
+        Some(expires_at) => Ok(Some((user_id, expires_at))),
+        None => {
+            forget_account(conn, user_id)?;
+            Ok(None)
+        }
+    }
+}
+
+/* ===========================
+   LIST ACCOUNTS
+=========================== */
+
+pub fn list_accounts(conn: &Connection) -> AppResult<Vec<AccountSummary>> {
+    let mut stmt = conn.prepare(
+        "SELECT u.id, u.email, u.password_hash, u.display_name, u.avatar_url,
+                u.email_verified, a.is_current, l.last_used_at
+         FROM app_session a
+         JOIN users u ON u.id = a.user_id
+         JOIN login_sessions l ON l.id = a.login_session_id
+         WHERE l.revoked_at IS NULL
+           AND julianday(l.expires_at) > julianday('now')
+         ORDER BY a.is_current DESC, l.last_used_at DESC",
+    )?;
+
+    let rows = stmt.query_map([], |row| {
+        let user = DbUser {
+            id: row.get(0)?,
+            email: row.get(1)?,
+            password_hash: row.get(2)?,
+            display_name: row.get(3)?,
+            avatar_url: row.get(4)?,
+            email_verified: row.get(5)?,
+        };
+
+        Ok(AccountSummary {
+            user: user.into(),
+            is_current: row.get(6)?,
+            last_used_at: row.get(7)?,
+        })
+    })?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(AppError::from)
+}
+
+/* ===========================
+   SWITCH ACCOUNT
+=========================== */
+
+/// Only accounts with a valid login on this device can be switched to;
+/// anything else needs a regular login.
+pub fn switch_account(
+    conn: &Connection,
+    user_id: i64,
+) -> AppResult<(PublicUser, DateTime<Utc>)> {
+
+    let remembered: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM app_session WHERE user_id = ?1)",
+        params![user_id],
+        |row| row.get(0),
+    )?;
+
+    if !remembered {
+        return Err(AppError::NotFound("Account"));
+    }
+
+    let Some(expires_at) = check_stored_login(conn, user_id)? else {
+        forget_account(conn, user_id)?;
+        return Err(AppError::NotAuthenticated);
+    };
+
+    let tx = conn.unchecked_transaction()?;
+
+    tx.execute("UPDATE app_session SET is_current = 0 WHERE is_current = 1", [])?;
+
+    tx.execute(
+        "UPDATE app_session SET is_current = 1 WHERE user_id = ?1",
+        params![user_id],
+    )?;
+
+    tx.commit()?;
+
+    Ok((user_service::get_user_by_id(conn, user_id)?, expires_at))
+}
+
+/* ===========================
+   LOGIN SESSIONS
+=========================== */
+
+/// Active (unexpired, unrevoked) logins for the user, newest first.
+pub fn list_login_sessions(
+    conn: &Connection,
+    user_id: i64,
+) -> AppResult<Vec<LoginSessionInfo>> {
+
+    let mut stmt = conn.prepare(
+        "SELECT l.id, l.created_at, l.last_used_at, l.expires_at,
+                EXISTS (SELECT 1 FROM app_session a
+                        WHERE a.login_session_id = l.id AND a.is_current = 1)
+         FROM login_sessions l
+         WHERE l.user_id = ?1
+           AND l.revoked_at IS NULL
+           AND julianday(l.expires_at) > julianday('now')
+         ORDER BY l.created_at DESC",
+    )?;
+
+    let rows = stmt.query_map(params![user_id], |row| {
+        Ok(LoginSessionInfo {
+            id: row.get(0)?,
+            created_at: row.get(1)?,
+            last_used_at: row.get(2)?,
+            expires_at: row.get(3)?,
+            is_current: row.get(4)?,
+        })
+    })?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(AppError::from)
+}
+
+/// Revokes one of the user's logins. Returns `true` when it was the login
+/// this device is currently using, so the caller can sign out.
+pub fn revoke_login_session(
+    conn: &Connection,
+    user_id: i64,
+    login_session_id: i64,
+) -> AppResult<bool> {
+
+    let tx = conn.unchecked_transaction()?;
+
+    let updated = tx.execute(
+        "UPDATE login_sessions SET revoked_at = ?3
+         WHERE id = ?1 AND user_id = ?2 AND revoked_at IS NULL",
+        params![login_session_id, user_id, Utc::now().to_rfc3339()],
+    )?;
+
+    if updated == 0 {
+        return Err(AppError::NotFound("Login session"));
+    }
+
+    let was_current: bool = tx.query_row(
+        "SELECT EXISTS (SELECT 1 FROM app_session
+                        WHERE login_session_id = ?1 AND is_current = 1)",
+        params![login_session_id],
+        |row| row.get(0),
+    )?;
+
+    tx.execute(
+        "DELETE FROM secrets.login_tokens
+         WHERE user_id IN (SELECT user_id FROM app_session WHERE login_session_id = ?1)",
+        params![login_session_id],
+    )?;
+
+    tx.execute(
+        "DELETE FROM app_session WHERE login_session_id = ?1",
+        params![login_session_id],
+    )?;
+
+    tx.commit()?;
+
+    Ok(was_current)
+}
+
+/// Revokes every login the user has and drops the account from this
+/// device. Runs inside the caller's transaction.
+pub fn revoke_all_logins(
+    conn: &Connection,
+    user_id: i64,
+) -> AppResult<()> {
+
+    conn.execute(
+        "UPDATE login_sessions SET revoked_at = ?2
+         WHERE user_id = ?1 AND revoked_at IS NULL",
+        params![user_id, Utc::now().to_rfc3339()],
+    )?;
+
+    conn.execute(
+        "DELETE FROM app_session WHERE user_id = ?1",
+        params![user_id],
+    )?;
+
+    conn.execute(
+        "DELETE FROM secrets.login_tokens WHERE user_id = ?1",
+        params![user_id],
+    )?;
+
+    Ok(())
+}
+
+/* ===========================
+   HELPERS
+=========================== */
+
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Checks the token stored for `user_id` against `login_sessions` and
+/// touches `last_used_at`. Returns the expiry when the login is valid.
+fn check_stored_login(
+    conn: &Connection,
+    user_id: i64,
+) -> AppResult<Option<DateTime<Utc>>> {
+
+    let result = conn.query_row(
+        "SELECT token FROM secrets.login_tokens WHERE user_id = ?1",
+        params![user_id],
+        |row| row.get::<_, String>(0),
+    );
+
+    let token = match result {
+        Ok(token) => token,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let result = conn.query_row(
+        "SELECT id, expires_at, revoked_at FROM login_sessions
+         WHERE token_hash = ?1 AND user_id = ?2",
+        params![hash_token(&token), user_id],
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        },
+    );
+
+    let (id, expires_at, revoked_at) = match result {
+        Ok(row) => row,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let Ok(expires_at) = DateTime::parse_from_rfc3339(&expires_at) else {
+        return Ok(None);
+    };
+    let expires_at = expires_at.with_timezone(&Utc);
+
+    if revoked_at.is_some() || expires_at <= Utc::now() {
+        return Ok(None);
+    }
+
+    conn.execute(
+        "UPDATE login_sessions SET last_used_at = ?1 WHERE id = ?2",
+        params![Utc::now().to_rfc3339(), id],
+    )?;
+
+    Ok(Some(expires_at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_in_memory;
+
+    fn setup() -> (Connection, i64) {
+        let conn = open_in_memory();
+        conn.execute(
+            "INSERT INTO users (email, password_hash) VALUES ('a@example.com', 'x')",
+            [],
+        )
+        .unwrap();
+        let user_id = conn.last_insert_rowid();
+        (conn, user_id)
+    }
+
+    fn stored_tokens(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM secrets.login_tokens", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn restores_a_login_from_the_token_store() {
+        let (conn, user_id) = setup();
+
+        let expires_at = issue_login(&conn, user_id, Some(30)).unwrap();
+
+        assert_eq!(restore_current(&conn).unwrap(), Some((user_id, expires_at)));
+        assert_eq!(stored_tokens(&conn), 1);
+
+        // The main database only holds the hash.
+        let token_columns: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('app_session') WHERE name = 'token'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(token_columns, 0);
+    }
+
+    #[test]
+    fn forgetting_or_losing_the_token_ends_the_login() {
+        let (conn, user_id) = setup();
+
+        issue_login(&conn, user_id, None).unwrap();
+        forget_account(&conn, user_id).unwrap();
+
+        assert_eq!(restore_current(&conn).unwrap(), None);
+        assert_eq!(stored_tokens(&conn), 0);
+
+        // A copy of trackly.db without the token store restores nothing.
+        issue_login(&conn, user_id, None).unwrap();
+        conn.execute("DELETE FROM secrets.login_tokens", []).unwrap();
+
+        assert_eq!(restore_current(&conn).unwrap(), None);
+        assert!(list_accounts(&conn).unwrap().is_empty());
+    }
+}
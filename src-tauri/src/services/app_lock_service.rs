@@ -0,0 +1,166 @@
+use rusqlite::{params, Connection};
+use chrono::{DateTime, Duration, Utc};
+
+use crate::error::{AppError, AppResult};
+use crate::services::user_service;
+
+const MIN_PIN_LENGTH: usize = 4;
+const MAX_IDLE_TIMEOUT_MINUTES: u32 = 24 * 60;
+
+// Failures allowed before backoff starts; each further failure doubles
+// the wait, up to MAX_BACKOFF_SECONDS.
+const FREE_ATTEMPTS: i64 = 5;
+const BASE_BACKOFF_SECONDS: i64 = 30;
+const MAX_BACKOFF_SECONDS: i64 = 60 * 60;
+
+/* ===========================
+   SETTINGS
+=========================== */
+
+/// The account's idle timeout, or `None` when app lock is off.
+pub fn get_idle_timeout(
+    conn: &Connection,
+    user_id: i64,
+) -> AppResult<Option<u32>> {
+
+    let result = conn.query_row(
+        "SELECT idle_timeout_minutes FROM app_lock WHERE user_id = ?1",
+        params![user_id],
+        |row| row.get(0),
+    );
+
+    match result {
+        Ok(minutes) => Ok(Some(minutes)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Enables the lock or replaces its PIN and timeout. The account
+/// password is required so an unattended session can't set a PIN.
+pub fn enable_app_lock(
+    conn: &Connection,
+    user_id: i64,
+    pin: &str,
+    current_password: &str,
+    idle_timeout_minutes: u32,
+) -> AppResult<()> {
+
+    if pin.chars().count() < MIN_PIN_LENGTH {
+        return Err(AppError::field(
+            "pin",
+            format!("PIN must be at least {} characters", MIN_PIN_LENGTH),
+        ));
+    }
+
+    if idle_timeout_minutes > MAX_IDLE_TIMEOUT_MINUTES {
+        return Err(AppError::field(
+            "idle_timeout_minutes",
+            format!("Idle timeout can be at most {} minutes", MAX_IDLE_TIMEOUT_MINUTES),
+        ));
+    }
+
+    user_service::confirm_password(conn, user_id, current_password)?;
+
+    let pin_hash = user_service::hash_password(pin)?;
+
+    conn.execute(
+        "INSERT INTO app_lock (user_id, pin_hash, idle_timeout_minutes)
+         VALUES (?1, ?2, ?3)
+         ON CONFLICT(user_id) DO UPDATE
+         SET pin_hash = excluded.pin_hash,
+             idle_timeout_minutes = excluded.idle_timeout_minutes,
+             failed_attempts = 0,
+             locked_until = NULL",
+        params![user_id, pin_hash, idle_timeout_minutes],
+    )?;
+
+    Ok(())
+}
+
+pub fn disable_app_lock(
+    conn: &Connection,
+    user_id: i64,
+    current_password: &str,
+) -> AppResult<()> {
+
+    user_service::confirm_password(conn, user_id, current_password)?;
+
+    conn.execute(
+        "DELETE FROM app_lock WHERE user_id = ?1",
+        params![user_id],
+    )?;
+
+    Ok(())
+}
+
+/* ===========================
+   UNLOCK
+=========================== */
+
+/// Checks the PIN, enforcing exponential backoff after repeated
+/// failures. Attempt counters are stored so a restart doesn't reset them.
+pub fn verify_pin(
+    conn: &Connection,
+    user_id: i64,
+    pin: &str,
+) -> AppResult<()> {
+
+    let (pin_hash, failed_attempts, locked_until): (String, i64, Option<String>) = conn
+        .query_row(
+            "SELECT pin_hash, failed_attempts, locked_until
+             FROM app_lock WHERE user_id = ?1",
+            params![user_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|_| AppError::invalid("App lock is not enabled"))?;
+
+    let now = Utc::now();
+
+    if let Some(until) = locked_until
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Utc))
+        .filter(|t| *t > now)
+    {
+        return Err(AppError::RateLimited {
+            retry_after_seconds: (until - now).num_seconds().max(1) as u64,
+        });
+    }
+
+    if user_service::verify_password(&pin_hash, pin) {
+        conn.execute(
+            "UPDATE app_lock SET failed_attempts = 0, locked_until = NULL
+             WHERE user_id = ?1",
+            params![user_id],
+        )?;
+
+        return Ok(());
+    }
+
+    let failed_attempts = failed_attempts + 1;
+
+    let locked_until = (failed_attempts >= FREE_ATTEMPTS)
+        .then(|| now + Duration::seconds(backoff_seconds(failed_attempts)))
+        .map(|t| t.to_rfc3339());
+
+    conn.execute(
+        "UPDATE app_lock SET failed_attempts = ?2, locked_until = ?3
+         WHERE user_id = ?1",
+        params![user_id, failed_attempts, locked_until],
+    )?;
+
+    Err(AppError::field("pin", "Incorrect PIN"))
+}
+
+/* ===========================
+   HELPERS
+=========================== */
+
+fn backoff_seconds(failed_attempts: i64) -> i64 {
+    let exponent = (failed_attempts - FREE_ATTEMPTS).clamp(0, 16) as u32;
+
+    BASE_BACKOFF_SECONDS
+        .saturating_mul(1 << exponent)
+        .min(MAX_BACKOFF_SECONDS)
+}
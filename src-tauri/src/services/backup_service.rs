@@ -0,0 +1,942 @@
+use rusqlite::{params, Connection};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tauri::AppHandle;
+
+use crate::error::{AppError, AppResult};
+use crate::db::migrations;
+use crate::models::backup::{
+    AccountBackup,
+    BackupAvatar,
+    BackupClient,
+    BackupInvoice,
+    BackupInvoiceLine,
+    BackupPause,
+    BackupProfile,
+    BackupProject,
+    BackupRate,
+    BackupSession,
+    RestoreConflict,
+    RestoreMode,
+    RestoreSummary,
+    BACKUP_FORMAT_VERSION,
+};
+use crate::models::rate::RateScope;
+use crate::services::{
+    client_service,
+    project_service,
+    rate_service,
+    session_service,
+    user_service,
+};
+
+/* ===========================
+   EXPORT BACKUP
+=========================== */
+
+pub fn export_account_backup(
+    conn: &Connection,
+    user_id: i64,
+) -> AppResult<AccountBackup> {
+
+    let user = user_service::get_user_by_id(conn, user_id)?;
+
+    let avatar = match user.avatar_url {
+        Some(ref path) => read_avatar(Path::new(path)),
+        None => None,
+    };
+
+    let clients = client_service::get_clients(conn, user_id)?
+        .into_iter()
+        .map(|c| BackupClient {
+            id: c.id,
+            name: c.name,
+            color: c.color,
+        })
+        .collect();
+
+    let projects = project_service::get_projects(conn, user_id, true)?
+        .into_iter()
+        .map(|p| BackupProject {
+            id: p.id,
+            name: p.name,
+            color: p.color,
+            archived_at: p.archived_at,
+            client_id: p.client_id,
+            parent_id: p.parent_id,
+            billable: Some(p.billable),
+        })
+        .collect();
+
+    let rates = rate_service::get_rates(conn, user_id)?
+        .into_iter()
+        .map(|r| BackupRate {
+            scope: r.scope,
+            amount_cents: r.amount_cents,
+            currency: r.currency,
+            effective_from: r.effective_from,
+        })
+        .collect();
+
+    Ok(AccountBackup {
+        format_version: BACKUP_FORMAT_VERSION,
+        schema_version: migrations::latest_version(),
+        exported_at: Utc::now().to_rfc3339(),
+        profile: BackupProfile {
+            email: user.email,
+            display_name: user.display_name,
+        },
+        avatar,
+        clients,
+        projects,
+        sessions: load_sessions(conn, user_id)?,
+        rates,
+        invoices: load_invoices(conn, user_id)?,
+    })
+}
+
+pub fn write_backup(
+    backup: &AccountBackup,
+    path: &Path,
+) -> AppResult<()> {
+
+    let file = std::fs::File::create(path)?;
+
+    serde_json::to_writer_pretty(std::io::BufWriter::new(file), backup)
+        .map_err(|e| AppError::Io(e.into()))
+}
+
+/* ===========================
+   IMPORT BACKUP
+=========================== */
+
+pub fn import_account_backup(
+    conn: &mut Connection,
+    app: &AppHandle,
+    user_id: i64,
+    content: &str,
+    mode: RestoreMode,
+) -> AppResult<RestoreSummary> {
+
+    let mut backup: AccountBackup = serde_json::from_str(content)
+        .map_err(|e| AppError::invalid(format!("Invalid backup file: {}", e)))?;
+
+    validate(&mut backup)?;
+
+    let avatar = match backup.avatar {
+        Some(ref a) => Some(decode_avatar(a)?),
+        None => None,
+    };
+
+    let current = user_service::get_user_by_id(conn, user_id)?;
+
+    let summary = restore_backup(conn, user_id, &backup, mode)?;
+
+    // Merge keeps an avatar the user already has.
+    if let Some(bytes) = avatar {
+        if mode == RestoreMode::Replace || current.avatar_url.is_none() {
+            user_service::upload_avatar(conn, app, user_id, bytes)?;
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Everything but the avatar, in one transaction.
+fn restore_backup(
+    conn: &mut Connection,
+    user_id: i64,
+    backup: &AccountBackup,
+    mode: RestoreMode,
+) -> AppResult<RestoreSummary> {
+
+    // The running timer is never archived, so Replace couldn't bring it back.
+    if mode == RestoreMode::Replace
+        && session_service::get_active_session(conn, user_id)?.is_some()
+    {
+        return Err(AppError::Conflict(
+            "Stop the active session before replacing your data".into(),
+        ));
+    }
+
+    let mut summary = RestoreSummary::default();
+
+    let tx = conn.transaction()?;
+
+    if mode == RestoreMode::Replace {
+        clear_account(&tx, user_id)?;
+
+        tx.execute(
+            "UPDATE users SET display_name = ?1 WHERE id = ?2",
+            params![backup.profile.display_name, user_id],
+        )?;
+    }
+
+    /* ---------- Clients ---------- */
+
+    let mut clients_by_name: HashMap<String, i64> =
+        client_service::get_clients(&tx, user_id)?
+            .into_iter()
+            .map(|c| (c.name.to_lowercase(), c.id))
+            .collect();
+
+    let mut client_map: HashMap<i64, i64> = HashMap::new();
+
+    for client in &backup.clients {
+        let key = client.name.trim().to_lowercase();
+
+        let new_id = match clients_by_name.get(&key) {
+            Some(id) => {
+                summary.clients_matched += 1;
+                *id
+            }
+            None => {
+                let created = client_service::create_client(
+                    &tx,
+                    user_id,
+                    &client.name,
+                    Some(&client.color),
+                )?;
+
+                clients_by_name.insert(key, created.id);
+                summary.clients_created += 1;
+                created.id
+            }
+        };
+
+        client_map.insert(client.id, new_id);
+    }
+
+    /* ---------- Projects ---------- */
+
+    let mut by_name: HashMap<String, i64> =
+        project_service::get_projects(&tx, user_id, true)?
+            .into_iter()
+            .map(|p| (p.name.to_lowercase(), p.id))
+            .collect();
+
+    let mut project_map: HashMap<i64, i64> = HashMap::new();
+    let mut created_projects: Vec<(&BackupProject, i64)> = Vec::new();
+
+    for project in &backup.projects {
+        let key = project.name.to_lowercase();
+
+        let new_id = match by_name.get(&key) {
+            Some(id) => {
+                summary.projects_matched += 1;
+                *id
+            }
+            None => {
+                let created = project_service::create_project(
+                    &tx,
+                    user_id,
+                    &project.name,
+                    &project.color,
+                    None,
+                    None,
+                )?;
+
+                tx.execute(
+                    "UPDATE projects SET archived_at = ?1, billable = ?2 WHERE id = ?3",
+                    params![project.archived_at, project.billable.unwrap_or(true), created.id],
+                )?;
+
+                by_name.insert(key, created.id);
+                created_projects.push((project, created.id));
+                summary.projects_created += 1;
+                created.id
+            }
+        };
+
+        project_map.insert(project.id, new_id);
+    }
+
+    // Placed once every project has an id, since a parent may come after
+    // its sub-projects. Matched projects keep their current placement, so
+    // a parent that is already a sub-project here is skipped.
+    for (project, new_id) in &created_projects {
+        let client_id = project.client_id.and_then(|old| client_map.get(&old).copied());
+        let parent_id = project.parent_id.and_then(|old| project_map.get(&old).copied());
+
+        tx.execute(
+            "UPDATE projects SET client_id = ?1 WHERE id = ?2",
+            params![client_id, new_id],
+        )?;
+
+        tx.execute(
+            "UPDATE projects SET parent_id = ?1, client_id = NULL
+             WHERE id = ?2
+               AND ?1 != ?2
+               AND EXISTS (SELECT 1 FROM projects WHERE id = ?1 AND parent_id IS NULL)
+               AND NOT EXISTS (SELECT 1 FROM projects WHERE parent_id = ?2)",
+            params![parent_id, new_id],
+        )?;
+    }
+
+    /* ---------- Invoices ---------- */
+
+    // Numbers are kept as issued. Merge matches invoices by sequence and
+    // refuses a different invoice already holding the same number.
+    let mut invoice_map: HashMap<i64, i64> = HashMap::new();
+
+    for invoice in &backup.invoices {
+        let existing = tx.query_row(
+            "SELECT id, number, issued_at FROM invoices
+             WHERE user_id = ?1 AND sequence = ?2",
+            params![user_id, invoice.sequence],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)),
+        );
+
+        let new_id = match existing {
+            Ok((id, number, issued_at))
+                if number == invoice.number && issued_at == invoice.issued_at =>
+            {
+                summary.invoices_matched += 1;
+                id
+            }
+            Ok((_, number, _)) => {
+                return Err(AppError::Conflict(format!(
+                    "Invoice {} in this account differs from {} in the backup; restore with Replace instead",
+                    number, invoice.number
+                )));
+            }
+            Err(rusqlite::Error::QueryReturnedNoRows) => {
+                insert_invoice(&tx, user_id, invoice, &client_map)?;
+                summary.invoices_imported += 1;
+                tx.last_insert_rowid()
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        invoice_map.insert(invoice.id, new_id);
+    }
+
+    /* ---------- Sessions ---------- */
+
+    for session in &backup.sessions {
+        let project_id = session
+            .project_id
+            .and_then(|old| project_map.get(&old).copied());
+
+        let invoice_id = session
+            .invoice_id
+            .and_then(|old| invoice_map.get(&old).copied());
+
+        if mode == RestoreMode::Merge {
+            if let Some(existing) = find_session(&tx, user_id, session, project_id)? {
+                // Billed in the archive means billed here too.
+                tx.execute(
+                    "UPDATE sessions SET invoice_id = ?1
+                     WHERE id = ?2 AND invoice_id IS NULL",
+                    params![invoice_id, existing],
+                )?;
+
+                summary.sessions_skipped += 1;
+                continue;
+            }
+
+            // Time tracked on two machines mustn't pile up on one account.
+            if let (Some(end), "finished") = (&session.end_time, session.status.as_str()) {
+                let overlap =
+                    session_service::find_overlap(&tx, user_id, &session.start_time, end, None)?;
+
+                if let Some(session_id) = overlap {
+                    summary.sessions_conflicting.push(RestoreConflict {
+                        start_time: session.start_time.clone(),
+                        end_time: end.clone(),
+                        session_id,
+                    });
+                    continue;
+                }
+            }
+        }
+
+        tx.execute(
+            "INSERT INTO sessions
+                 (user_id, project_id, start_time, end_time, description, status, billable,
+                  invoice_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                user_id,
+                project_id,
+                session.start_time,
+                session.end_time,
+                session.description,
+                session.status,
+                session.billable,
+                invoice_id
+            ],
+        )?;
+
+        let id = tx.last_insert_rowid();
+
+        session_service::replace_tags(&tx, id, &session.tags)?;
+
+        for pause in &session.pauses {
+            tx.execute(
+                "INSERT INTO session_pauses (session_id, start_time, end_time)
+                 VALUES (?1, ?2, ?3)",
+                params![id, pause.start_time, pause.end_time],
+            )?;
+        }
+
+        summary.sessions_imported += 1;
+    }
+
+    /* ---------- Rates ---------- */
+
+    // Rates for clients or projects the archive doesn't describe are
+    // dropped. Existing versions with the same start are replaced.
+    for rate in &backup.rates {
+        let scope = match rate.scope {
+            RateScope::User => Some(RateScope::User),
+            RateScope::Client { client_id } => client_map
+                .get(&client_id)
+                .map(|&id| RateScope::Client { client_id: id }),
+            RateScope::Project { project_id } => project_map
+                .get(&project_id)
+                .map(|&id| RateScope::Project { project_id: id }),
+        };
+
+        let Some(scope) = scope else {
+            continue;
+        };
+
+        rate_service::set_rate(
+            &tx,
+            user_id,
+            scope,
+            rate.amount_cents,
+            &rate.currency,
+            Some(&rate.effective_from),
+        )?;
+
+        summary.rates_imported += 1;
+    }
+
+    tx.commit()?;
+
+    Ok(summary)
+}
+
+/* ===========================
+   HELPERS
+=========================== */
+
+/// Checks the archive and rewrites every timestamp in the UTC form the
+/// app stores, so restored rows compare correctly with existing ones.
+fn validate(backup: &mut AccountBackup) -> AppResult<()> {
+    if backup.format_version != BACKUP_FORMAT_VERSION {
+        return Err(AppError::invalid(format!(
+            "Unsupported backup format version {}",
+            backup.format_version
+        )));
+    }
+
+    if backup.schema_version > migrations::latest_version() {
+        return Err(AppError::invalid("Backup was created by a newer version of Trackly"));
+    }
+
+    let mut invoice_ids = HashSet::new();
+
+    for invoice in &mut backup.invoices {
+        if !invoice_ids.insert(invoice.id) {
+            return Err(AppError::invalid(format!("Duplicate invoice id {}", invoice.id)));
+        }
+
+        normalize_time(&mut invoice.issued_at)?;
+        normalize_time(&mut invoice.period_from)?;
+        normalize_time(&mut invoice.period_to)?;
+
+        if let Some(ref mut voided_at) = invoice.voided_at {
+            normalize_time(voided_at)?;
+        }
+    }
+
+    for project in &mut backup.projects {
+        if let Some(ref mut archived_at) = project.archived_at {
+            normalize_time(archived_at)?;
+        }
+    }
+
+    for rate in &mut backup.rates {
+        normalize_time(&mut rate.effective_from)?;
+    }
+
+    for session in &mut backup.sessions {
+        if session.invoice_id.is_some_and(|id| !invoice_ids.contains(&id)) {
+            return Err(AppError::invalid("Session refers to an invoice missing from the backup"));
+        }
+
+        if !matches!(session.status.as_str(), "finished" | "cancelled") {
+            return Err(AppError::invalid(format!("Invalid session status: {}", session.status)));
+        }
+
+        let start = normalize_time(&mut session.start_time)?;
+
+        let end = match session.end_time {
+            Some(ref mut end) => Some(normalize_time(end)?),
+            None if session.status == "finished" => {
+                return Err(AppError::invalid("Finished session without an end time"));
+            }
+            None => None,
+        };
+
+        if end.is_some_and(|end| end < start) {
+            return Err(AppError::invalid(format!(
+                "Session starting {} ends before it starts",
+                session.start_time
+            )));
+        }
+
+        for pause in &mut session.pauses {
+            let pause_start = normalize_time(&mut pause.start_time)?;
+
+            let pause_end = match pause.end_time {
+                Some(ref mut pause_end) => Some(normalize_time(pause_end)?),
+                None => None,
+            };
+
+            let pause_last = pause_end.unwrap_or(pause_start);
+
+            if pause_start < start
+                || pause_last < pause_start
+                || end.is_some_and(|end| pause_last > end)
+            {
+                return Err(AppError::invalid(format!(
+                    "Pause of the session starting {} falls outside it",
+                    session.start_time
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn normalize_time(value: &mut String) -> AppResult<DateTime<Utc>> {
+    let time = session_service::parse_time(value)?;
+    *value = time.to_rfc3339();
+    Ok(time)
+}
+
+/// Only finished and cancelled sessions are archived; an active timer
+/// belongs to the machine it is running on.
+fn load_sessions(
+    conn: &Connection,
+    user_id: i64,
+) -> AppResult<Vec<BackupSession>> {
+
+    let mut stmt = conn.prepare(
+        "SELECT id, project_id, start_time, end_time, description, status, billable,
+                invoice_id
+         FROM sessions
+         WHERE user_id = ?1 AND status IN ('finished','cancelled')
+         ORDER BY start_time ASC",
+    )?;
+
+    let rows = stmt
+        .query_map(params![user_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                BackupSession {
+                    project_id: row.get(1)?,
+                    start_time: row.get(2)?,
+                    end_time: row.get(3)?,
+                    description: row.get(4)?,
+                    status: row.get(5)?,
+                    billable: row.get(6)?,
+                    invoice_id: row.get(7)?,
+                    tags: Vec::new(),
+                    pauses: Vec::new(),
+                },
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let ids: Vec<i64> = rows.iter().map(|(id, _)| *id).collect();
+    let mut tags = session_service::load_tags(conn, &ids)?;
+    let mut pauses = load_pauses(conn, user_id)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, mut session)| {
+            session.tags = tags.remove(&id).unwrap_or_default();
+            session.pauses = pauses.remove(&id).unwrap_or_default();
+            session
+        })
+        .collect())
+}
+
+fn load_pauses(
+    conn: &Connection,
+    user_id: i64,
+) -> AppResult<HashMap<i64, Vec<BackupPause>>> {
+
+    let mut stmt = conn.prepare(
+        "SELECT p.session_id, p.start_time, p.end_time
+         FROM session_pauses p
+         JOIN sessions s ON s.id = p.session_id
+         WHERE s.user_id = ?1
+         ORDER BY p.start_time",
+    )?;
+
+    let rows = stmt
+        .query_map(params![user_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                BackupPause {
+                    start_time: row.get(1)?,
+                    end_time: row.get(2)?,
+                },
+            ))
+        })?;
+
+    let mut pauses: HashMap<i64, Vec<BackupPause>> = HashMap::new();
+
+    for r in rows {
+        let (id, pause) = r?;
+        pauses.entry(id).or_default().push(pause);
+    }
+
+    Ok(pauses)
+}
+
+fn load_invoices(
+    conn: &Connection,
+    user_id: i64,
+) -> AppResult<Vec<BackupInvoice>> {
+
+    let mut stmt = conn.prepare(
+        "SELECT id, client_id, sequence, number, client_name, period_from, period_to,
+                currency, total_cents, notes, issued_at, voided_at
+         FROM invoices
+         WHERE user_id = ?1
+         ORDER BY sequence ASC",
+    )?;
+
+    let mut invoices = stmt
+        .query_map(params![user_id], |row| {
+            Ok(BackupInvoice {
+                id: row.get(0)?,
+                client_id: row.get(1)?,
+                sequence: row.get(2)?,
+                number: row.get(3)?,
+                client_name: row.get(4)?,
+                period_from: row.get(5)?,
+                period_to: row.get(6)?,
+                currency: row.get(7)?,
+                total_cents: row.get(8)?,
+                notes: row.get(9)?,
+                issued_at: row.get(10)?,
+                voided_at: row.get(11)?,
+                lines: Vec::new(),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT l.invoice_id, l.description, l.session_count, l.seconds,
+                l.rate_cents, l.amount_cents
+         FROM invoice_lines l
+         JOIN invoices i ON i.id = l.invoice_id
+         WHERE i.user_id = ?1
+         ORDER BY l.invoice_id, l.position",
+    )?;
+
+    let rows = stmt
+        .query_map(params![user_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                BackupInvoiceLine {
+                    description: row.get(1)?,
+                    session_count: row.get(2)?,
+                    seconds: row.get(3)?,
+                    rate_cents: row.get(4)?,
+                    amount_cents: row.get(5)?,
+                },
+            ))
+        })?;
+
+    let mut lines: HashMap<i64, Vec<BackupInvoiceLine>> = HashMap::new();
+
+    for r in rows {
+        let (id, line) = r?;
+        lines.entry(id).or_default().push(line);
+    }
+
+    for invoice in &mut invoices {
+        invoice.lines = lines.remove(&invoice.id).unwrap_or_default();
+    }
+
+    Ok(invoices)
+}
+
+fn insert_invoice(
+    conn: &Connection,
+    user_id: i64,
+    invoice: &BackupInvoice,
+    client_map: &HashMap<i64, i64>,
+) -> AppResult<()> {
+
+    let client_id = invoice.client_id.and_then(|old| client_map.get(&old).copied());
+
+    conn.execute(
+        "INSERT INTO invoices
+            (user_id, client_id, sequence, number, client_name, period_from,
+             period_to, currency, total_cents, notes, issued_at, voided_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            user_id,
+            client_id,
+            invoice.sequence,
+            invoice.number,
+            invoice.client_name,
+            invoice.period_from,
+            invoice.period_to,
+            invoice.currency,
+            invoice.total_cents,
+            invoice.notes,
+            invoice.issued_at,
+            invoice.voided_at,
+        ],
+    )?;
+
+    let invoice_id = conn.last_insert_rowid();
+
+    for (position, line) in invoice.lines.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO invoice_lines
+                (invoice_id, position, description, session_count, seconds,
+                 rate_cents, amount_cents)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                invoice_id,
+                position as i64,
+                line.description,
+                line.session_count,
+                line.seconds,
+                line.rate_cents,
+                line.amount_cents,
+            ],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn find_session(
+    conn: &Connection,
+    user_id: i64,
+    session: &BackupSession,
+    project_id: Option<i64>,
+) -> AppResult<Option<i64>> {
+
+    let result = conn.query_row(
+        "SELECT id FROM sessions
+         WHERE user_id = ?1
+           AND start_time = ?2
+           AND end_time IS ?3
+           AND project_id IS ?4
+         LIMIT 1",
+        params![user_id, session.start_time, session.end_time, project_id],
+        |row| row.get(0),
+    );
+
+    match result {
+        Ok(id) => Ok(Some(id)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn clear_account(
+    conn: &Connection,
+    user_id: i64,
+) -> AppResult<()> {
+
+    for sql in [
+        "DELETE FROM invoice_lines
+         WHERE invoice_id IN (SELECT id FROM invoices WHERE user_id = ?1)",
+        "DELETE FROM session_tags
+         WHERE session_id IN (SELECT id FROM sessions WHERE user_id = ?1)",
+        "DELETE FROM session_pauses
+         WHERE session_id IN (SELECT id FROM sessions WHERE user_id = ?1)",
+        "DELETE FROM sessions WHERE user_id = ?1",
+        "DELETE FROM invoices WHERE user_id = ?1",
+        "DELETE FROM hourly_rates WHERE user_id = ?1",
+        "DELETE FROM projects WHERE user_id = ?1",
+        "DELETE FROM clients WHERE user_id = ?1",
+    ] {
+        conn.execute(sql, params![user_id])?;
+    }
+
+    Ok(())
+}
+
+fn read_avatar(path: &Path) -> Option<BackupAvatar> {
+    let bytes = std::fs::read(path).ok()?;
+
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("webp")
+        .to_string();
+
+    Some(BackupAvatar {
+        extension,
+        data_base64: STANDARD.encode(bytes),
+    })
+}
+
+fn decode_avatar(avatar: &BackupAvatar) -> AppResult<Vec<u8>> {
+    let bytes = STANDARD
+        .decode(&avatar.data_base64)
+        .map_err(|_| AppError::invalid("Invalid avatar data in backup"))?;
+
+    image::load_from_memory(&bytes)
+        .map_err(|_| AppError::invalid("Invalid avatar data in backup"))?;
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_in_memory;
+
+    fn account() -> (Connection, i64, i64) {
+        let conn = open_in_memory();
+        let user = user_service::create_user(&conn, "a@example.com", "Old-passphrase-42").unwrap();
+        let project = project_service::create_project(&conn, user.id, "Web", "#ff0000", None, None).unwrap();
+
+        conn.execute(
+            "INSERT INTO sessions (user_id, project_id, start_time, end_time, status)
+             VALUES (?1, ?2, '2026-01-05T09:00:00+00:00', '2026-01-05T10:00:00+00:00', 'finished')",
+            params![user.id, project.id],
+        )
+        .unwrap();
+
+        (conn, user.id, project.id)
+    }
+
+    fn session_count(conn: &Connection, user_id: i64) -> i64 {
+        conn.query_row(
+            "SELECT COUNT(*) FROM sessions WHERE user_id = ?1",
+            params![user_id],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn replace_refuses_to_drop_the_active_session() {
+        let (mut conn, user_id, project_id) = account();
+        let backup = export_account_backup(&conn, user_id).unwrap();
+
+        session_service::start_session(&conn, user_id, Some(project_id)).unwrap();
+
+        let result = restore_backup(&mut conn, user_id, &backup, RestoreMode::Replace);
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        assert!(session_service::get_active_session(&conn, user_id).unwrap().is_some());
+        assert_eq!(session_count(&conn, user_id), 2);
+
+        // Merge leaves the timer alone.
+        let summary = restore_backup(&mut conn, user_id, &backup, RestoreMode::Merge).unwrap();
+        assert_eq!(summary.sessions_skipped, 1);
+        assert!(session_service::get_active_session(&conn, user_id).unwrap().is_some());
+    }
+
+    #[test]
+    fn timestamps_are_stored_in_utc() {
+        let (conn, user_id, _) = account();
+        let mut backup = export_account_backup(&conn, user_id).unwrap();
+
+        let (mut target, target_id, _) = account();
+        conn.execute("DELETE FROM sessions", []).unwrap();
+
+        let session = &mut backup.sessions[0];
+        session.start_time = "2026-02-01T11:00:00+02:00".into();
+        session.end_time = Some("2026-02-01T12:30:00+02:00".into());
+        session.pauses.push(BackupPause {
+            start_time: "2026-02-01T11:15:00+02:00".into(),
+            end_time: Some("2026-02-01T11:20:00+02:00".into()),
+        });
+        backup.projects[0].name = "Old site".into();
+        backup.projects[0].archived_at = Some("2026-03-01T08:00:00-05:00".into());
+
+        validate(&mut backup).unwrap();
+        restore_backup(&mut target, target_id, &backup, RestoreMode::Merge).unwrap();
+
+        let stored: (String, String, String) = target
+            .query_row(
+                "SELECT s.start_time, s.end_time, p.start_time
+                 FROM sessions s JOIN session_pauses p ON p.session_id = s.id
+                 WHERE s.user_id = ?1",
+                params![target_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+
+        assert_eq!(
+            stored,
+            (
+                "2026-02-01T09:00:00+00:00".to_string(),
+                "2026-02-01T10:30:00+00:00".to_string(),
+                "2026-02-01T09:15:00+00:00".to_string(),
+            )
+        );
+
+        let archived: String = target
+            .query_row(
+                "SELECT archived_at FROM projects WHERE user_id = ?1 AND name = 'Old site'",
+                params![target_id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(archived, "2026-03-01T13:00:00+00:00");
+    }
+
+    #[test]
+    fn rejects_sessions_ending_early_and_stray_pauses() {
+        let (conn, user_id, _) = account();
+        let backup = || export_account_backup(&conn, user_id).unwrap();
+
+        let mut reversed = backup();
+        reversed.sessions[0].end_time = Some("2026-01-05T08:00:00Z".into());
+        assert!(validate(&mut reversed).is_err());
+
+        let mut stray = backup();
+        stray.sessions[0].pauses.push(BackupPause {
+            start_time: "2026-01-05T09:50:00Z".into(),
+            end_time: Some("2026-01-05T10:10:00Z".into()),
+        });
+        assert!(validate(&mut stray).is_err());
+
+        let mut bad_archive = backup();
+        bad_archive.projects[0].archived_at = Some("yesterday".into());
+        assert!(validate(&mut bad_archive).is_err());
+    }
+
+    #[test]
+    fn merge_reports_overlapping_sessions_instead_of_inserting_them() {
+        let (conn, user_id, _) = account();
+        let mut backup = export_account_backup(&conn, user_id).unwrap();
+
+        let (mut target, target_id, _) = account();
+
+        // Same hour on the other machine, but not the same session.
+        backup.sessions[0].start_time = "2026-01-05T09:30:00Z".into();
+        backup.sessions[0].end_time = Some("2026-01-05T10:30:00Z".into());
+        validate(&mut backup).unwrap();
+
+        let summary = restore_backup(&mut target, target_id, &backup, RestoreMode::Merge).unwrap();
+
+        assert_eq!(summary.sessions_imported, 0);
+        assert_eq!(summary.sessions_conflicting.len(), 1);
+        assert_eq!(summary.sessions_conflicting[0].start_time, "2026-01-05T09:30:00+00:00");
+        assert_eq!(session_count(&target, target_id), 1);
+    }
+}
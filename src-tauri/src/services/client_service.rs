@@ -0,0 +1,184 @@
+use rusqlite::{params, Connection};
+use crate::error::{AppError, AppResult};
+use crate::models::client::Client;
+
+const DEFAULT_CLIENT_COLOR: &str = "#6c757d";
+
+/* ===========================
+   GET CLIENTS
+=========================== */
+
+pub fn get_clients(
+    conn: &Connection,
+    user_id: i64,
+) -> AppResult<Vec<Client>> {
+
+    let mut stmt = conn.prepare(
+        "SELECT id, name, color
+         FROM clients
+         WHERE user_id = ?1
+         ORDER BY name COLLATE NOCASE ASC"
+    )?;
+
+    let rows = stmt
+        .query_map(params![user_id], |row| {
+            Ok(Client {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                color: row.get(2)?,
+            })
+        })?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(AppError::from)
+}
+
+pub fn get_client_by_id(
+    conn: &Connection,
+    user_id: i64,
+    client_id: i64,
+) -> AppResult<Client> {
+
+    conn.query_row(
+        "SELECT id, name, color
+         FROM clients
+         WHERE id = ?1 AND user_id = ?2",
+        params![client_id, user_id],
+        |row| {
+            Ok(Client {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                color: row.get(2)?,
+            })
+        },
+    )
+    .map_err(|_| AppError::NotFound("Client"))
+}
+
+/* ===========================
+   CREATE CLIENT
+=========================== */
+
+pub fn create_client(
+    conn: &Connection,
+    user_id: i64,
+    name: &str,
+    color: Option<&str>,
+) -> AppResult<Client> {
+
+    let name = validate_name(conn, user_id, name, None)?;
+    let color = color.unwrap_or(DEFAULT_CLIENT_COLOR);
+
+    conn.execute(
+        "INSERT INTO clients (user_id, name, color)
+         VALUES (?1, ?2, ?3)",
+        params![user_id, name, color],
+    )?;
+
+    Ok(Client {
+        id: conn.last_insert_rowid(),
+        name,
+        color: color.to_string(),
+    })
+}
+
+/* ===========================
+   UPDATE CLIENT
+=========================== */
+
+pub fn update_client(
+    conn: &Connection,
+    user_id: i64,
+    client_id: i64,
+    name: Option<String>,
+    color: Option<String>,
+) -> AppResult<Client> {
+
+    let current = get_client_by_id(conn, user_id, client_id)?;
+
+    let name = match name {
+        Some(n) => validate_name(conn, user_id, &n, Some(client_id))?,
+        None => current.name,
+    };
+
+    let color = color.unwrap_or(current.color);
+
+    conn.execute(
+        "UPDATE clients SET name = ?1, color = ?2
+         WHERE id = ?3 AND user_id = ?4",
+        params![name, color, client_id, user_id],
+    )?;
+
+    Ok(Client {
+        id: client_id,
+        name,
+        color,
+    })
+}
+
+/* ===========================
+   DELETE CLIENT
+=========================== */
+
+/// Deletes the client. Its projects are kept without a client.
+pub fn delete_client(
+    conn: &Connection,
+    user_id: i64,
+    client_id: i64,
+) -> AppResult<()> {
+
+    get_client_by_id(conn, user_id, client_id)?;
+
+    let tx = conn.unchecked_transaction()?;
+
+    tx.execute(
+        "UPDATE projects SET client_id = NULL
+         WHERE client_id = ?1 AND user_id = ?2",
+        params![client_id, user_id],
+    )?;
+
+    tx.execute(
+        "DELETE FROM clients WHERE id = ?1 AND user_id = ?2",
+        params![client_id, user_id],
+    )?;
+
+    tx.commit()?;
+
+    Ok(())
+}
+
+/* ===========================
+   HELPERS
+=========================== */
+
+/// Trims the name and rejects blanks and case-insensitive duplicates.
+fn validate_name(
+    conn: &Connection,
+    user_id: i64,
+    name: &str,
+    except_id: Option<i64>,
+) -> AppResult<String> {
+
+    let name = name.trim();
+
+    if name.is_empty() {
+        return Err(AppError::field("name", "Client name is required"));
+    }
+
+    let taken: bool = conn.query_row(
+        "SELECT EXISTS (
+             SELECT 1 FROM clients
+             WHERE user_id = ?1
+               AND name = ?2 COLLATE NOCASE
+               AND (?3 IS NULL OR id != ?3)
+         )",
+        params![user_id, name, except_id],
+        |row| row.get(0),
+    )?;
+
+    if taken {
+        return Err(AppError::Conflict(format!("A client named \"{}\" already exists", name)));
+    }
+
+    Ok(name.to_string())
+}
@@ -0,0 +1,253 @@
+use rusqlite::Connection;
+use chrono::DateTime;
+use chrono::format::{Item, StrftimeItems};
+use chrono_tz::Tz;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::error::{AppError, AppResult};
+use crate::models::export::{
+    CsvColumn,
+    CsvExportOptions,
+    DurationFormat,
+    ExportSummary,
+};
+use crate::models::session::{
+    SessionListItem,
+    SessionQuery,
+    SessionStatus,
+    SortOrder,
+    TagMatch,
+};
+use crate::models::project::Project;
+use crate::services::{client_service, project_service, session_service};
+
+/* ===========================
+   EXPORT CSV
+=========================== */
+
+pub fn export_sessions_csv(
+    conn: &Connection,
+    user_id: i64,
+    options: &CsvExportOptions,
+    path: &Path,
+) -> AppResult<ExportSummary> {
+
+    if options.columns.is_empty() {
+        return Err(AppError::field("columns", "Select at least one column"));
+    }
+
+    let tz = parse_timezone(options.timezone.as_deref())?;
+    let date_format = validate_date_format(&options.date_format)?;
+
+    let projects: HashMap<i64, Project> =
+        project_service::get_projects(conn, user_id, true)?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+
+    let client_names: HashMap<i64, String> =
+        client_service::get_clients(conn, user_id)?
+            .into_iter()
+            .map(|c| (c.id, c.name))
+            .collect();
+
+    let sessions = collect_finished_sessions(conn, user_id, options, &projects)?;
+
+    // Sub-projects nest one level deep and share their parent's client.
+    let top_level = |id: i64| {
+        projects
+            .get(&id)
+            .map(|p| p.parent_id.and_then(|parent| projects.get(&parent)).unwrap_or(p))
+    };
+
+    let mut writer = csv::Writer::from_path(path)?;
+
+    writer
+        .write_record(options.columns.iter().map(|c| c.header()))?;
+
+    for session in &sessions {
+        let record: Vec<String> = options
+            .columns
+            .iter()
+            .map(|column| match column {
+                CsvColumn::Project => session
+                    .project_id
+                    .and_then(|id| {
+                        if options.roll_up { top_level(id) } else { projects.get(&id) }
+                    })
+                    .map(|p| spreadsheet_safe(&p.name))
+                    .unwrap_or_default(),
+                CsvColumn::Client => session
+                    .project_id
+                    .and_then(top_level)
+                    .and_then(|p| p.client_id)
+                    .and_then(|id| client_names.get(&id))
+                    .map(|name| spreadsheet_safe(name))
+                    .unwrap_or_default(),
+                CsvColumn::Start => {
+                    format_time(&session.start_time, &tz, &date_format)
+                }
+                CsvColumn::End => session
+                    .end_time
+                    .as_deref()
+                    .map(|t| format_time(t, &tz, &date_format))
+                    .unwrap_or_default(),
+                CsvColumn::Duration => {
+                    format_duration(session.net_seconds, options.duration_format)
+                }
+                CsvColumn::Description => session
+                    .description
+                    .as_deref()
+                    .map(spreadsheet_safe)
+                    .unwrap_or_default(),
+                CsvColumn::Tags => spreadsheet_safe(&session.tags.join(&options.tag_separator)),
+            })
+            .collect();
+
+        writer.write_record(&record)?;
+    }
+
+    writer.flush()?;
+
+    Ok(ExportSummary {
+        path: path.to_string_lossy().to_string(),
+        rows: sessions.len(),
+    })
+}
+
+/* ===========================
+   HELPERS
+=========================== */
+
+/// Walks every page of `query_sessions` for the export filter, oldest first.
+fn collect_finished_sessions(
+    conn: &Connection,
+    user_id: i64,
+    options: &CsvExportOptions,
+    projects: &HashMap<i64, Project>,
+) -> AppResult<Vec<SessionListItem>> {
+
+    let mut project_ids = options.project_ids.clone();
+
+    project_ids.extend(
+        projects
+            .values()
+            .filter(|p| p.parent_id.is_some_and(|parent| options.project_ids.contains(&parent)))
+            .map(|p| p.id),
+    );
+
+    let mut query = SessionQuery {
+        from: options.from.clone(),
+        to: options.to.clone(),
+        project_ids,
+        tags: options.tags.clone(),
+        tag_match: TagMatch::Any,
+        statuses: vec![SessionStatus::Finished],
+        sort: SortOrder::Asc,
+        limit: Some(200),
+        ..Default::default()
+    };
+
+    let mut sessions = Vec::new();
+
+    loop {
+        let page = session_service::query_sessions(conn, user_id, &query)?;
+        sessions.extend(page.items);
+
+        match page.next_cursor {
+            Some(cursor) => query.cursor = Some(cursor),
+            None => break,
+        }
+    }
+
+    Ok(sessions)
+}
+
+pub(crate) fn parse_timezone(value: Option<&str>) -> AppResult<Tz> {
+    match value {
+        None | Some("") => Ok(Tz::UTC),
+        Some(name) => name
+            .parse::<Tz>()
+            .map_err(|_| AppError::field("timezone", format!("Unknown timezone: {}", name))),
+    }
+}
+
+/// chrono panics while formatting an invalid pattern, so reject it upfront.
+pub(crate) fn validate_date_format(format: &str) -> AppResult<String> {
+    if format.is_empty()
+        || StrftimeItems::new(format).any(|item| matches!(item, Item::Error))
+    {
+        return Err(AppError::field("date_format", format!("Invalid date format: {}", format)));
+    }
+
+    Ok(format.to_string())
+}
+
+pub(crate) fn format_time(value: &str, tz: &Tz, format: &str) -> String {
+    match DateTime::parse_from_rfc3339(value) {
+        Ok(t) => t.with_timezone(tz).format(format).to_string(),
+        Err(_) => value.to_string(),
+    }
+}
+
+/// Quotes text a spreadsheet would otherwise run as a formula, the way
+/// spreadsheets mark a cell as literal text.
+fn spreadsheet_safe(value: &str) -> String {
+    match value.chars().next() {
+        Some('=' | '+' | '-' | '@' | '\t' | '\r') => format!("'{}", value),
+        _ => value.to_string(),
+    }
+}
+
+pub(crate) fn format_duration(seconds: i64, format: DurationFormat) -> String {
+    match format {
+        DurationFormat::DecimalHours => format!("{:.2}", seconds as f64 / 3600.0),
+        DurationFormat::HoursMinutes => {
+            let minutes = (seconds + 30) / 60;
+            format!("{}:{:02}", minutes / 60, minutes % 60)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_in_memory;
+    use crate::services::user_service;
+
+    #[test]
+    fn formulas_are_exported_as_text() {
+        let conn = open_in_memory();
+        let user = user_service::create_user(&conn, "a@example.com", "Old-passphrase-42").unwrap();
+        let client = client_service::create_client(&conn, user.id, "@Acme", None).unwrap();
+        let project = project_service::create_project(&conn, user.id, "=SUM(A1:A9)", "#ff0000", None, Some(client.id)).unwrap();
+
+        conn.execute(
+            "INSERT INTO sessions (user_id, project_id, start_time, end_time, description, status)
+             VALUES (?1, ?2, '2026-01-05T09:00:00+00:00', '2026-01-05T10:00:00+00:00',
+                     '+1 call with -team', 'finished')",
+            rusqlite::params![user.id, project.id],
+        )
+        .unwrap();
+        session_service::replace_tags(&conn, conn.last_insert_rowid(), &["-urgent".to_string()]).unwrap();
+
+        let options = CsvExportOptions {
+            columns: vec![CsvColumn::Project, CsvColumn::Client, CsvColumn::Description, CsvColumn::Tags],
+            ..Default::default()
+        };
+
+        let path = std::env::temp_dir().join(format!("trackly-export-{}.csv", std::process::id()));
+        export_sessions_csv(&conn, user.id, &options, &path).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            content.lines().nth(1),
+            Some("'=SUM(A1:A9),'@Acme,'+1 call with -team,'-urgent")
+        );
+
+        assert_eq!(spreadsheet_safe("plain -text"), "plain -text");
+    }
+}
@@ -0,0 +1,495 @@
+use rusqlite::{params, Connection};
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::collections::{HashMap, HashSet};
+
+use crate::error::{AppError, AppResult};
+use crate::models::import::{
+    ImportFormat,
+    ImportOptions,
+    ImportReport,
+    RowIssue,
+};
+use crate::services::{export_service, project_service, session_service};
+
+const NEW_PROJECT_COLOR: &str = "#2e86ab";
+
+// Tried in order when a timestamp isn't RFC 3339 and no format is given.
+const FALLBACK_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+];
+
+type Record = HashMap<String, String>;
+
+struct ParsedRow {
+    row: usize,
+    project: Option<String>,
+    start: String,
+    end: String,
+    description: Option<String>,
+    tags: Vec<String>,
+}
+
+/* ===========================
+   IMPORT SESSIONS
+=========================== */
+
+/// Validates every row, then writes the valid ones in a single
+/// transaction unless `dry_run` is set or a blocking issue was found.
+pub fn import_sessions(
+    conn: &mut Connection,
+    user_id: i64,
+    content: &str,
+    options: &ImportOptions,
+) -> AppResult<ImportReport> {
+
+    let tz = export_service::parse_timezone(options.timezone.as_deref())?;
+
+    if let Some(ref format) = options.date_format {
+        export_service::validate_date_format(format)?;
+    }
+
+    let records = match options.format {
+        ImportFormat::Csv => read_csv(content)?,
+        ImportFormat::Json => read_json(content, &options.tag_separator)?,
+    };
+
+    let mut report = ImportReport {
+        dry_run: options.dry_run,
+        total_rows: records.len(),
+        ..Default::default()
+    };
+
+    /* ---------- Parse ---------- */
+
+    let mut rows = Vec::new();
+
+    for (index, record) in records.iter().enumerate() {
+        match parse_row(index + 1, record, options, &tz) {
+            Ok(row) => rows.push(row),
+            Err(e) => report.errors.push(RowIssue {
+                row: index + 1,
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    /* ---------- Projects ---------- */
+
+    let existing_projects: HashMap<String, i64> =
+        project_service::get_projects(conn, user_id, true)?
+            .into_iter()
+            .map(|p| (p.name.to_lowercase(), p.id))
+            .collect();
+
+    let mut missing: Vec<String> = Vec::new();
+
+    rows.retain(|row| {
+        let Some(ref name) = row.project else { return true };
+
+        if existing_projects.contains_key(&name.to_lowercase()) {
+            return true;
+        }
+
+        if !options.create_missing_projects {
+            report.errors.push(RowIssue {
+                row: row.row,
+                message: format!("Unknown project: {}", name),
+            });
+            return false;
+        }
+
+        if !missing.iter().any(|m| m.to_lowercase() == name.to_lowercase()) {
+            missing.push(name.clone());
+        }
+
+        true
+    });
+
+    report.projects_to_create = missing.clone();
+
+    /* ---------- Duplicates & overlaps ---------- */
+
+    let mut seen: HashSet<(String, String, Option<String>)> = HashSet::new();
+    let mut accepted: Vec<ParsedRow> = Vec::new();
+
+    for row in rows {
+        let key = (
+            row.start.clone(),
+            row.end.clone(),
+            row.project.as_ref().map(|p| p.to_lowercase()),
+        );
+
+        // A project that doesn't exist yet can't have stored sessions.
+        let in_db = match row.project {
+            Some(ref name) => match existing_projects.get(&name.to_lowercase()) {
+                Some(id) => exists_in_db(conn, user_id, &row.start, &row.end, Some(*id))?,
+                None => false,
+            },
+            None => exists_in_db(conn, user_id, &row.start, &row.end, None)?,
+        };
+
+        if in_db || seen.contains(&key) {
+            report.duplicates.push(RowIssue {
+                row: row.row,
+                message: "Duplicate session".into(),
+            });
+            continue;
+        }
+
+        if let Some(id) =
+            session_service::find_overlap(conn, user_id, &row.start, &row.end, None)?
+        {
+            report.overlaps.push(RowIssue {
+                row: row.row,
+                message: format!("Overlaps existing session {}", id),
+            });
+        }
+
+        seen.insert(key);
+        accepted.push(row);
+    }
+
+    accepted.sort_by(|a, b| a.start.cmp(&b.start));
+
+    // Compare each row with the latest-ending row before it, not just its
+    // neighbour: a long row can overlap several that start after it.
+    let mut furthest: Option<&ParsedRow> = None;
+
+    for row in &accepted {
+        match furthest {
+            Some(previous) if row.start < previous.end => {
+                report.overlaps.push(RowIssue {
+                    row: row.row,
+                    message: format!("Overlaps row {}", previous.row),
+                });
+
+                if row.end > previous.end {
+                    furthest = Some(row);
+                }
+            }
+            _ => furthest = Some(row),
+        }
+    }
+
+    report.overlaps.sort_by_key(|issue| issue.row);
+    report.valid_rows = accepted.len();
+
+    let blocked = !report.errors.is_empty()
+        || (!options.allow_overlap && !report.overlaps.is_empty());
+
+    if options.dry_run || blocked {
+        return Ok(report);
+    }
+
+    /* ---------- Write ---------- */
+
+    let tx = conn.transaction()?;
+
+    let mut project_ids = existing_projects;
+
+    for name in &missing {
+        let project = project_service::create_project(
+            &tx,
+            user_id,
+            name,
+            NEW_PROJECT_COLOR,
+            None,
+            None,
+        )?;
+        project_ids.insert(name.to_lowercase(), project.id);
+    }
+
+    for row in &accepted {
+        let project_id = row
+            .project
+            .as_ref()
+            .and_then(|p| project_ids.get(&p.to_lowercase()).copied());
+
+        tx.execute(
+            "INSERT INTO sessions (user_id, project_id, start_time, end_time, description, status)
+             VALUES (?1, ?2, ?3, ?4, ?5, 'finished')",
+            params![user_id, project_id, row.start, row.end, row.description],
+        )?;
+
+        let id = tx.last_insert_rowid();
+
+        session_service::replace_tags(&tx, id, &row.tags)?;
+    }
+
+    tx.commit()?;
+
+    report.imported = accepted.len();
+
+    Ok(report)
+}
+
+/* ===========================
+   READERS
+=========================== */
+
+fn read_csv(content: &str) -> AppResult<Vec<Record>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|e| AppError::invalid(format!("Invalid CSV: {}", e)))?
+        .clone();
+
+    let mut records = Vec::new();
+
+    for result in reader.records() {
+        let record =
+            result.map_err(|e| AppError::invalid(format!("Invalid CSV: {}", e)))?;
+
+        records.push(
+            headers
+                .iter()
+                .zip(record.iter())
+                .map(|(h, v)| (h.to_string(), v.to_string()))
+                .collect(),
+        );
+    }
+
+    Ok(records)
+}
+
+/// Expects an array of flat objects. Arrays of strings (e.g. tags) are
+/// joined with `tag_separator` so both formats share one row parser.
+fn read_json(
+    content: &str,
+    tag_separator: &str,
+) -> AppResult<Vec<Record>> {
+
+    let value: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| AppError::invalid(format!("Invalid JSON: {}", e)))?;
+
+    let items = value
+        .as_array()
+        .ok_or_else(|| AppError::invalid("Expected a JSON array of sessions"))?;
+
+    let mut records = Vec::new();
+
+    for item in items {
+        let object = item
+            .as_object()
+            .ok_or_else(|| AppError::invalid("Expected every JSON entry to be an object"))?;
+
+        let record = object
+            .iter()
+            .filter_map(|(key, value)| {
+                let text = match value {
+                    serde_json::Value::Null => return None,
+                    serde_json::Value::String(s) => s.clone(),
+                    serde_json::Value::Array(values) => values
+                        .iter()
+                        .filter_map(|v| v.as_str())
+                        .collect::<Vec<_>>()
+                        .join(tag_separator),
+                    other => other.to_string(),
+                };
+                Some((key.clone(), text))
+            })
+            .collect();
+
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/* ===========================
+   HELPERS
+=========================== */
+
+fn parse_row(
+    row: usize,
+    record: &Record,
+    options: &ImportOptions,
+    tz: &Tz,
+) -> AppResult<ParsedRow> {
+
+    let field = |column: Option<&str>| -> Option<String> {
+        column
+            .and_then(|c| record.get(c))
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    let mapping = &options.mapping;
+
+    let start_raw = field(Some(&mapping.start))
+        .ok_or_else(|| AppError::invalid(format!("Missing {}", mapping.start)))?;
+    let end_raw = field(Some(&mapping.end))
+        .ok_or_else(|| AppError::invalid(format!("Missing {}", mapping.end)))?;
+
+    let start = parse_timestamp(&start_raw, options.date_format.as_deref(), tz)?;
+    let end = parse_timestamp(&end_raw, options.date_format.as_deref(), tz)?;
+
+    if end <= start {
+        return Err(AppError::invalid("End time must be after start time"));
+    }
+
+    let tags = field(mapping.tags.as_deref())
+        .map(|raw| {
+            raw.split(options.tag_separator.as_str())
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(ParsedRow {
+        row,
+        project: field(mapping.project.as_deref()),
+        start: start.to_rfc3339(),
+        end: end.to_rfc3339(),
+        description: field(mapping.description.as_deref()),
+        tags,
+    })
+}
+
+fn parse_timestamp(
+    value: &str,
+    format: Option<&str>,
+    tz: &Tz,
+) -> AppResult<DateTime<Utc>> {
+
+    if let Ok(t) = DateTime::parse_from_rfc3339(value) {
+        return Ok(t.with_timezone(&Utc));
+    }
+
+    let naive = match format {
+        Some(f) => NaiveDateTime::parse_from_str(value, f).ok(),
+        None => FALLBACK_FORMATS
+            .iter()
+            .find_map(|f| NaiveDateTime::parse_from_str(value, f).ok()),
+    }
+    .ok_or_else(|| AppError::invalid(format!("Invalid timestamp: {}", value)))?;
+
+    tz.from_local_datetime(&naive)
+        .single()
+        .map(|t| t.with_timezone(&Utc))
+        .ok_or_else(|| AppError::invalid(format!("Ambiguous or skipped local time: {}", value)))
+}
+
+fn exists_in_db(
+    conn: &Connection,
+    user_id: i64,
+    start: &str,
+    end: &str,
+    project_id: Option<i64>,
+) -> AppResult<bool> {
+
+    conn.query_row(
+        "SELECT EXISTS (
+             SELECT 1 FROM sessions
+             WHERE user_id = ?1
+               AND status = 'finished'
+               AND start_time = ?2
+               AND end_time = ?3
+               AND project_id IS ?4
+         )",
+        params![user_id, start, end, project_id],
+        |row| row.get(0),
+    )
+    .map_err(AppError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_in_memory;
+
+    const CSV_HEADER: &str = "Project,Start,End,Description,Tags\n";
+
+    fn setup() -> (Connection, i64) {
+        let conn = open_in_memory();
+        conn.execute(
+            "INSERT INTO users (email, password_hash) VALUES ('a@example.com', 'x')",
+            [],
+        )
+        .unwrap();
+        let user_id = conn.last_insert_rowid();
+        (conn, user_id)
+    }
+
+    fn csv(rows: &[(&str, &str)]) -> String {
+        let mut out = CSV_HEADER.to_string();
+        for (start, end) in rows {
+            out.push_str(&format!("Web,2026-03-01 {},2026-03-01 {},,\n", start, end));
+        }
+        out
+    }
+
+    fn write_options() -> ImportOptions {
+        ImportOptions { dry_run: false, ..Default::default() }
+    }
+
+    fn session_count(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM sessions", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn reports_overlaps_with_non_adjacent_rows() {
+        let (mut conn, user_id) = setup();
+
+        // Row 1 covers both later rows, which don't overlap each other.
+        let content = csv(&[("01:00", "10:00"), ("02:00", "03:00"), ("04:00", "05:00")]);
+
+        let report = import_sessions(&mut conn, user_id, &content, &write_options()).unwrap();
+
+        let rows: Vec<(usize, &str)> = report
+            .overlaps
+            .iter()
+            .map(|issue| (issue.row, issue.message.as_str()))
+            .collect();
+
+        assert_eq!(rows, vec![(2, "Overlaps row 1"), (3, "Overlaps row 1")]);
+        assert_eq!(session_count(&conn), 0);
+    }
+
+    #[test]
+    fn back_to_back_rows_do_not_overlap() {
+        let (mut conn, user_id) = setup();
+
+        let content = csv(&[("03:00", "04:00"), ("01:00", "02:00"), ("02:00", "03:00")]);
+
+        let report = import_sessions(&mut conn, user_id, &content, &write_options()).unwrap();
+
+        assert!(report.overlaps.is_empty());
+        assert_eq!(report.imported, 3);
+        assert_eq!(session_count(&conn), 3);
+    }
+
+    #[test]
+    fn reports_overlaps_with_stored_sessions() {
+        let (mut conn, user_id) = setup();
+
+        import_sessions(&mut conn, user_id, &csv(&[("01:00", "03:00")]), &write_options()).unwrap();
+
+        let report = import_sessions(
+            &mut conn,
+            user_id,
+            &csv(&[("02:00", "04:00")]),
+            &write_options(),
+        )
+        .unwrap();
+
+        assert_eq!(report.overlaps.len(), 1);
+        assert!(report.overlaps[0].message.starts_with("Overlaps existing session"));
+        assert_eq!(session_count(&conn), 1);
+
+        // Unless overlaps are allowed explicitly.
+        let options = ImportOptions { allow_overlap: true, ..write_options() };
+        import_sessions(&mut conn, user_id, &csv(&[("02:00", "04:00")]), &options).unwrap();
+
+        assert_eq!(session_count(&conn), 2);
+    }
+}
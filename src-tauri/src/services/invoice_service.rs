@@ -0,0 +1,715 @@
+use rusqlite::{params, Connection, Row};
+use chrono::Utc;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::error::{AppError, AppResult};
+use crate::invoice_render;
+use crate::models::invoice::{
+    Invoice,
+    InvoiceDraft,
+    InvoiceGrouping,
+    InvoiceLine,
+    InvoiceRequest,
+};
+use crate::services::rate_service::{amount_cents_sql, rate_for_session_sql, BILLABLE_SQL};
+use crate::services::session_service::{net_seconds_sql, parse_time};
+use crate::services::stats_service::TOP_LEVEL_JOIN_SQL;
+use crate::services::{client_service, export_service};
+
+const NUMBER_PREFIX: &str = "INV-";
+
+/* ===========================
+   DRAFT
+=========================== */
+
+/// Collects the client's finished, billable sessions in the period that
+/// aren't on an invoice yet, and prices them. Nothing is stored.
+pub fn draft_invoice(
+    conn: &Connection,
+    user_id: i64,
+    request: &InvoiceRequest,
+) -> AppResult<InvoiceDraft> {
+
+    let client = client_service::get_client_by_id(conn, user_id, request.client_id)?;
+
+    let from = parse_time(&request.from)
+        .map_err(|e| AppError::field("from", e.to_string()))?;
+    let to = parse_time(&request.to)
+        .map_err(|e| AppError::field("to", e.to_string()))?;
+
+    if to <= from {
+        return Err(AppError::field("to", "End of the period must be after its start"));
+    }
+
+    let sql = format!(
+        "SELECT s.id, {net}, hr.amount_cents, hr.currency,
+                p.name, p.parent_id, r.name,
+                (SELECT MIN(t.tag) FROM session_tags t WHERE t.session_id = s.id),
+                {amount}
+         FROM sessions s
+         {join}
+         LEFT JOIN hourly_rates hr ON hr.id = {rate}
+         WHERE s.user_id = ?1
+           AND s.status = 'finished'
+           AND s.invoice_id IS NULL
+           AND s.start_time >= ?2
+           AND s.start_time < ?3
+           AND r.client_id = ?4
+           AND {billable}
+         ORDER BY s.start_time ASC",
+        net = net_seconds_sql(),
+        amount = amount_cents_sql(&net_seconds_sql(), "hr.amount_cents"),
+        join = TOP_LEVEL_JOIN_SQL,
+        rate = rate_for_session_sql(),
+        billable = BILLABLE_SQL,
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+
+    let rows = stmt
+        .query_map(
+            params![user_id, from.to_rfc3339(), to.to_rfc3339(), client.id],
+            |row| read_billable_session(row, request.group_by),
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if rows.is_empty() {
+        return Err(AppError::invalid(
+            "No unbilled sessions for this client and period",
+        ));
+    }
+
+    let unrated = rows.iter().filter(|s| s.rate.is_none()).count();
+
+    if unrated > 0 {
+        return Err(AppError::invalid(format!(
+            "{} session(s) have no hourly rate; set a rate for the client or its projects first",
+            unrated
+        )));
+    }
+
+    let currencies: BTreeSet<&str> = rows
+        .iter()
+        .filter_map(|s| s.rate.as_ref().map(|(_, currency)| currency.as_str()))
+        .collect();
+
+    if currencies.len() > 1 {
+        return Err(AppError::invalid(format!(
+            "Sessions are billed in several currencies ({}); invoice them separately",
+            currencies.into_iter().collect::<Vec<_>>().join(", ")
+        )));
+    }
+
+    let currency = currencies.into_iter().next().unwrap_or_default().to_string();
+
+    // (label, rate) -> (sessions, seconds, amount). Amounts are rounded
+    // per session, like earnings, and then added up.
+    let mut groups: BTreeMap<(String, i64), (i64, i64, i64)> = BTreeMap::new();
+
+    for session in &rows {
+        let rate = session.rate.as_ref().map(|(amount, _)| *amount).unwrap_or(0);
+        let entry = groups.entry((session.label.clone(), rate)).or_default();
+        entry.0 += 1;
+        entry.1 += session.seconds;
+        entry.2 += session.amount_cents;
+    }
+
+    let lines: Vec<InvoiceLine> = groups
+        .into_iter()
+        .map(|((description, rate_cents), (session_count, seconds, amount_cents))| InvoiceLine {
+            description,
+            session_count,
+            seconds,
+            rate_cents,
+            amount_cents,
+        })
+        .collect();
+
+    Ok(InvoiceDraft {
+        client_id: Some(client.id),
+        client_name: client.name,
+        period_from: from.to_rfc3339(),
+        period_to: to.to_rfc3339(),
+        currency,
+        total_cents: lines.iter().map(|l| l.amount_cents).sum(),
+        lines,
+        session_ids: rows.iter().map(|s| s.id).collect(),
+    })
+}
+
+/* ===========================
+   CREATE
+=========================== */
+
+/// Stores the draft under the next number, marks its sessions as
+/// invoiced and writes `<number>.html` and `<number>.pdf` to `output_dir`.
+/// Existing files are never overwritten.
+pub fn create_invoice(
+    conn: &Connection,
+    user_id: i64,
+    request: &InvoiceRequest,
+    output_dir: &Path,
+) -> AppResult<Invoice> {
+
+    let tz = export_service::parse_timezone(request.timezone.as_deref())?;
+    let draft = draft_invoice(conn, user_id, request)?;
+
+    let notes = request
+        .notes
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string);
+
+    let tx = conn.unchecked_transaction()?;
+
+    let sequence: i64 = tx.query_row(
+        "SELECT COALESCE(MAX(sequence), 0) + 1 FROM invoices WHERE user_id = ?1",
+        params![user_id],
+        |row| row.get(0),
+    )?;
+
+    let number = format!("{}{:04}", NUMBER_PREFIX, sequence);
+    let issued_at = Utc::now().to_rfc3339();
+
+    let html_path = output_dir.join(format!("{}.html", number));
+    let pdf_path = output_dir.join(format!("{}.pdf", number));
+
+    for path in [&html_path, &pdf_path] {
+        if path.exists() {
+            return Err(AppError::Conflict(format!(
+                "{} already exists; choose another folder",
+                path.display()
+            )));
+        }
+    }
+
+    tx.execute(
+        "INSERT INTO invoices
+            (user_id, client_id, sequence, number, client_name, period_from,
+             period_to, currency, total_cents, notes, issued_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            user_id,
+            draft.client_id,
+            sequence,
+            number,
+            draft.client_name,
+            draft.period_from,
+            draft.period_to,
+            draft.currency,
+            draft.total_cents,
+            notes,
+            issued_at,
+        ],
+    )?;
+
+    let invoice_id = tx.last_insert_rowid();
+
+    for (position, line) in draft.lines.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO invoice_lines
+                (invoice_id, position, description, session_count, seconds,
+                 rate_cents, amount_cents)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                invoice_id,
+                position as i64,
+                line.description,
+                line.session_count,
+                line.seconds,
+                line.rate_cents,
+                line.amount_cents,
+            ],
+        )?;
+    }
+
+    // The guard on `invoice_id` keeps a session from landing on two invoices.
+    let mut marked = 0;
+
+    for session_id in &draft.session_ids {
+        marked += tx.execute(
+            "UPDATE sessions SET invoice_id = ?1
+             WHERE id = ?2 AND user_id = ?3 AND invoice_id IS NULL",
+            params![invoice_id, session_id, user_id],
+        )?;
+    }
+
+    if marked != draft.session_ids.len() {
+        return Err(AppError::Conflict(
+            "Some sessions were invoiced in the meantime; try again".into(),
+        ));
+    }
+
+    let mut invoice = Invoice {
+        id: invoice_id,
+        number,
+        issued_at,
+        notes,
+        voided_at: None,
+        html_path: None,
+        pdf_path: None,
+        draft,
+    };
+
+    // Files are staged next to their final names before the commit, so a
+    // full disk or a read-only folder fails the whole invoice. Once it is
+    // stored, only the renames are left.
+    let files = [
+        StagedFile::new(html_path, invoice_render::html(&invoice, &tz).into_bytes()),
+        StagedFile::new(pdf_path, invoice_render::pdf(&invoice, &tz)),
+    ];
+
+    let stored = stage_files(&files).and_then(|_| tx.commit().map_err(AppError::from));
+
+    if let Err(e) = stored {
+        discard_files(&files);
+        return Err(e);
+    }
+
+    // The invoice exists now, so a failed rename only leaves it without
+    // that file; the error is logged rather than returned.
+    let publish = |file: &StagedFile| match file.publish() {
+        Ok(()) => Some(file.path.to_string_lossy().to_string()),
+        Err(e) => {
+            log::warn!("Invoice {} saved without {}: {}", invoice.number, file.path.display(), e);
+            None
+        }
+    };
+
+    let html_path = publish(&files[0]);
+    let pdf_path = publish(&files[1]);
+
+    invoice.html_path = html_path;
+    invoice.pdf_path = pdf_path;
+
+    discard_files(&files);
+
+    conn.execute(
+        "UPDATE invoices SET html_path = ?1, pdf_path = ?2 WHERE id = ?3",
+        params![invoice.html_path, invoice.pdf_path, invoice_id],
+    )?;
+
+    Ok(invoice)
+}
+
+/* ===========================
+   GET INVOICES
+=========================== */
+
+/// Newest first, voided ones included.
+pub fn get_invoices(
+    conn: &Connection,
+    user_id: i64,
+) -> AppResult<Vec<Invoice>> {
+
+    let sql = format!(
+        "{} WHERE user_id = ?1 ORDER BY sequence DESC",
+        INVOICE_SELECT_SQL
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+
+    let mut invoices = stmt
+        .query_map(params![user_id], read_invoice)?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for invoice in &mut invoices {
+        invoice.draft.lines = load_lines(conn, invoice.id)?;
+    }
+
+    Ok(invoices)
+}
+
+pub fn get_invoice(
+    conn: &Connection,
+    user_id: i64,
+    invoice_id: i64,
+) -> AppResult<Invoice> {
+
+    let sql = format!("{} WHERE id = ?1 AND user_id = ?2", INVOICE_SELECT_SQL);
+
+    let mut invoice = conn
+        .query_row(&sql, params![invoice_id, user_id], read_invoice)
+        .map_err(|_| AppError::NotFound("Invoice"))?;
+
+    invoice.draft.lines = load_lines(conn, invoice.id)?;
+
+    Ok(invoice)
+}
+
+/* ===========================
+   VOID
+=========================== */
+
+/// Keeps the invoice and its number, but releases its sessions so they
+/// can be billed again.
+pub fn void_invoice(
+    conn: &Connection,
+    user_id: i64,
+    invoice_id: i64,
+) -> AppResult<Invoice> {
+
+    let invoice = get_invoice(conn, user_id, invoice_id)?;
+
+    if invoice.voided_at.is_some() {
+        return Err(AppError::invalid("Invoice already voided"));
+    }
+
+    let tx = conn.unchecked_transaction()?;
+
+    tx.execute(
+        "UPDATE invoices SET voided_at = ?1 WHERE id = ?2 AND user_id = ?3",
+        params![Utc::now().to_rfc3339(), invoice_id, user_id],
+    )?;
+
+    tx.execute(
+        "UPDATE sessions SET invoice_id = NULL
+         WHERE invoice_id = ?1 AND user_id = ?2",
+        params![invoice_id, user_id],
+    )?;
+
+    tx.commit()?;
+
+    get_invoice(conn, user_id, invoice_id)
+}
+
+/* ===========================
+   FILES
+=========================== */
+
+/// Default output folder, one per account so numbers can't collide.
+pub fn invoice_dir(app: &AppHandle, user_id: i64) -> AppResult<PathBuf> {
+    let app_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(app_dir.join("invoices").join(user_id.to_string()))
+}
+
+/// A rendered file, first written under a hidden temporary name.
+struct StagedFile {
+    path: PathBuf,
+    staging: PathBuf,
+    content: Vec<u8>,
+}
+
+impl StagedFile {
+    fn new(path: PathBuf, content: Vec<u8>) -> Self {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let staging = path.with_file_name(format!(".{}.part", name));
+
+        Self { path, staging, content }
+    }
+
+    /// Moves the file into place, refusing to replace an existing one.
+    fn publish(&self) -> std::io::Result<()> {
+        if self.path.exists() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                "file already exists",
+            ));
+        }
+
+        std::fs::rename(&self.staging, &self.path)
+    }
+}
+
+fn stage_files(files: &[StagedFile]) -> AppResult<()> {
+    for file in files {
+        if let Some(dir) = file.staging.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        std::fs::write(&file.staging, &file.content)?;
+    }
+
+    Ok(())
+}
+
+/// Removes whatever staging files are left.
+fn discard_files(files: &[StagedFile]) {
+    for file in files {
+        let _ = std::fs::remove_file(&file.staging);
+    }
+}
+
+/* ===========================
+   HELPERS
+=========================== */
+
+struct BillableSession {
+    id: i64,
+    seconds: i64,
+    /// `(amount_cents, currency)`, if any rate applies.
+    rate: Option<(i64, String)>,
+    /// Value of the session, rounded to the cent.
+    amount_cents: i64,
+    label: String,
+}
+
+fn read_billable_session(
+    row: &Row,
+    group_by: InvoiceGrouping,
+) -> rusqlite::Result<BillableSession> {
+
+    let amount: Option<i64> = row.get(2)?;
+    let currency: Option<String> = row.get(3)?;
+
+    let label = match group_by {
+        InvoiceGrouping::Project => {
+            let project: String = row.get(4)?;
+            let parent_id: Option<i64> = row.get(5)?;
+
+            match parent_id {
+                Some(_) => format!("{} / {}", row.get::<_, String>(6)?, project),
+                None => project,
+            }
+        }
+        InvoiceGrouping::Tag => row
+            .get::<_, Option<String>>(7)?
+            .unwrap_or_else(|| "Untagged".into()),
+    };
+
+    Ok(BillableSession {
+        id: row.get(0)?,
+        seconds: row.get(1)?,
+        rate: amount.zip(currency),
+        amount_cents: row.get::<_, Option<i64>>(8)?.unwrap_or(0),
+        label,
+    })
+}
+
+const INVOICE_SELECT_SQL: &str =
+    "SELECT id, number, issued_at, notes, voided_at, html_path, pdf_path,
+            client_id, client_name, period_from, period_to, currency, total_cents
+     FROM invoices";
+
+fn read_invoice(row: &Row) -> rusqlite::Result<Invoice> {
+    Ok(Invoice {
+        id: row.get(0)?,
+        number: row.get(1)?,
+        issued_at: row.get(2)?,
+        notes: row.get(3)?,
+        voided_at: row.get(4)?,
+        html_path: row.get(5)?,
+        pdf_path: row.get(6)?,
+        draft: InvoiceDraft {
+            client_id: row.get(7)?,
+            client_name: row.get(8)?,
+            period_from: row.get(9)?,
+            period_to: row.get(10)?,
+            currency: row.get(11)?,
+            total_cents: row.get(12)?,
+            lines: Vec::new(),
+            session_ids: Vec::new(),
+        },
+    })
+}
+
+fn load_lines(
+    conn: &Connection,
+    invoice_id: i64,
+) -> AppResult<Vec<InvoiceLine>> {
+
+    let mut stmt = conn.prepare(
+        "SELECT description, session_count, seconds, rate_cents, amount_cents
+         FROM invoice_lines
+         WHERE invoice_id = ?1
+         ORDER BY position ASC"
+    )?;
+
+    let rows = stmt
+        .query_map(params![invoice_id], |row| {
+            Ok(InvoiceLine {
+                description: row.get(0)?,
+                session_count: row.get(1)?,
+                seconds: row.get(2)?,
+                rate_cents: row.get(3)?,
+                amount_cents: row.get(4)?,
+            })
+        })?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(AppError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_in_memory;
+    use crate::models::rate::RateScope;
+    use crate::models::stats::StatsFilter;
+    use crate::services::{project_service, rate_service, stats_service, user_service};
+
+    struct Fixture {
+        conn: Connection,
+        user_id: i64,
+        client_id: i64,
+        project_id: i64,
+        output_dir: PathBuf,
+    }
+
+    fn fixture(name: &str, rate_cents: i64) -> Fixture {
+        let conn = open_in_memory();
+        let user = user_service::create_user(&conn, "a@example.com", "Old-passphrase-42").unwrap();
+        let client = client_service::create_client(&conn, user.id, "Acme", None).unwrap();
+        let project = project_service::create_project(&conn, user.id, "Web", "#ff0000", None, Some(client.id)).unwrap();
+
+        rate_service::set_rate(
+            &conn,
+            user.id,
+            RateScope::Client { client_id: client.id },
+            rate_cents,
+            "EUR",
+            Some("2026-01-01T00:00:00Z"),
+        )
+        .unwrap();
+
+        let output_dir = std::env::temp_dir()
+            .join(format!("trackly-invoices-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&output_dir);
+
+        Fixture {
+            conn,
+            user_id: user.id,
+            client_id: client.id,
+            project_id: project.id,
+            output_dir,
+        }
+    }
+
+    impl Fixture {
+        fn add_session(&self, start: &str, end: &str) {
+            self.conn
+                .execute(
+                    "INSERT INTO sessions (user_id, project_id, start_time, end_time, status)
+                     VALUES (?1, ?2, ?3, ?4, 'finished')",
+                    params![self.user_id, self.project_id, start, end],
+                )
+                .unwrap();
+        }
+
+        fn request(&self) -> InvoiceRequest {
+            InvoiceRequest {
+                client_id: self.client_id,
+                from: "2026-01-01T00:00:00Z".into(),
+                to: "2026-02-01T00:00:00Z".into(),
+                group_by: InvoiceGrouping::Project,
+                notes: None,
+                timezone: None,
+            }
+        }
+
+        fn create(&self) -> AppResult<Invoice> {
+            create_invoice(&self.conn, self.user_id, &self.request(), &self.output_dir)
+        }
+    }
+
+    #[test]
+    fn totals_round_each_session_like_earnings() {
+        let f = fixture("totals", 100);
+
+        // 20 minutes and a second each: 33.36 cents, billed as 33.
+        f.add_session("2026-01-05T09:00:00+00:00", "2026-01-05T09:20:01+00:00");
+        f.add_session("2026-01-06T09:00:00+00:00", "2026-01-06T09:20:01+00:00");
+
+        let draft = draft_invoice(&f.conn, f.user_id, &f.request()).unwrap();
+
+        assert_eq!(draft.lines.len(), 1);
+        assert_eq!(draft.lines[0].session_count, 2);
+        assert_eq!(draft.lines[0].seconds, 2402);
+        assert_eq!(draft.lines[0].amount_cents, 66);
+        assert_eq!(draft.total_cents, 66);
+
+        let filter = StatsFilter {
+            client_id: Some(f.client_id),
+            ..Default::default()
+        };
+        let earnings = stats_service::get_earnings_by_project(&f.conn, f.user_id, &filter, true).unwrap();
+
+        assert_eq!(earnings.iter().map(|e| e.amount_cents).sum::<i64>(), draft.total_cents);
+    }
+
+    #[test]
+    fn numbers_are_sequential_and_voiding_releases_sessions() {
+        let f = fixture("numbers", 6000);
+        f.add_session("2026-01-05T09:00:00+00:00", "2026-01-05T10:00:00+00:00");
+
+        let first = f.create().unwrap();
+        assert_eq!(first.number, "INV-0001");
+        assert_eq!(first.draft.total_cents, 6000);
+        assert!(Path::new(first.pdf_path.as_deref().unwrap()).exists());
+
+        // Everything in the period is billed.
+        assert!(f.create().is_err());
+
+        let voided = void_invoice(&f.conn, f.user_id, first.id).unwrap();
+        assert!(voided.voided_at.is_some());
+        assert!(void_invoice(&f.conn, f.user_id, first.id).is_err());
+
+        // The voided number isn't reused.
+        let second = f.create().unwrap();
+        assert_eq!(second.number, "INV-0002");
+        assert_eq!(second.draft.total_cents, 6000);
+
+        let numbers: Vec<String> = get_invoices(&f.conn, f.user_id)
+            .unwrap()
+            .into_iter()
+            .map(|i| i.number)
+            .collect();
+        assert_eq!(numbers, ["INV-0002", "INV-0001"]);
+
+        let _ = std::fs::remove_dir_all(&f.output_dir);
+    }
+
+    #[test]
+    fn existing_files_block_the_invoice() {
+        let f = fixture("files", 6000);
+        f.add_session("2026-01-05T09:00:00+00:00", "2026-01-05T10:00:00+00:00");
+
+        std::fs::create_dir_all(&f.output_dir).unwrap();
+        std::fs::write(f.output_dir.join("INV-0001.html"), "mine").unwrap();
+
+        assert!(matches!(f.create(), Err(AppError::Conflict(_))));
+        assert_eq!(std::fs::read_to_string(f.output_dir.join("INV-0001.html")).unwrap(), "mine");
+
+        // Nothing was stored, so the sessions are still billable.
+        assert!(get_invoices(&f.conn, f.user_id).unwrap().is_empty());
+        assert!(draft_invoice(&f.conn, f.user_id, &f.request()).is_ok());
+
+        let _ = std::fs::remove_dir_all(&f.output_dir);
+    }
+
+    #[test]
+    fn unwritable_folders_store_nothing() {
+        let f = fixture("unwritable", 6000);
+        f.add_session("2026-01-05T09:00:00+00:00", "2026-01-05T10:00:00+00:00");
+
+        // A plain file where the folder should be.
+        std::fs::write(&f.output_dir, "not a folder").unwrap();
+
+        assert!(f.create().is_err());
+        assert!(get_invoices(&f.conn, f.user_id).unwrap().is_empty());
+        assert!(draft_invoice(&f.conn, f.user_id, &f.request()).is_ok());
+
+        std::fs::remove_file(&f.output_dir).unwrap();
+
+        let invoice = f.create().unwrap();
+        assert_eq!(invoice.number, "INV-0001");
+        assert!(invoice.html_path.is_some() && invoice.pdf_path.is_some());
+
+        // No staging files are left behind.
+        let names: Vec<String> = std::fs::read_dir(&f.output_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names.len(), 2);
+        assert!(names.iter().all(|name| !name.starts_with('.')));
+
+        let _ = std::fs::remove_dir_all(&f.output_dir);
+    }
+}
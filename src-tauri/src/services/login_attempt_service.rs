@@ -0,0 +1,250 @@
+use rusqlite::{params, Connection};
+use chrono::{DateTime, Duration, Utc};
+
+use crate::error::{AppError, AppResult};
+use crate::models::user::{LoginAttempt, LoginOutcome};
+
+// Failures allowed before backoff starts; each further failure doubles
+// the wait until LOCKOUT_AFTER, which locks the email for LOCKOUT_MINUTES.
+const FREE_ATTEMPTS: i64 = 3;
+const BASE_BACKOFF_SECONDS: i64 = 5;
+const LOCKOUT_AFTER: i64 = 10;
+const LOCKOUT_MINUTES: i64 = 30;
+
+const DEFAULT_ATTEMPT_LIMIT: u32 = 50;
+const MAX_ATTEMPT_LIMIT: u32 = 500;
+
+/* ===========================
+   THROTTLE
+=========================== */
+
+/// Rejects the attempt while the email is backing off or locked out.
+pub fn check_throttle(
+    conn: &Connection,
+    email: &str,
+) -> AppResult<()> {
+
+    let result = conn.query_row(
+        "SELECT locked_until FROM login_throttle WHERE email = ?1",
+        params![email],
+        |row| row.get::<_, Option<String>>(0),
+    );
+
+    let locked_until = match result {
+        Ok(value) => value,
+        Err(rusqlite::Error::QueryReturnedNoRows) => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    let now = Utc::now();
+
+    if let Some(until) = locked_until
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Utc))
+        .filter(|t| *t > now)
+    {
+        return Err(AppError::RateLimited {
+            retry_after_seconds: (until - now).num_seconds().max(1) as u64,
+        });
+    }
+
+    Ok(())
+}
+
+/* ===========================
+   RECORD
+=========================== */
+
+/// Appends the attempt to the audit log and updates the email's
+/// throttle: failures extend the backoff, a success clears it.
+pub fn record_attempt(
+    conn: &Connection,
+    email: &str,
+    user_id: Option<i64>,
+    outcome: LoginOutcome,
+) -> AppResult<()> {
+
+    let now = Utc::now();
+
+    let tx = conn.unchecked_transaction()?;
+
+    tx.execute(
+        "INSERT INTO login_attempts (email, user_id, outcome, attempted_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![email, user_id, outcome.as_str(), now.to_rfc3339()],
+    )?;
+
+    match outcome {
+        LoginOutcome::Succeeded => {
+            tx.execute(
+                "DELETE FROM login_throttle WHERE email = ?1",
+                params![email],
+            )?;
+        }
+        LoginOutcome::InvalidCredentials => {
+            let failed_count: i64 = tx.query_row(
+                "INSERT INTO login_throttle (email, failed_count)
+                 VALUES (?1, 1)
+                 ON CONFLICT(email) DO UPDATE
+                 SET failed_count = failed_count + 1
+                 RETURNING failed_count",
+                params![email],
+                |row| row.get(0),
+            )?;
+
+            let locked_until = lock_duration(failed_count)
+                .map(|wait| (now + wait).to_rfc3339());
+
+            tx.execute(
+                "UPDATE login_throttle SET locked_until = ?2 WHERE email = ?1",
+                params![email, locked_until],
+            )?;
+        }
+        // Attempts made while throttled don't extend the wait.
+        LoginOutcome::Throttled => {}
+    }
+
+    tx.commit()?;
+
+    Ok(())
+}
+
+/* ===========================
+   AUDIT
+=========================== */
+
+/// Recent sign-in attempts against the user's account, newest first.
+pub fn list_login_attempts(
+    conn: &Connection,
+    user_id: i64,
+    limit: Option<u32>,
+) -> AppResult<Vec<LoginAttempt>> {
+
+    let limit = limit
+        .unwrap_or(DEFAULT_ATTEMPT_LIMIT)
+        .clamp(1, MAX_ATTEMPT_LIMIT);
+
+    let mut stmt = conn.prepare(
+        "SELECT id, outcome, attempted_at
+         FROM login_attempts
+         WHERE user_id = ?1
+         ORDER BY attempted_at DESC, id DESC
+         LIMIT ?2",
+    )?;
+
+    let rows = stmt.query_map(params![user_id, limit], |row| {
+        let outcome: String = row.get(1)?;
+
+        Ok(LoginAttempt {
+            id: row.get(0)?,
+            outcome: LoginOutcome::from_db(&outcome),
+            attempted_at: row.get(2)?,
+        })
+    })?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(AppError::from)
+}
+
+/* ===========================
+   HELPERS
+=========================== */
+
+fn lock_duration(failed_count: i64) -> Option<Duration> {
+    if failed_count >= LOCKOUT_AFTER {
+        return Some(Duration::minutes(LOCKOUT_MINUTES));
+    }
+
+    if failed_count < FREE_ATTEMPTS {
+        return None;
+    }
+
+    let exponent = (failed_count - FREE_ATTEMPTS) as u32;
+
+    Some(Duration::seconds(BASE_BACKOFF_SECONDS << exponent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_in_memory;
+
+    const EMAIL: &str = "a@example.com";
+
+    fn fail(conn: &Connection, times: usize) {
+        for _ in 0..times {
+            record_attempt(conn, EMAIL, None, LoginOutcome::InvalidCredentials).unwrap();
+        }
+    }
+
+    fn retry_after(conn: &Connection) -> Option<u64> {
+        match check_throttle(conn, EMAIL) {
+            Ok(()) => None,
+            Err(AppError::RateLimited { retry_after_seconds }) => Some(retry_after_seconds),
+            Err(e) => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_until_lockout() {
+        let waits: Vec<Option<i64>> = (1..=11)
+            .map(|count| lock_duration(count).map(|d| d.num_seconds()))
+            .collect();
+
+        assert_eq!(
+            waits,
+            vec![
+                None,
+                None,
+                Some(5),
+                Some(10),
+                Some(20),
+                Some(40),
+                Some(80),
+                Some(160),
+                Some(320),
+                Some(LOCKOUT_MINUTES * 60),
+                Some(LOCKOUT_MINUTES * 60),
+            ]
+        );
+    }
+
+    #[test]
+    fn throttles_after_the_free_attempts() {
+        let conn = open_in_memory();
+
+        fail(&conn, 2);
+        assert_eq!(retry_after(&conn), None);
+
+        fail(&conn, 1);
+        let wait = retry_after(&conn).expect("throttled");
+        assert!((1..=5).contains(&wait));
+
+        // Attempts rejected while throttled don't extend the wait.
+        record_attempt(&conn, EMAIL, None, LoginOutcome::Throttled).unwrap();
+        let failed: i64 = conn
+            .query_row(
+                "SELECT failed_count FROM login_throttle WHERE email = ?1",
+                params![EMAIL],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(failed, 3);
+    }
+
+    #[test]
+    fn locks_out_and_clears_on_success() {
+        let conn = open_in_memory();
+
+        fail(&conn, LOCKOUT_AFTER as usize);
+        let wait = retry_after(&conn).expect("locked out");
+        assert!(wait > (LOCKOUT_MINUTES as u64 - 1) * 60);
+
+        // Other emails are unaffected.
+        assert!(check_throttle(&conn, "b@example.com").is_ok());
+
+        record_attempt(&conn, EMAIL, None, LoginOutcome::Succeeded).unwrap();
+        assert_eq!(retry_after(&conn), None);
+    }
+}
@@ -1,3 +1,17 @@
 pub mod user_service;
+pub mod account_service;
+pub mod client_service;
 pub mod project_service;
-pub mod session_service;
\ No newline at end of file
+pub mod rate_service;
+pub mod invoice_service;
+pub mod session_service;
+pub mod stats_service;
+pub mod search_service;
+pub mod export_service;
+pub mod import_service;
+pub mod backup_service;
+pub mod app_lock_service;
+pub mod login_attempt_service;
+pub mod recovery_service;
+pub mod password_policy_service;
+pub mod verification_service;
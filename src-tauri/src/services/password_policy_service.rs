@@ -0,0 +1,340 @@
+use crate::error::{AppError, AppResult};
+use crate::models::password::{PasswordReport, PasswordViolation};
+
+// Lowercase forms; candidates are also checked with trailing digits and
+// symbols stripped, so "Password123!" counts as "password".
+const COMMON_PASSWORDS: &[&str] = &[
+    "123456", "12345678", "123456789", "1234567890", "111111", "000000",
+    "password", "passw0rd", "p@ssw0rd", "qwerty", "qwertyuiop", "asdfgh",
+    "asdfghjkl", "zxcvbnm", "1q2w3e4r", "1qaz2wsx", "abc123", "abcdef",
+    "letmein", "welcome", "admin", "administrator", "root", "login",
+    "master", "monkey", "dragon", "football", "baseball", "soccer",
+    "iloveyou", "sunshine", "princess", "shadow", "superman", "batman",
+    "trustno1", "starwars", "whatever", "freedom", "hello", "secret",
+    "changeme", "default", "guest", "test", "qazwsx", "michael",
+    "charlie", "jennifer", "hunter", "ranger", "killer", "computer",
+    "internet", "pokemon", "summer", "winter", "spring", "autumn",
+    "contraseña", "contrasena", "clave", "hola", "teamo", "trackly",
+];
+
+/* ===========================
+   POLICY
+=========================== */
+
+/// Rules a new password must satisfy.
+#[derive(Clone, Copy)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    /// Of lowercase, uppercase, digits and symbols.
+    pub min_character_classes: usize,
+    pub min_score: u8,
+    pub reject_common: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 128,
+            min_character_classes: 2,
+            min_score: 2,
+            reject_common: true,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Starts from the defaults and applies whichever of
+    /// `TRACKLY_PASSWORD_MIN_LENGTH`, `TRACKLY_PASSWORD_MAX_LENGTH`,
+    /// `TRACKLY_PASSWORD_MIN_CLASSES`, `TRACKLY_PASSWORD_MIN_SCORE` and
+    /// `TRACKLY_PASSWORD_REJECT_COMMON` are set.
+    pub fn from_env() -> AppResult<Self> {
+        Self::from_vars(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> AppResult<Self> {
+        fn setting<T: std::str::FromStr>(
+            var: &impl Fn(&str) -> Option<String>,
+            name: &str,
+            default: T,
+        ) -> AppResult<T> {
+            match var(name) {
+                Some(value) => value
+                    .parse()
+                    .map_err(|_| AppError::Internal(format!("Invalid {}: {}", name, value))),
+                None => Ok(default),
+            }
+        }
+
+        let defaults = Self::default();
+
+        let policy = Self {
+            min_length: setting(&var, "TRACKLY_PASSWORD_MIN_LENGTH", defaults.min_length)?,
+            max_length: setting(&var, "TRACKLY_PASSWORD_MAX_LENGTH", defaults.max_length)?,
+            min_character_classes: setting(&var, "TRACKLY_PASSWORD_MIN_CLASSES", defaults.min_character_classes)?,
+            min_score: setting(&var, "TRACKLY_PASSWORD_MIN_SCORE", defaults.min_score)?,
+            reject_common: setting(&var, "TRACKLY_PASSWORD_REJECT_COMMON", defaults.reject_common)?,
+        };
+
+        if policy.min_length == 0 || policy.min_length > policy.max_length {
+            return Err(AppError::Internal(format!(
+                "Invalid password length range: {}..={}",
+                policy.min_length, policy.max_length
+            )));
+        }
+
+        if policy.min_character_classes > 4 || policy.min_score > 4 {
+            return Err(AppError::Internal(
+                "Password classes and score go up to 4".into(),
+            ));
+        }
+
+        Ok(policy)
+    }
+
+    /// Scores the password and lists every rule it breaks. `email` lets
+    /// the check reject passwords built from the account's address.
+    pub fn evaluate(&self, password: &str, email: Option<&str>) -> PasswordReport {
+        let length = password.chars().count();
+        let classes = character_classes(password);
+        let common = is_common(password);
+
+        let entropy_bits = estimate_entropy(password);
+        let score = if common { 0 } else { score_for(entropy_bits) };
+
+        let mut violations = Vec::new();
+
+        if length < self.min_length {
+            violations.push(PasswordViolation::TooShort { min_length: self.min_length });
+        }
+
+        if length > self.max_length {
+            violations.push(PasswordViolation::TooLong { max_length: self.max_length });
+        }
+
+        if classes.count() < self.min_character_classes {
+            violations.push(PasswordViolation::TooFewCharacterClasses {
+                required: self.min_character_classes,
+                found: classes.count(),
+            });
+        }
+
+        if self.reject_common && common {
+            violations.push(PasswordViolation::Common);
+        }
+
+        if email.is_some_and(|e| contains_email(password, e)) {
+            violations.push(PasswordViolation::ContainsEmail);
+        }
+
+        if score < self.min_score {
+            violations.push(PasswordViolation::TooWeak { score, min_score: self.min_score });
+        }
+
+        PasswordReport {
+            score,
+            entropy_bits: (entropy_bits * 10.0).round() / 10.0,
+            violations,
+        }
+    }
+
+    /// Rejects the password with a `Validation` error on `field` that
+    /// carries every violation.
+    pub fn enforce(
+        &self,
+        field: &'static str,
+        password: &str,
+        email: Option<&str>,
+    ) -> AppResult<()> {
+
+        let report = self.evaluate(password, email);
+
+        if report.violations.is_empty() {
+            return Ok(());
+        }
+
+        Err(AppError::PasswordPolicy {
+            field,
+            violations: report.violations,
+        })
+    }
+}
+
+/* ===========================
+   HELPERS
+=========================== */
+
+#[derive(Default)]
+struct CharacterClasses {
+    lower: bool,
+    upper: bool,
+    digit: bool,
+    symbol: bool,
+}
+
+impl CharacterClasses {
+    fn count(&self) -> usize {
+        [self.lower, self.upper, self.digit, self.symbol]
+            .iter()
+            .filter(|present| **present)
+            .count()
+    }
+
+    /// Rough alphabet size an attacker would have to cover.
+    fn pool_size(&self) -> f64 {
+        let mut pool = 0.0;
+        if self.lower { pool += 26.0; }
+        if self.upper { pool += 26.0; }
+        if self.digit { pool += 10.0; }
+        if self.symbol { pool += 33.0; }
+        pool
+    }
+}
+
+fn character_classes(password: &str) -> CharacterClasses {
+    let mut classes = CharacterClasses::default();
+
+    for c in password.chars() {
+        if c.is_lowercase() {
+            classes.lower = true;
+        } else if c.is_uppercase() {
+            classes.upper = true;
+        } else if c.is_numeric() {
+            classes.digit = true;
+        } else {
+            classes.symbol = true;
+        }
+    }
+
+    classes
+}
+
+/// Length times bits per character, where repeated characters and
+/// runs like "abc" or "321" only count for half.
+fn estimate_entropy(password: &str) -> f64 {
+    let pool = character_classes(password).pool_size();
+
+    if pool == 0.0 {
+        return 0.0;
+    }
+
+    let chars: Vec<char> = password.chars().collect();
+
+    let effective_length: f64 = chars
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let predictable = i > 0 && {
+                let step = *c as i64 - chars[i - 1] as i64;
+                step.abs() <= 1
+            };
+            if predictable { 0.5 } else { 1.0 }
+        })
+        .sum();
+
+    effective_length * pool.log2()
+}
+
+fn score_for(entropy_bits: f64) -> u8 {
+    match entropy_bits {
+        b if b < 28.0 => 0,
+        b if b < 36.0 => 1,
+        b if b < 60.0 => 2,
+        b if b < 80.0 => 3,
+        _ => 4,
+    }
+}
+
+fn is_common(password: &str) -> bool {
+    let lower = password.to_lowercase();
+    let stripped = lower.trim_end_matches(|c: char| !c.is_alphabetic());
+
+    COMMON_PASSWORDS
+        .iter()
+        .any(|common| *common == lower || *common == stripped)
+}
+
+fn contains_email(password: &str, email: &str) -> bool {
+    let local = email.split('@').next().unwrap_or("").to_lowercase();
+
+    local.chars().count() >= 3 && password.to_lowercase().contains(&local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violations(policy: &PasswordPolicy, password: &str, email: Option<&str>) -> Vec<PasswordViolation> {
+        policy.evaluate(password, email).violations
+    }
+
+    #[test]
+    fn accepts_a_strong_password() {
+        let policy = PasswordPolicy::default();
+        let report = policy.evaluate("Correct-horse-battery-9", Some("ana@example.com"));
+
+        assert!(report.violations.is_empty());
+        assert!(report.score >= policy.min_score);
+    }
+
+    #[test]
+    fn reports_length_and_character_classes() {
+        let policy = PasswordPolicy::default();
+
+        let short = violations(&policy, "Ab1!", None);
+        assert!(short.contains(&PasswordViolation::TooShort { min_length: 8 }));
+
+        let long = "Ab1!".repeat(40);
+        assert!(violations(&policy, &long, None)
+            .contains(&PasswordViolation::TooLong { max_length: 128 }));
+
+        assert!(violations(&policy, "zqxjvkwmtr", None)
+            .contains(&PasswordViolation::TooFewCharacterClasses { required: 2, found: 1 }));
+    }
+
+    #[test]
+    fn rejects_common_passwords_and_the_email() {
+        let policy = PasswordPolicy::default();
+
+        let common = policy.evaluate("Password123!", None);
+        assert_eq!(common.score, 0);
+        assert!(common.violations.contains(&PasswordViolation::Common));
+
+        assert!(violations(&policy, "Gonzalo-Trx-7781", Some("gonzalo@example.com"))
+            .contains(&PasswordViolation::ContainsEmail));
+
+        let lenient = PasswordPolicy { reject_common: false, min_score: 0, ..policy };
+        assert!(violations(&lenient, "Password123!", None).is_empty());
+    }
+
+    #[test]
+    fn sequences_score_lower_than_random_characters() {
+        assert!(estimate_entropy("abcdefgh") < estimate_entropy("aqzmwkxp"));
+        assert!(estimate_entropy("11111111") < estimate_entropy("19375028"));
+    }
+
+    #[test]
+    fn reads_overrides_from_the_environment() {
+        let policy = PasswordPolicy::from_vars(|name| match name {
+            "TRACKLY_PASSWORD_MIN_LENGTH" => Some("12".into()),
+            "TRACKLY_PASSWORD_REJECT_COMMON" => Some("false".into()),
+            _ => None,
+        })
+        .unwrap();
+
+        assert_eq!(policy.min_length, 12);
+        assert!(!policy.reject_common);
+        assert_eq!(policy.max_length, PasswordPolicy::default().max_length);
+
+        assert!(PasswordPolicy::from_vars(|_| None).is_ok());
+        assert!(PasswordPolicy::from_vars(|name| {
+            (name == "TRACKLY_PASSWORD_MIN_SCORE").then(|| "five".into())
+        })
+        .is_err());
+        assert!(PasswordPolicy::from_vars(|name| {
+            (name == "TRACKLY_PASSWORD_MIN_LENGTH").then(|| "500".into())
+        })
+        .is_err());
+    }
+}
@@ -1,59 +1,191 @@
 use rusqlite::{params, Connection};
-use crate::models::project::{DbProject, Project};
+use chrono::Utc;
+use std::collections::HashMap;
+use crate::error::{AppError, AppResult};
+use crate::models::project::{
+    DbProject,
+    Project,
+    ProjectGroup,
+    ProjectNode,
+    ProjectSessionsOnDelete,
+};
+use crate::services::client_service;
 
 /* ===========================
    GET PROJECTS
 =========================== */
 
+/// Archived projects are left out unless `include_archived` is set.
 pub fn get_projects(
     conn: &Connection,
     user_id: i64,
-) -> Result<Vec<Project>, String> {
+    include_archived: bool,
+) -> AppResult<Vec<Project>> {
 
     let mut stmt = conn.prepare(
-        "SELECT id, user_id, name, color
+        "SELECT id, user_id, name, color, archived_at, client_id, parent_id, billable
          FROM projects
          WHERE user_id = ?1
+           AND (?2 OR archived_at IS NULL)
          ORDER BY created_at ASC"
-    ).map_err(|e| e.to_string())?;
+    )?;
 
     let rows = stmt
-        .query_map(params![user_id], |row| {
+        .query_map(params![user_id, include_archived], |row| {
             Ok(DbProject {
                 id: row.get(0)?,
                 user_id: row.get(1)?,
                 name: row.get(2)?,
                 color: row.get(3)?,
+                archived_at: row.get(4)?,
+                client_id: row.get(5)?,
+                parent_id: row.get(6)?,
+                billable: row.get(7)?,
             })
-        })
-        .map_err(|e| e.to_string())?;
+        })?;
 
     let mut projects = Vec::new();
 
     for row in rows {
-        projects.push(row.map_err(|e| e.to_string())?.into());
+        projects.push(row?.into());
     }
 
     Ok(projects)
 }
 
+/* ===========================
+   GET PROJECT TREE
+=========================== */
+
+/// Groups top-level projects by client, each with its sub-projects.
+/// Every client gets a group, even without projects; projects without a
+/// client come last. A sub-project whose parent is archived and left
+/// out is listed at the top level under the parent's client.
+pub fn get_project_tree(
+    conn: &Connection,
+    user_id: i64,
+    include_archived: bool,
+) -> AppResult<Vec<ProjectGroup>> {
+
+    let all = get_projects(conn, user_id, true)?;
+
+    let client_of: HashMap<i64, Option<i64>> = all
+        .iter()
+        .map(|p| (p.id, p.client_id))
+        .collect();
+
+    let visible: Vec<Project> = all
+        .into_iter()
+        .filter(|p| include_archived || p.archived_at.is_none())
+        .collect();
+
+    let is_visible = |id: i64| visible.iter().any(|p| p.id == id);
+
+    let mut children: HashMap<i64, Vec<Project>> = HashMap::new();
+    let mut roots: Vec<(Option<i64>, Project)> = Vec::new();
+
+    for project in &visible {
+        match project.parent_id {
+            Some(parent) if is_visible(parent) => {
+                children.entry(parent).or_default().push(project.clone());
+            }
+            Some(parent) => {
+                let client = client_of.get(&parent).copied().flatten();
+                roots.push((client, project.clone()));
+            }
+            None => roots.push((project.client_id, project.clone())),
+        }
+    }
+
+    let mut node = |project: Project| ProjectNode {
+        children: children.remove(&project.id).unwrap_or_default(),
+        project,
+    };
+
+    let mut groups = Vec::new();
+
+    for client in client_service::get_clients(conn, user_id)? {
+        let projects = roots
+            .iter()
+            .filter(|(c, _)| *c == Some(client.id))
+            .map(|(_, p)| node(p.clone()))
+            .collect();
+
+        groups.push(ProjectGroup {
+            client: Some(client),
+            projects,
+        });
+    }
+
+    let unassigned: Vec<ProjectNode> = roots
+        .iter()
+        .filter(|(c, _)| c.is_none())
+        .map(|(_, p)| node(p.clone()))
+        .collect();
+
+    if !unassigned.is_empty() {
+        groups.push(ProjectGroup {
+            client: None,
+            projects: unassigned,
+        });
+    }
+
+    Ok(groups)
+}
+
+/* ===========================
+   GET PROJECT BY ID
+=========================== */
+
+pub fn get_project_by_id(
+    conn: &Connection,
+    user_id: i64,
+    project_id: i64,
+) -> AppResult<DbProject> {
+
+    conn.query_row(
+        "SELECT id, user_id, name, color, archived_at, client_id, parent_id, billable
+         FROM projects
+         WHERE id = ?1 AND user_id = ?2",
+        params![project_id, user_id],
+        |row| {
+            Ok(DbProject {
+                id: row.get(0)?,
+                user_id: row.get(1)?,
+                name: row.get(2)?,
+                color: row.get(3)?,
+                archived_at: row.get(4)?,
+                client_id: row.get(5)?,
+                parent_id: row.get(6)?,
+                billable: row.get(7)?,
+            })
+        },
+    )
+    .map_err(|_| AppError::NotFound("Project"))
+}
+
 /* ===========================
    CREATE PROJECT
 =========================== */
 
+/// `parent_id` makes it a sub-project of a top-level project; only
+/// top-level projects take a `client_id`.
 pub fn create_project(
     conn: &Connection,
     user_id: i64,
     name: &str,
     color: &str,
-) -> Result<Project, String> {
+    parent_id: Option<i64>,
+    client_id: Option<i64>,
+) -> AppResult<Project> {
+
+    validate_placement(conn, user_id, None, parent_id, client_id)?;
 
     conn.execute(
-        "INSERT INTO projects (user_id, name, color)
-         VALUES (?1, ?2, ?3)",
-        params![user_id, name, color],
-    )
-    .map_err(|e| e.to_string())?;
+        "INSERT INTO projects (user_id, name, color, parent_id, client_id)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![user_id, name, color, parent_id, client_id],
+    )?;
 
     let id = conn.last_insert_rowid();
 
@@ -61,6 +193,10 @@ pub fn create_project(
         id,
         name: name.to_string(),
         color: color.to_string(),
+        archived_at: None,
+        client_id,
+        parent_id,
+        billable: true,
     })
 }
 
@@ -74,7 +210,8 @@ pub fn update_project(
     project_id: i64,
     name: Option<String>,
     color: Option<String>,
-) -> Result<(), String> {
+    billable: Option<bool>,
+) -> AppResult<()> {
 
     let mut fields = Vec::new();
     let mut values: Vec<&dyn rusqlite::ToSql> = Vec::new();
@@ -89,6 +226,11 @@ pub fn update_project(
         values.push(c);
     }
 
+    if let Some(ref b) = billable {
+        fields.push("billable = ?");
+        values.push(b);
+    }
+
     if fields.is_empty() {
         return Ok(());
     }
@@ -98,41 +240,333 @@ pub fn update_project(
         fields.join(", ")
     );
 
-    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(&sql)?;
 
     let mut final_values = values;
     final_values.push(&project_id);
     final_values.push(&user_id);
 
-    let result = stmt.execute(rusqlite::params_from_iter(final_values))
-        .map_err(|e| e.to_string())?;
+    let result = stmt.execute(rusqlite::params_from_iter(final_values))?;
 
     if result == 0 {
-        return Err("Project not found".into());
+        return Err(AppError::NotFound("Project"));
     }
 
     Ok(())
 }
 
+/* ===========================
+   HIERARCHY
+=========================== */
+
+/// Moves the project under `parent_id`, or back to the top level with
+/// `None`. A sub-project takes its parent's client; one moved back to
+/// the top level keeps the client it had through its old parent.
+pub fn set_project_parent(
+    conn: &Connection,
+    user_id: i64,
+    project_id: i64,
+    parent_id: Option<i64>,
+) -> AppResult<Project> {
+
+    let project = get_project_by_id(conn, user_id, project_id)?;
+
+    validate_placement(conn, user_id, Some(project_id), parent_id, None)?;
+
+    let client_id = match (parent_id, project.parent_id) {
+        (Some(_), _) => None,
+        (None, Some(old_parent)) => get_project_by_id(conn, user_id, old_parent)?.client_id,
+        (None, None) => project.client_id,
+    };
+
+    conn.execute(
+        "UPDATE projects SET parent_id = ?1, client_id = ?2
+         WHERE id = ?3 AND user_id = ?4",
+        params![parent_id, client_id, project_id, user_id],
+    )?;
+
+    Ok(get_project_by_id(conn, user_id, project_id)?.into())
+}
+
+/// Assigns a top-level project, and so its sub-projects, to a client.
+pub fn set_project_client(
+    conn: &Connection,
+    user_id: i64,
+    project_id: i64,
+    client_id: Option<i64>,
+) -> AppResult<Project> {
+
+    let project = get_project_by_id(conn, user_id, project_id)?;
+
+    validate_placement(conn, user_id, Some(project_id), project.parent_id, client_id)?;
+
+    conn.execute(
+        "UPDATE projects SET client_id = ?1
+         WHERE id = ?2 AND user_id = ?3",
+        params![client_id, project_id, user_id],
+    )?;
+
+    Ok(get_project_by_id(conn, user_id, project_id)?.into())
+}
+
+/* ===========================
+   ARCHIVE PROJECT
+=========================== */
+
+/// Hides the project from the default list and from new sessions while
+/// keeping its history. Archiving twice keeps the original timestamp.
+pub fn archive_project(
+    conn: &Connection,
+    user_id: i64,
+    project_id: i64,
+) -> AppResult<Project> {
+
+    get_project_by_id(conn, user_id, project_id)?;
+
+    if has_running_session(conn, project_id)? {
+        return Err(AppError::Conflict("Stop the project's active session first".into()));
+    }
+
+    conn.execute(
+        "UPDATE projects SET archived_at = COALESCE(archived_at, ?3)
+         WHERE id = ?1 AND user_id = ?2",
+        params![project_id, user_id, Utc::now().to_rfc3339()],
+    )?;
+
+    Ok(get_project_by_id(conn, user_id, project_id)?.into())
+}
+
+pub fn unarchive_project(
+    conn: &Connection,
+    user_id: i64,
+    project_id: i64,
+) -> AppResult<Project> {
+
+    let result = conn.execute(
+        "UPDATE projects SET archived_at = NULL
+         WHERE id = ?1 AND user_id = ?2",
+        params![project_id, user_id],
+    )?;
+
+    if result == 0 {
+        return Err(AppError::NotFound("Project"));
+    }
+
+    Ok(get_project_by_id(conn, user_id, project_id)?.into())
+}
+
 /* ===========================
    DELETE PROJECT
 =========================== */
 
+/// Permanently deletes the project. `confirm_name` must repeat the
+/// project's name, and `sessions` decides what happens to its history.
 pub fn delete_project(
     conn: &Connection,
     user_id: i64,
     project_id: i64,
-) -> Result<(), String> {
+    confirm_name: &str,
+    sessions: ProjectSessionsOnDelete,
+) -> AppResult<()> {
 
-    let result = conn.execute(
+    let project = get_project_by_id(conn, user_id, project_id)?;
+
+    if confirm_name.trim() != project.name {
+        return Err(AppError::field("confirm_name", "Type the project name to confirm"));
+    }
+
+    let tx = conn.unchecked_transaction()?;
+
+    match sessions {
+        ProjectSessionsOnDelete::Unassign => {
+            tx.execute(
+                "UPDATE sessions SET project_id = NULL
+                 WHERE project_id = ?1 AND user_id = ?2",
+                params![project_id, user_id],
+            )?;
+        }
+        ProjectSessionsOnDelete::Delete => {
+            if has_running_session(&tx, project_id)? {
+                return Err(AppError::Conflict("Stop the project's active session first".into()));
+            }
+
+            if has_invoiced_session(&tx, project_id)? {
+                return Err(AppError::Conflict(
+                    "Some of the project's sessions are invoiced; keep or move them instead".into(),
+                ));
+            }
+
+            for sql in [
+                "DELETE FROM session_tags
+                 WHERE session_id IN (SELECT id FROM sessions WHERE project_id = ?1 AND user_id = ?2)",
+                "DELETE FROM session_pauses
+                 WHERE session_id IN (SELECT id FROM sessions WHERE project_id = ?1 AND user_id = ?2)",
+                "DELETE FROM sessions WHERE project_id = ?1 AND user_id = ?2",
+            ] {
+                tx.execute(sql, params![project_id, user_id])?;
+            }
+        }
+        ProjectSessionsOnDelete::Reassign { project_id: target } => {
+            if target == project_id {
+                return Err(AppError::field("project_id", "Choose a different project"));
+            }
+
+            get_project_by_id(&tx, user_id, target)?;
+
+            // Invoiced work stays with the project it was billed under.
+            if has_invoiced_session(&tx, project_id)? {
+                return Err(AppError::Conflict(
+                    "Some of the project's sessions are invoiced; keep them instead".into(),
+                ));
+            }
+
+            tx.execute(
+                "UPDATE sessions SET project_id = ?3
+                 WHERE project_id = ?1 AND user_id = ?2",
+                params![project_id, user_id, target],
+            )?;
+        }
+    }
+
+    // Sub-projects move to the top level and keep the client.
+    tx.execute(
+        "UPDATE projects SET parent_id = NULL, client_id = ?3
+         WHERE parent_id = ?1 AND user_id = ?2",
+        params![project_id, user_id, project.client_id],
+    )?;
+
+    tx.execute(
         "DELETE FROM projects WHERE id = ?1 AND user_id = ?2",
         params![project_id, user_id],
+    )?;
+
+    tx.commit()?;
+
+    Ok(())
+}
+
+/* ===========================
+   HELPERS
+=========================== */
+
+fn has_running_session(
+    conn: &Connection,
+    project_id: i64,
+) -> AppResult<bool> {
+
+    conn.query_row(
+        "SELECT EXISTS (
+             SELECT 1 FROM sessions
+             WHERE project_id = ?1 AND status = 'running'
+         )",
+        params![project_id],
+        |row| row.get(0),
     )
-    .map_err(|e| e.to_string())?;
+    .map_err(AppError::from)
+}
 
-    if result == 0 {
-        return Err("Project not found".into());
+fn has_invoiced_session(
+    conn: &Connection,
+    project_id: i64,
+) -> AppResult<bool> {
+
+    conn.query_row(
+        "SELECT EXISTS (
+             SELECT 1 FROM sessions
+             WHERE project_id = ?1 AND invoice_id IS NOT NULL
+         )",
+        params![project_id],
+        |row| row.get(0),
+    )
+    .map_err(AppError::from)
+}
+
+/// Checks that `project_id` (`None` for a new project) can sit under
+/// `parent_id` with `client_id`: projects nest one level deep, and the
+/// client belongs to the top level.
+fn validate_placement(
+    conn: &Connection,
+    user_id: i64,
+    project_id: Option<i64>,
+    parent_id: Option<i64>,
+    client_id: Option<i64>,
+) -> AppResult<()> {
+
+    if let Some(parent_id) = parent_id {
+        if Some(parent_id) == project_id {
+            return Err(AppError::field("parent_id", "A project can't be its own parent"));
+        }
+
+        let parent = get_project_by_id(conn, user_id, parent_id)?;
+
+        if parent.parent_id.is_some() {
+            return Err(AppError::field("parent_id", "Sub-projects can't have sub-projects"));
+        }
+
+        if let Some(project_id) = project_id {
+            let has_children: bool = conn.query_row(
+                "SELECT EXISTS (SELECT 1 FROM projects WHERE parent_id = ?1)",
+                params![project_id],
+                |row| row.get(0),
+            )?;
+
+            if has_children {
+                return Err(AppError::field(
+                    "parent_id",
+                    "A project with sub-projects can't become a sub-project",
+                ));
+            }
+        }
+
+        if client_id.is_some() {
+            return Err(AppError::field("client_id", "Sub-projects use their parent's client"));
+        }
+    }
+
+    if let Some(client_id) = client_id {
+        client_service::get_client_by_id(conn, user_id, client_id)?;
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_in_memory;
+    use crate::services::user_service;
+
+    #[test]
+    fn invoiced_sessions_stay_with_their_project() {
+        let conn = open_in_memory();
+        let user = user_service::create_user(&conn, "a@example.com", "Old-passphrase-42").unwrap();
+
+        let billed = create_project(&conn, user.id, "Billed", "#ff0000", None, None).unwrap();
+        let other = create_project(&conn, user.id, "Other", "#00ff00", None, None).unwrap();
+
+        conn.execute_batch(&format!(
+            "INSERT INTO invoices (id, user_id, sequence, number, client_name, period_from,
+                                   period_to, currency, total_cents, issued_at)
+             VALUES (1, {user}, 1, 'INV-0001', 'Acme', '2026-01-01', '2026-01-31', 'EUR', 0,
+                     '2026-02-01T00:00:00Z');
+             INSERT INTO sessions (user_id, project_id, start_time, end_time, status, invoice_id)
+             VALUES ({user}, {project}, '2026-01-05T09:00:00Z', '2026-01-05T10:00:00Z', 'finished', 1);",
+            user = user.id,
+            project = billed.id,
+        ))
+        .unwrap();
+
+        for mode in [
+            ProjectSessionsOnDelete::Delete,
+            ProjectSessionsOnDelete::Reassign { project_id: other.id },
+        ] {
+            let result = delete_project(&conn, user.id, billed.id, "Billed", mode);
+            assert!(matches!(result, Err(AppError::Conflict(_))));
+        }
+
+        let project: Option<i64> = conn
+            .query_row("SELECT project_id FROM sessions WHERE invoice_id = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(project, Some(billed.id));
+    }
+}
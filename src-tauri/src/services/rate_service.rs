@@ -0,0 +1,277 @@
+use rusqlite::{params, Connection};
+use chrono::Utc;
+use crate::error::{AppError, AppResult};
+use crate::models::rate::{HourlyRate, RateScope};
+use crate::services::{client_service, project_service, session_service};
+
+/* ===========================
+   SQL
+=========================== */
+
+// Both expect `s` (the session), `p` (its project) and `r` (its
+// top-level project) in scope, as joined by the stats queries.
+
+/// Id of the rate that applies to session `s`: the most specific scope
+/// with a rate in effect when the session started, then the latest
+/// version of it. (SQLite can't refer to the outer query from a
+/// subquery's ORDER BY, hence one lookup per scope.)
+pub(crate) fn rate_for_session_sql() -> String {
+    let latest = |scope: &str| {
+        format!(
+            "(SELECT x.id FROM hourly_rates x
+              WHERE x.user_id = s.user_id
+                AND x.effective_from <= s.start_time
+                AND {}
+              ORDER BY x.effective_from DESC
+              LIMIT 1)",
+            scope
+        )
+    };
+
+    format!(
+        "COALESCE({}, {}, {}, {})",
+        latest("x.project_id = s.project_id"),
+        latest("x.project_id = r.id"),
+        latest("x.client_id = r.client_id"),
+        latest("x.client_id IS NULL AND x.project_id IS NULL"),
+    )
+}
+
+/// Value of `seconds` at `rate` cents per hour, rounded to the nearest
+/// cent. Earnings and invoices both round each session this way before
+/// adding them up, so their totals agree.
+pub(crate) fn amount_cents_sql(seconds: &str, rate: &str) -> String {
+    format!("(({}) * {} + 1800) / 3600", seconds, rate)
+}
+
+/// Sessions follow their project unless flagged themselves.
+pub(crate) const BILLABLE_SQL: &str = "COALESCE(s.billable, p.billable, 1) = 1";
+
+/* ===========================
+   GET RATES
+=========================== */
+
+/// Every version of every rate, grouped by scope, oldest first.
+pub fn get_rates(
+    conn: &Connection,
+    user_id: i64,
+) -> AppResult<Vec<HourlyRate>> {
+
+    let mut stmt = conn.prepare(
+        "SELECT id, client_id, project_id, amount_cents, currency, effective_from
+         FROM hourly_rates
+         WHERE user_id = ?1
+         ORDER BY project_id, client_id, effective_from ASC"
+    )?;
+
+    let rows = stmt
+        .query_map(params![user_id], |row| {
+            Ok(HourlyRate {
+                id: row.get(0)?,
+                scope: RateScope::from_columns(row.get(1)?, row.get(2)?),
+                amount_cents: row.get(3)?,
+                currency: row.get(4)?,
+                effective_from: row.get(5)?,
+            })
+        })?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(AppError::from)
+}
+
+/* ===========================
+   SET RATE
+=========================== */
+
+/// Adds a rate version for `scope`, effective from `effective_from` (now
+/// by default). Sessions that started earlier keep their old rate. A
+/// version with the same start replaces the existing one.
+pub fn set_rate(
+    conn: &Connection,
+    user_id: i64,
+    scope: RateScope,
+    amount_cents: i64,
+    currency: &str,
+    effective_from: Option<&str>,
+) -> AppResult<HourlyRate> {
+
+    match scope {
+        RateScope::User => {}
+        RateScope::Client { client_id } => {
+            client_service::get_client_by_id(conn, user_id, client_id)?;
+        }
+        RateScope::Project { project_id } => {
+            project_service::get_project_by_id(conn, user_id, project_id)?;
+        }
+    }
+
+    if amount_cents < 0 {
+        return Err(AppError::field("amount_cents", "Rate can't be negative"));
+    }
+
+    let currency = normalize_currency(currency)?;
+
+    let effective_from = match effective_from {
+        Some(value) => session_service::parse_time(value)
+            .map_err(|e| AppError::field("effective_from", e.to_string()))?,
+        None => Utc::now(),
+    }
+    .to_rfc3339();
+
+    let (client_id, project_id) = scope.columns();
+
+    conn.execute(
+        "INSERT OR REPLACE INTO hourly_rates
+             (user_id, client_id, project_id, amount_cents, currency, effective_from)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![user_id, client_id, project_id, amount_cents, currency, effective_from],
+    )?;
+
+    Ok(HourlyRate {
+        id: conn.last_insert_rowid(),
+        scope,
+        amount_cents,
+        currency,
+        effective_from,
+    })
+}
+
+/* ===========================
+   DELETE RATE
+=========================== */
+
+/// Removes one version; sessions it covered fall back to the previous
+/// version or a broader scope.
+pub fn delete_rate(
+    conn: &Connection,
+    user_id: i64,
+    rate_id: i64,
+) -> AppResult<()> {
+
+    let result = conn.execute(
+        "DELETE FROM hourly_rates WHERE id = ?1 AND user_id = ?2",
+        params![rate_id, user_id],
+    )?;
+
+    if result == 0 {
+        return Err(AppError::NotFound("Rate"));
+    }
+
+    Ok(())
+}
+
+/* ===========================
+   HELPERS
+=========================== */
+
+/// Three-letter ISO 4217 code, uppercased.
+pub(crate) fn normalize_currency(value: &str) -> AppResult<String> {
+    let code = value.trim().to_uppercase();
+
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(AppError::field("currency", "Use a three-letter currency code such as EUR"));
+    }
+
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_in_memory;
+    use crate::services::stats_service::TOP_LEVEL_JOIN_SQL;
+    use crate::services::user_service;
+
+    const STARTED: &str = "2026-03-10T10:00:00+00:00";
+
+    fn applied_rate(conn: &Connection, session_id: i64) -> Option<i64> {
+        conn.query_row(
+            &format!(
+                "SELECT hr.amount_cents
+                 FROM sessions s
+                 {join}
+                 LEFT JOIN hourly_rates hr ON hr.id = {rate}
+                 WHERE s.id = ?1",
+                join = TOP_LEVEL_JOIN_SQL,
+                rate = rate_for_session_sql(),
+            ),
+            params![session_id],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    fn add_session(conn: &Connection, user_id: i64, project_id: Option<i64>) -> i64 {
+        conn.execute(
+            "INSERT INTO sessions (user_id, project_id, start_time, end_time, status)
+             VALUES (?1, ?2, ?3, ?3, 'finished')",
+            params![user_id, project_id, STARTED],
+        )
+        .unwrap();
+
+        conn.last_insert_rowid()
+    }
+
+    #[test]
+    fn the_most_specific_scope_wins() {
+        let conn = open_in_memory();
+        let user = user_service::create_user(&conn, "a@example.com", "Old-passphrase-42").unwrap();
+
+        let client = client_service::create_client(&conn, user.id, "Acme", None).unwrap();
+        let parent = project_service::create_project(&conn, user.id, "Parent", "#ff0000", None, Some(client.id)).unwrap();
+        let child = project_service::create_project(&conn, user.id, "Child", "#00ff00", Some(parent.id), None).unwrap();
+
+        let session = add_session(&conn, user.id, Some(child.id));
+        let unassigned = add_session(&conn, user.id, None);
+
+        assert_eq!(applied_rate(&conn, session), None);
+
+        let set = |scope, amount| {
+            set_rate(&conn, user.id, scope, amount, "eur", Some("2026-01-01T00:00:00Z")).unwrap()
+        };
+
+        set(RateScope::User, 1000);
+        assert_eq!(applied_rate(&conn, session), Some(1000));
+
+        set(RateScope::Client { client_id: client.id }, 2000);
+        assert_eq!(applied_rate(&conn, session), Some(2000));
+
+        set(RateScope::Project { project_id: parent.id }, 3000);
+        assert_eq!(applied_rate(&conn, session), Some(3000));
+
+        let own = set(RateScope::Project { project_id: child.id }, 4000);
+        assert_eq!(applied_rate(&conn, session), Some(4000));
+
+        // Sessions outside any project only ever get the user's rate.
+        assert_eq!(applied_rate(&conn, unassigned), Some(1000));
+
+        delete_rate(&conn, user.id, own.id).unwrap();
+        assert_eq!(applied_rate(&conn, session), Some(3000));
+    }
+
+    #[test]
+    fn uses_the_version_in_effect_when_the_session_started() {
+        let conn = open_in_memory();
+        let user = user_service::create_user(&conn, "a@example.com", "Old-passphrase-42").unwrap();
+        let project = project_service::create_project(&conn, user.id, "Work", "#ff0000", None, None).unwrap();
+
+        let session = add_session(&conn, user.id, Some(project.id));
+        let scope = RateScope::Project { project_id: project.id };
+
+        set_rate(&conn, user.id, RateScope::User, 1000, "EUR", Some("2026-01-01T00:00:00Z")).unwrap();
+
+        // A rate starting after the session doesn't apply to it, even
+        // from a more specific scope.
+        set_rate(&conn, user.id, scope, 9000, "EUR", Some("2026-04-01T00:00:00Z")).unwrap();
+        assert_eq!(applied_rate(&conn, session), Some(1000));
+
+        set_rate(&conn, user.id, scope, 4000, "EUR", Some("2026-01-15T00:00:00Z")).unwrap();
+        set_rate(&conn, user.id, scope, 4500, "EUR", Some("2026-02-01T00:00:00Z")).unwrap();
+        assert_eq!(applied_rate(&conn, session), Some(4500));
+
+        // Same start replaces the version.
+        set_rate(&conn, user.id, scope, 4800, "EUR", Some("2026-02-01T00:00:00Z")).unwrap();
+        assert_eq!(applied_rate(&conn, session), Some(4800));
+        assert_eq!(get_rates(&conn, user.id).unwrap().len(), 4);
+    }
+}
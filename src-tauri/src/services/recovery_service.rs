@@ -0,0 +1,229 @@
+use rusqlite::{params, Connection};
+use chrono::Utc;
+use rand_core::{OsRng, RngCore};
+
+use crate::error::{AppError, AppResult};
+use crate::models::user::LoginOutcome;
+use crate::services::{account_service, login_attempt_service, user_service};
+use crate::services::password_policy_service::PasswordPolicy;
+
+const CODE_COUNT: usize = 10;
+const CODE_LENGTH: usize = 10;
+
+// No 0/O, 1/I/L: codes are meant to be copied by hand.
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+
+/* ===========================
+   GENERATE
+=========================== */
+
+/// Replaces the user's recovery codes with a fresh set and returns them.
+/// Only hashes are stored, so this is the one time they can be shown.
+pub fn generate_recovery_codes(
+    conn: &Connection,
+    user_id: i64,
+) -> AppResult<Vec<String>> {
+
+    let codes: Vec<String> = (0..CODE_COUNT).map(|_| generate_code()).collect();
+
+    let now = Utc::now().to_rfc3339();
+
+    let tx = conn.unchecked_transaction()?;
+
+    tx.execute(
+        "DELETE FROM recovery_codes WHERE user_id = ?1",
+        params![user_id],
+    )?;
+
+    for code in &codes {
+        tx.execute(
+            "INSERT INTO recovery_codes (user_id, code_hash, created_at)
+             VALUES (?1, ?2, ?3)",
+            params![user_id, user_service::hash_password(&normalize(code))?, now],
+        )?;
+    }
+
+    tx.commit()?;
+
+    Ok(codes)
+}
+
+pub fn count_remaining_codes(
+    conn: &Connection,
+    user_id: i64,
+) -> AppResult<i64> {
+
+    conn.query_row(
+        "SELECT COUNT(*) FROM recovery_codes
+         WHERE user_id = ?1 AND used_at IS NULL",
+        params![user_id],
+        |row| row.get(0),
+    )
+    .map_err(AppError::from)
+}
+
+/* ===========================
+   RESET PASSWORD
+=========================== */
+
+/// Consumes one unused code and sets a new password. Every existing
+/// login for the account is revoked; returns the account's id.
+///
+/// Shares the login throttle, and fails the same way for unknown emails
+/// and wrong codes.
+pub fn reset_password_with_recovery_code(
+    conn: &Connection,
+    policy: &PasswordPolicy,
+    email: &str,
+    code: &str,
+    new_password: &str,
+) -> AppResult<i64> {
+
+    policy.enforce("new_password", new_password, Some(email))?;
+
+    let user = user_service::get_user_by_email(conn, email);
+    let user_id = user.as_ref().map(|u| u.id);
+
+    if let Err(e) = login_attempt_service::check_throttle(conn, email) {
+        login_attempt_service::record_attempt(conn, email, user_id, LoginOutcome::Throttled)?;
+        return Err(e);
+    }
+
+    let matched = find_unused_code(conn, user_id, &normalize(code))?;
+
+    let (Some(user_id), Some(code_id)) = (user_id, matched) else {
+        login_attempt_service::record_attempt(
+            conn,
+            email,
+            user_id,
+            LoginOutcome::InvalidCredentials,
+        )?;
+        return Err(AppError::invalid("Invalid email or recovery code"));
+    };
+
+    let now = Utc::now().to_rfc3339();
+
+    let tx = conn.unchecked_transaction()?;
+
+    tx.execute(
+        "UPDATE recovery_codes SET used_at = ?2 WHERE id = ?1",
+        params![code_id, now],
+    )?;
+
+    tx.execute(
+        "UPDATE users SET password_hash = ?2 WHERE id = ?1",
+        params![user_id, user_service::hash_password(new_password)?],
+    )?;
+
+    account_service::revoke_all_logins(&tx, user_id)?;
+
+    tx.commit()?;
+
+    login_attempt_service::record_attempt(conn, email, Some(user_id), LoginOutcome::Succeeded)?;
+
+    Ok(user_id)
+}
+
+/* ===========================
+   HELPERS
+=========================== */
+
+fn generate_code() -> String {
+    let chars: String = (0..CODE_LENGTH)
+        .map(|_| {
+            let index = OsRng.next_u32() as usize % CODE_ALPHABET.len();
+            CODE_ALPHABET[index] as char
+        })
+        .collect();
+
+    format!("{}-{}", &chars[..CODE_LENGTH / 2], &chars[CODE_LENGTH / 2..])
+}
+
+/// Codes are accepted case-insensitively, with or without separators.
+fn normalize(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Checks the code against every unused one. Always runs `CODE_COUNT`
+/// verifications, padding with the dummy hash, so the time taken doesn't
+/// reveal whether the account exists or how many codes it has left.
+fn find_unused_code(
+    conn: &Connection,
+    user_id: Option<i64>,
+    code: &str,
+) -> AppResult<Option<i64>> {
+
+    let mut stmt = conn.prepare(
+        "SELECT id, code_hash FROM recovery_codes
+         WHERE user_id = ?1 AND used_at IS NULL",
+    )?;
+
+    let rows = stmt
+        .query_map(params![user_id], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut matched = None;
+
+    // No early exit on a match, for the same reason.
+    for (id, hash) in padded(rows) {
+        if user_service::verify_password(&hash, code) && matched.is_none() {
+            matched = id;
+        }
+    }
+
+    Ok(matched)
+}
+
+/// Exactly `CODE_COUNT` candidates; the dummy ones carry no id.
+fn padded(rows: Vec<(i64, String)>) -> Vec<(Option<i64>, String)> {
+    let dummy = user_service::DUMMY_HASH.clone().unwrap_or_default();
+
+    rows.into_iter()
+        .map(|(id, hash)| (Some(id), hash))
+        .chain(std::iter::repeat((None, dummy)))
+        .take(CODE_COUNT)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_in_memory;
+
+    #[test]
+    fn always_checks_the_same_number_of_hashes() {
+        assert_eq!(padded(Vec::new()).len(), CODE_COUNT);
+
+        let rows: Vec<(i64, String)> = (0..3).map(|id| (id, format!("hash-{}", id))).collect();
+        let candidates = padded(rows);
+
+        assert_eq!(candidates.len(), CODE_COUNT);
+        assert_eq!(candidates.iter().filter(|(id, _)| id.is_some()).count(), 3);
+    }
+
+    #[test]
+    fn resets_the_password_once_per_code() {
+        let conn = open_in_memory();
+        let user = user_service::create_user(&conn, "a@example.com", "Old-passphrase-42").unwrap();
+
+        let codes = generate_recovery_codes(&conn, user.id).unwrap();
+        let code = codes[0].to_lowercase().replace('-', " ");
+
+        let policy = PasswordPolicy::default();
+        let new_password = "New-passphrase-42";
+
+        assert!(reset_password_with_recovery_code(&conn, &policy, "a@example.com", "WRONG-CODE1", new_password).is_err());
+        assert!(reset_password_with_recovery_code(&conn, &policy, "nobody@example.com", &code, new_password).is_err());
+
+        let reset = reset_password_with_recovery_code(&conn, &policy, "a@example.com", &code, new_password);
+        assert_eq!(reset.ok(), Some(user.id));
+        assert_eq!(count_remaining_codes(&conn, user.id).unwrap(), CODE_COUNT as i64 - 1);
+
+        assert!(reset_password_with_recovery_code(&conn, &policy, "a@example.com", &code, new_password).is_err());
+    }
+}
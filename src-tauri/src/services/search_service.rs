@@ -0,0 +1,108 @@
+use rusqlite::{params, Connection};
+
+use crate::error::AppResult;
+use crate::models::session::{SearchHit, SessionListItem, SessionStatus};
+use crate::services::session_service::{
+    load_tags,
+    GROSS_SECONDS_SQL,
+    PAUSED_SECONDS_SQL,
+};
+
+const DEFAULT_LIMIT: u32 = 25;
+const MAX_LIMIT: u32 = 100;
+
+/* ===========================
+   SEARCH SESSIONS
+=========================== */
+
+pub fn search_sessions(
+    conn: &Connection,
+    user_id: i64,
+    query: &str,
+    limit: Option<u32>,
+) -> AppResult<Vec<SearchHit>> {
+
+    let Some(match_expr) = build_match_expr(query) else {
+        return Ok(Vec::new());
+    };
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let sql = format!(
+        "SELECT s.id, s.project_id, s.start_time, s.end_time, s.description, s.status,
+                {gross}, {paused},
+                snippet(session_search, 0, char(2), char(3), '…', 12),
+                highlight(session_search, 1, char(2), char(3)),
+                session_search.rank,
+                s.billable, s.invoice_id
+         FROM session_search
+         JOIN sessions s ON s.id = session_search.rowid
+         WHERE session_search MATCH ?1
+           AND s.user_id = ?2
+           AND s.status != 'cancelled'
+         ORDER BY session_search.rank
+         LIMIT ?3",
+        gross = GROSS_SECONDS_SQL,
+        paused = PAUSED_SECONDS_SQL,
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+
+    let rows = stmt
+        .query_map(params![match_expr, user_id, limit], |row| {
+            let status_str: String = row.get(5)?;
+            let gross_seconds: i64 = row.get(6)?;
+            let paused_seconds: i64 = row.get(7)?;
+
+            Ok(SearchHit {
+                session: SessionListItem {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    start_time: row.get(2)?,
+                    end_time: row.get(3)?,
+                    description: row.get(4)?,
+                    status: SessionStatus::from_db(&status_str),
+                    tags: Vec::new(),
+                    gross_seconds,
+                    net_seconds: gross_seconds - paused_seconds,
+                    billable: row.get(11)?,
+                    invoice_id: row.get(12)?,
+                },
+                description_snippet: row.get(8)?,
+                tags_snippet: row.get(9)?,
+                rank: row.get(10)?,
+            })
+        })?;
+
+    let mut hits = rows
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let ids: Vec<i64> = hits.iter().map(|h| h.session.id).collect();
+    let mut tags = load_tags(conn, &ids)?;
+
+    for hit in &mut hits {
+        hit.session.tags = tags.remove(&hit.session.id).unwrap_or_default();
+    }
+
+    Ok(hits)
+}
+
+/* ===========================
+   HELPERS
+=========================== */
+
+/// Turns free text into an FTS5 expression where every word must match
+/// as a prefix. Each word is quoted so user input can't inject FTS5
+/// operators or trigger syntax errors.
+fn build_match_expr(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
@@ -1,13 +1,44 @@
-use rusqlite::{params, Connection};
-use chrono::Utc;
+use rusqlite::{params, params_from_iter, Connection, ToSql};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::error::{AppError, AppResult};
+use crate::services::project_service;
 
 use crate::models::session::{
     DbSession,
     ActiveSessionResponse,
     FinishedSessionResponse,
+    SessionListItem,
+    SessionPage,
+    SessionQuery,
     SessionStatus,
+    SortOrder,
+    TagMatch,
 };
 
+/* ===========================
+   DURATION SQL
+=========================== */
+
+// Both expressions expect the session row aliased as `s`; open intervals
+// run until now. Pauses are clipped to the session's own range so an
+// edited session never reports negative worked time.
+
+pub(crate) const GROSS_SECONDS_SQL: &str =
+    "CAST(ROUND((julianday(COALESCE(s.end_time, 'now')) - julianday(s.start_time)) * 86400) AS INTEGER)";
+
+pub(crate) const PAUSED_SECONDS_SQL: &str =
+    "(SELECT CAST(ROUND(COALESCE(SUM(MAX(0,
+         MIN(julianday(COALESCE(p.end_time, 'now')), julianday(COALESCE(s.end_time, 'now')))
+         - MAX(julianday(p.start_time), julianday(s.start_time))
+     )), 0) * 86400) AS INTEGER)
+     FROM session_pauses p WHERE p.session_id = s.id)";
+
+pub(crate) fn net_seconds_sql() -> String {
+    format!("({} - {})", GROSS_SECONDS_SQL, PAUSED_SECONDS_SQL)
+}
+
 /* ===========================
    ACTIVE SESSION
 =========================== */
@@ -15,24 +46,28 @@ use crate::models::session::{
 pub fn get_active_session(
     conn: &Connection,
     user_id: i64,
-) -> Result<Option<ActiveSessionResponse>, String> {
+) -> AppResult<Option<ActiveSessionResponse>> {
+
+    let sql = format!(
+        "SELECT s.id, s.project_id, s.start_time, s.end_time, s.description, s.status,
+                {gross}, {paused}
+         FROM sessions s
+         WHERE s.user_id = ?1 AND s.status IN ('running','paused')
+         ORDER BY s.start_time DESC
+         LIMIT 1",
+        gross = GROSS_SECONDS_SQL,
+        paused = PAUSED_SECONDS_SQL,
+    );
 
     let result = conn.query_row(
-        "SELECT id, project_id, start_time, end_time, description, status
-         FROM sessions
-         WHERE user_id = ?1 AND status = 'running'
-         ORDER BY start_time DESC
-         LIMIT 1",
+        &sql,
         params![user_id],
         |row| {
             let status_str: String = row.get(5)?;
+            let status = SessionStatus::from_db(&status_str);
 
-            let status = match status_str.as_str() {
-                "running" => SessionStatus::Running,
-                "finished" => SessionStatus::Finished,
-                "cancelled" => SessionStatus::Cancelled,
-                _ => SessionStatus::Running,
-            };
+            let gross_seconds: i64 = row.get(6)?;
+            let paused_seconds: i64 = row.get(7)?;
 
             Ok(ActiveSessionResponse {
                 id: row.get(0)?,
@@ -41,13 +76,16 @@ pub fn get_active_session(
                 end_time: row.get(3)?,
                 description: row.get(4)?,
                 status,
+                gross_seconds,
+                net_seconds: gross_seconds - paused_seconds,
             })
         },
     );
 
     match result {
         Ok(session) => Ok(Some(session)),
-        Err(_) => Ok(None),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
     }
 }
 
@@ -59,10 +97,18 @@ pub fn start_session(
     conn: &Connection,
     user_id: i64,
     project_id: Option<i64>,
-) -> Result<ActiveSessionResponse, String> {
+) -> AppResult<ActiveSessionResponse> {
 
     if get_active_session(conn, user_id)?.is_some() {
-        return Err("There is already an active session".into());
+        return Err(AppError::Conflict("There is already an active session".into()));
+    }
+
+    if let Some(pid) = project_id {
+        let project = project_service::get_project_by_id(conn, user_id, pid)?;
+
+        if project.archived_at.is_some() {
+            return Err(AppError::Conflict("Project is archived".into()));
+        }
     }
 
     let now = Utc::now().to_rfc3339();
@@ -71,8 +117,7 @@ pub fn start_session(
         "INSERT INTO sessions (user_id, project_id, start_time, status)
          VALUES (?1, ?2, ?3, 'running')",
         params![user_id, project_id, now],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
 
     let id = conn.last_insert_rowid();
 
@@ -83,9 +128,81 @@ pub fn start_session(
         end_time: None,
         description: None,
         status: SessionStatus::Running,
+        gross_seconds: 0,
+        net_seconds: 0,
     })
 }
 
+/* ===========================
+   PAUSE SESSION
+=========================== */
+
+pub fn pause_session(
+    conn: &mut Connection,
+    user_id: i64,
+) -> AppResult<ActiveSessionResponse> {
+
+    let session = get_active_session(conn, user_id)?
+        .ok_or(AppError::NotFound("Active session"))?;
+
+    if !matches!(session.status, SessionStatus::Running) {
+        return Err(AppError::Conflict("Invalid session state".into()));
+    }
+
+    let now = Utc::now().to_rfc3339();
+
+    let tx = conn.transaction()?;
+
+    tx.execute(
+        "INSERT INTO session_pauses (session_id, start_time)
+         VALUES (?1, ?2)",
+        params![session.id, now],
+    )?;
+
+    tx.execute(
+        "UPDATE sessions SET status = 'paused' WHERE id = ?1",
+        params![session.id],
+    )?;
+
+    tx.commit()?;
+
+    get_active_session(conn, user_id)?
+        .ok_or(AppError::NotFound("Session"))
+}
+
+/* ===========================
+   RESUME SESSION
+=========================== */
+
+pub fn resume_session(
+    conn: &mut Connection,
+    user_id: i64,
+) -> AppResult<ActiveSessionResponse> {
+
+    let session = get_active_session(conn, user_id)?
+        .ok_or(AppError::NotFound("Active session"))?;
+
+    if !matches!(session.status, SessionStatus::Paused) {
+        return Err(AppError::Conflict("Invalid session state".into()));
+    }
+
+    let now = Utc::now().to_rfc3339();
+
+    let tx = conn.transaction()?;
+
+    close_open_pause(&tx, session.id, &now)?;
+
+    tx.execute(
+        "UPDATE sessions SET status = 'running' WHERE id = ?1",
+        params![session.id],
+    )?;
+
+    tx.commit()?;
+
+    get_active_session(conn, user_id)?
+        .ok_or(AppError::NotFound("Session"))
+}
+
 /* ===========================
    FINALIZE SESSION
 =========================== */
@@ -96,17 +213,21 @@ pub fn finalize_session(
     session_id: i64,
     description: String,
     tags: Vec<String>,
-) -> Result<(), String> {
+) -> AppResult<()> {
 
     let session = get_session_by_id(conn, session_id)?;
 
-    if session.user_id != user_id || session.status != "running" {
-        return Err("Invalid session state".into());
+    if session.user_id != user_id
+        || (session.status != "running" && session.status != "paused")
+    {
+        return Err(AppError::Conflict("Invalid session state".into()));
     }
 
     let now = Utc::now().to_rfc3339();
 
-    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let tx = conn.transaction()?;
+
+    close_open_pause(&tx, session_id, &now)?;
 
     tx.execute(
         "UPDATE sessions
@@ -115,25 +236,175 @@ pub fn finalize_session(
              description = ?2
          WHERE id = ?3",
         params![now, description, session_id],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
+
+    replace_tags(&tx, session_id, &tags)?;
+
+    tx.commit()?;
+
+    Ok(())
+}
+
+/* ===========================
+   MANUAL SESSION
+=========================== */
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_manual_session(
+    conn: &mut Connection,
+    user_id: i64,
+    project_id: Option<i64>,
+    start_time: &str,
+    end_time: &str,
+    description: String,
+    tags: Vec<String>,
+    allow_overlap: bool,
+) -> AppResult<FinishedSessionResponse> {
+
+    let (start, end) = parse_time_range(start_time, end_time)?;
+
+    if let Some(pid) = project_id {
+        project_service::get_project_by_id(conn, user_id, pid)?;
+    }
+
+    if !allow_overlap && find_overlap(conn, user_id, &start, &end, None)?.is_some() {
+        return Err(AppError::Conflict("Session overlaps an existing session".into()));
+    }
+
+    let tx = conn.transaction()?;
+
+    tx.execute(
+        "INSERT INTO sessions (user_id, project_id, start_time, end_time, description, status)
+         VALUES (?1, ?2, ?3, ?4, ?5, 'finished')",
+        params![user_id, project_id, start, end, description],
+    )?;
+
+    let id = tx.last_insert_rowid();
+
+    replace_tags(&tx, id, &tags)?;
+
+    tx.commit()?;
+
+    get_finished_session(conn, id)
+}
+
+/* ===========================
+   UPDATE SESSION
+=========================== */
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_session(
+    conn: &mut Connection,
+    user_id: i64,
+    session_id: i64,
+    project_id: Option<i64>,
+    start_time: &str,
+    end_time: &str,
+    description: String,
+    tags: Vec<String>,
+    allow_overlap: bool,
+) -> AppResult<FinishedSessionResponse> {
+
+    let session = get_owned_session(conn, user_id, session_id)?;
+
+    if session.status != "finished" {
+        return Err(AppError::Conflict("Invalid session state".into()));
+    }
+
+    ensure_not_invoiced(&session)?;
+
+    let (start, end) = parse_time_range(start_time, end_time)?;
+
+    if let Some(pid) = project_id {
+        project_service::get_project_by_id(conn, user_id, pid)?;
+    }
+
+    if !allow_overlap
+        && find_overlap(conn, user_id, &start, &end, Some(session_id))?.is_some()
+    {
+        return Err(AppError::Conflict("Session overlaps an existing session".into()));
+    }
+
+    let tx = conn.transaction()?;
+
+    tx.execute(
+        "UPDATE sessions
+         SET project_id = ?1,
+             start_time = ?2,
+             end_time = ?3,
+             description = ?4
+         WHERE id = ?5",
+        params![project_id, start, end, description, session_id],
+    )?;
+
+    replace_tags(&tx, session_id, &tags)?;
+
+    tx.commit()?;
+
+    get_finished_session(conn, session_id)
+}
+
+/* ===========================
+   BILLABLE
+=========================== */
+
+/// Overrides the project's billable default for one session; `None`
+/// goes back to following the project.
+pub fn set_session_billable(
+    conn: &Connection,
+    user_id: i64,
+    session_id: i64,
+    billable: Option<bool>,
+) -> AppResult<()> {
+
+    let session = get_owned_session(conn, user_id, session_id)?;
+
+    ensure_not_invoiced(&session)?;
+
+    conn.execute(
+        "UPDATE sessions SET billable = ?1 WHERE id = ?2",
+        params![billable, session_id],
+    )?;
+
+    Ok(())
+}
+
+/* ===========================
+   DELETE SESSION
+=========================== */
+
+pub fn delete_session(
+    conn: &mut Connection,
+    user_id: i64,
+    session_id: i64,
+) -> AppResult<()> {
+
+    let session = get_owned_session(conn, user_id, session_id)?;
+
+    if session.status == "running" || session.status == "paused" {
+        return Err(AppError::Conflict("Invalid session state".into()));
+    }
+
+    ensure_not_invoiced(&session)?;
+
+    let tx = conn.transaction()?;
 
     tx.execute(
         "DELETE FROM session_tags WHERE session_id = ?1",
         params![session_id],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
 
-    for tag in tags {
-        tx.execute(
-            "INSERT INTO session_tags (session_id, tag)
-             VALUES (?1, ?2)",
-            params![session_id, tag],
-        )
-        .map_err(|e| e.to_string())?;
-    }
+    tx.execute(
+        "DELETE FROM session_pauses WHERE session_id = ?1",
+        params![session_id],
+    )?;
 
-    tx.commit().map_err(|e| e.to_string())?;
+    tx.execute(
+        "DELETE FROM sessions WHERE id = ?1",
+        params![session_id],
+    )?;
+
+    tx.commit()?;
 
     Ok(())
 }
@@ -143,17 +414,33 @@ pub fn finalize_session(
 =========================== */
 
 pub fn cancel_session(
-    conn: &Connection,
+    conn: &mut Connection,
     user_id: i64,
-) -> Result<(), String> {
+) -> AppResult<()> {
 
-    conn.execute(
+    let now = Utc::now().to_rfc3339();
+
+    let tx = conn.transaction()?;
+
+    tx.execute(
+        "UPDATE session_pauses
+         SET end_time = ?1
+         WHERE end_time IS NULL
+           AND session_id IN (
+               SELECT id FROM sessions
+               WHERE user_id = ?2 AND status = 'paused'
+           )",
+        params![now, user_id],
+    )?;
+
+    tx.execute(
         "UPDATE sessions
          SET status = 'cancelled'
-         WHERE user_id = ?1 AND status = 'running'",
+         WHERE user_id = ?1 AND status IN ('running','paused')",
         params![user_id],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
+
+    tx.commit()?;
 
     Ok(())
 }
@@ -165,71 +452,406 @@ pub fn cancel_session(
 pub fn get_finished_sessions(
     conn: &Connection,
     user_id: i64,
-) -> Result<Vec<FinishedSessionResponse>, String> {
+) -> AppResult<Vec<FinishedSessionResponse>> {
+
+    let sql = format!(
+        "SELECT s.id, s.project_id, s.start_time, s.end_time, s.description,
+                {gross}, {paused}
+         FROM sessions s
+         WHERE s.user_id = ?1 AND s.status = 'finished'
+         ORDER BY s.start_time DESC",
+        gross = GROSS_SECONDS_SQL,
+        paused = PAUSED_SECONDS_SQL,
+    );
 
-    let mut stmt = conn.prepare(
-        "SELECT id, project_id, start_time, end_time, description
-         FROM sessions
-         WHERE user_id = ?1 AND status = 'finished'
-         ORDER BY start_time DESC",
-    )
-    .map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(&sql)?;
 
     let rows = stmt
-        .query_map(params![user_id], |row| {
-            Ok((
-                row.get::<_, i64>(0)?,
-                row.get::<_, Option<i64>>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, String>(3)?,
-                row.get::<_, Option<String>>(4)?,
-            ))
-        })
-        .map_err(|e| e.to_string())?;
-
-    let mut result = Vec::new();
-
-    for r in rows {
-        let (id, project_id, start_time, end_time, description) =
-            r.map_err(|e| e.to_string())?;
-
-        let mut tag_stmt = conn.prepare(
-            "SELECT tag FROM session_tags WHERE session_id = ?1",
-        ).map_err(|e| e.to_string())?;
-
-        let tag_rows = tag_stmt
-            .query_map(params![id], |row| row.get(0))
-            .map_err(|e| e.to_string())?;
-
-        let mut tags = Vec::new();
-        for t in tag_rows {
-            tags.push(t.map_err(|e| e.to_string())?);
+        .query_map(params![user_id], finished_row)?;
+
+    let mut result = rows
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let ids: Vec<i64> = result.iter().map(|s| s.id).collect();
+    let mut tags = load_tags(conn, &ids)?;
+
+    for session in &mut result {
+        session.tags = tags.remove(&session.id).unwrap_or_default();
+    }
+
+    Ok(result)
+}
+
+/* ===========================
+   QUERY SESSIONS
+=========================== */
+
+const DEFAULT_PAGE_SIZE: u32 = 50;
+const MAX_PAGE_SIZE: u32 = 200;
+
+pub fn query_sessions(
+    conn: &Connection,
+    user_id: i64,
+    query: &SessionQuery,
+) -> AppResult<SessionPage> {
+
+    let mut clauses: Vec<String> = vec!["s.user_id = ?".into()];
+    let mut values: Vec<Box<dyn ToSql>> = vec![Box::new(user_id)];
+
+    if let Some(ref from) = query.from {
+        clauses.push("s.start_time >= ?".into());
+        values.push(Box::new(parse_time(from)?.to_rfc3339()));
+    }
+
+    if let Some(ref to) = query.to {
+        clauses.push("s.start_time < ?".into());
+        values.push(Box::new(parse_time(to)?.to_rfc3339()));
+    }
+
+    if !query.project_ids.is_empty() {
+        clauses.push(format!(
+            "s.project_id IN ({})",
+            placeholders(query.project_ids.len())
+        ));
+        for id in &query.project_ids {
+            values.push(Box::new(*id));
         }
+    }
 
-        result.push(FinishedSessionResponse {
-            id,
-            project_id,
-            start_time,
-            end_time,
-            description,
-            tags,
+    if !query.statuses.is_empty() {
+        clauses.push(format!(
+            "s.status IN ({})",
+            placeholders(query.statuses.len())
+        ));
+        for status in &query.statuses {
+            values.push(Box::new(status.as_str()));
+        }
+    }
+
+    // Tags match case-insensitively, and a repeated tag counts once.
+    let tags: BTreeSet<String> = query
+        .tags
+        .iter()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    if !tags.is_empty() {
+        let list = placeholders(tags.len());
+
+        clauses.push(match query.tag_match {
+            TagMatch::Any => format!(
+                "s.id IN (SELECT session_id FROM session_tags WHERE lower(trim(tag)) IN ({}))",
+                list
+            ),
+            TagMatch::All => format!(
+                "(SELECT COUNT(DISTINCT lower(trim(tag))) FROM session_tags
+                  WHERE session_id = s.id AND lower(trim(tag)) IN ({})) = {}",
+                list,
+                tags.len()
+            ),
         });
+
+        for tag in tags {
+            values.push(Box::new(tag));
+        }
     }
 
-    Ok(result)
+    if let Some(text) = query.text.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        clauses.push("s.description LIKE ? ESCAPE '\\'".into());
+        values.push(Box::new(format!("%{}%", escape_like(text))));
+    }
+
+    // Keyset pagination on (start_time, id) keeps pages stable while
+    // new sessions are being recorded.
+    let (order, cmp) = match query.sort {
+        SortOrder::Asc => ("ASC", ">"),
+        SortOrder::Desc => ("DESC", "<"),
+    };
+
+    if let Some(ref cursor) = query.cursor {
+        let (start, id) = decode_cursor(cursor)?;
+        clauses.push(format!(
+            "(s.start_time {cmp} ? OR (s.start_time = ? AND s.id {cmp} ?))",
+            cmp = cmp
+        ));
+        values.push(Box::new(start.clone()));
+        values.push(Box::new(start));
+        values.push(Box::new(id));
+    }
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    // Fetch one extra row to know whether another page exists.
+    values.push(Box::new(limit + 1));
+
+    let sql = format!(
+        "SELECT s.id, s.project_id, s.start_time, s.end_time, s.description, s.status,
+                {gross}, {paused}, s.billable, s.invoice_id
+         FROM sessions s
+         WHERE {where_clause}
+         ORDER BY s.start_time {order}, s.id {order}
+         LIMIT ?",
+        gross = GROSS_SECONDS_SQL,
+        paused = PAUSED_SECONDS_SQL,
+        where_clause = clauses.join(" AND "),
+        order = order,
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+
+    let rows = stmt
+        .query_map(params_from_iter(values.iter()), |row| {
+            let status_str: String = row.get(5)?;
+            let gross_seconds: i64 = row.get(6)?;
+            let paused_seconds: i64 = row.get(7)?;
+
+            Ok(SessionListItem {
+                id: row.get(0)?,
+                project_id: row.get(1)?,
+                start_time: row.get(2)?,
+                end_time: row.get(3)?,
+                description: row.get(4)?,
+                status: SessionStatus::from_db(&status_str),
+                tags: Vec::new(),
+                gross_seconds,
+                net_seconds: gross_seconds - paused_seconds,
+                billable: row.get(8)?,
+                invoice_id: row.get(9)?,
+            })
+        })?;
+
+    let mut items = rows
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let next_cursor = if items.len() > limit as usize {
+        items.truncate(limit as usize);
+        items.last().map(|s| encode_cursor(&s.start_time, s.id))
+    } else {
+        None
+    };
+
+    let ids: Vec<i64> = items.iter().map(|s| s.id).collect();
+    let mut tags = load_tags(conn, &ids)?;
+
+    for item in &mut items {
+        item.tags = tags.remove(&item.id).unwrap_or_default();
+    }
+
+    Ok(SessionPage { items, next_cursor })
 }
 
 /* ===========================
-   HELPER
+   HELPERS
 =========================== */
 
+pub(crate) fn parse_time(value: &str) -> AppResult<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|_| AppError::invalid(format!("Invalid timestamp: {}", value)))
+}
+
+/// Parses both ends as RFC 3339 and normalizes them to the UTC form
+/// `start_session` writes, so stored timestamps stay comparable.
+fn parse_time_range(
+    start_time: &str,
+    end_time: &str,
+) -> AppResult<(String, String)> {
+
+    let start = parse_time(start_time)
+        .map_err(|e| AppError::field("start_time", e.to_string()))?;
+    let end = parse_time(end_time)
+        .map_err(|e| AppError::field("end_time", e.to_string()))?;
+
+    if end <= start {
+        return Err(AppError::field("end_time", "End time must be after start time"));
+    }
+
+    Ok((start.to_rfc3339(), end.to_rfc3339()))
+}
+
+/// Returns the id of an active or finished session that intersects
+/// `[start, end)`. An active session is treated as ending now.
+pub(crate) fn find_overlap(
+    conn: &Connection,
+    user_id: i64,
+    start: &str,
+    end: &str,
+    exclude_id: Option<i64>,
+) -> AppResult<Option<i64>> {
+
+    let now = Utc::now().to_rfc3339();
+
+    let result = conn.query_row(
+        "SELECT id FROM sessions
+         WHERE user_id = ?1
+           AND status IN ('running','paused','finished')
+           AND id IS NOT ?2
+           AND julianday(start_time) < julianday(?4)
+           AND julianday(COALESCE(end_time, ?5)) > julianday(?3)
+         LIMIT 1",
+        params![user_id, exclude_id, start, end, now],
+        |row| row.get(0),
+    );
+
+    match result {
+        Ok(id) => Ok(Some(id)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub(crate) fn replace_tags(
+    conn: &Connection,
+    session_id: i64,
+    tags: &[String],
+) -> AppResult<()> {
+
+    conn.execute(
+        "DELETE FROM session_tags WHERE session_id = ?1",
+        params![session_id],
+    )?;
+
+    for tag in tags {
+        conn.execute(
+            "INSERT INTO session_tags (session_id, tag)
+             VALUES (?1, ?2)",
+            params![session_id, tag],
+        )?;
+    }
+
+    Ok(())
+}
+
+fn close_open_pause(
+    conn: &Connection,
+    session_id: i64,
+    now: &str,
+) -> AppResult<()> {
+
+    conn.execute(
+        "UPDATE session_pauses
+         SET end_time = ?1
+         WHERE session_id = ?2 AND end_time IS NULL",
+        params![now, session_id],
+    )?;
+
+    Ok(())
+}
+
+/// Loads tags for many sessions in one query, keyed by session id.
+pub(crate) fn load_tags(
+    conn: &Connection,
+    session_ids: &[i64],
+) -> AppResult<HashMap<i64, Vec<String>>> {
+
+    let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+
+    // Chunked to stay well below SQLite's bound-parameter limit.
+    for chunk in session_ids.chunks(500) {
+        let sql = format!(
+            "SELECT session_id, tag FROM session_tags
+             WHERE session_id IN ({})
+             ORDER BY rowid",
+            placeholders(chunk.len())
+        );
+
+        let mut stmt = conn.prepare(&sql)?;
+
+        let rows = stmt
+            .query_map(params_from_iter(chunk.iter()), |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?;
+
+        for r in rows {
+            let (id, tag) = r?;
+            tags.entry(id).or_default().push(tag);
+        }
+    }
+
+    Ok(tags)
+}
+
+fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(", ")
+}
+
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+fn encode_cursor(start_time: &str, id: i64) -> String {
+    format!("{}|{}", start_time, id)
+}
+
+fn decode_cursor(cursor: &str) -> AppResult<(String, i64)> {
+    let (start, id) = cursor
+        .rsplit_once('|')
+        .ok_or_else(|| AppError::field("cursor", "Invalid cursor"))?;
+
+    let id = id
+        .parse()
+        .map_err(|_| AppError::field("cursor", "Invalid cursor"))?;
+
+    Ok((start.to_string(), id))
+}
+
+/// Maps `id, project_id, start_time, end_time, description, gross, paused`.
+/// Tags are left empty for the caller to fill in.
+fn finished_row(row: &rusqlite::Row) -> rusqlite::Result<FinishedSessionResponse> {
+    let gross_seconds: i64 = row.get(5)?;
+    let paused_seconds: i64 = row.get(6)?;
+
+    Ok(FinishedSessionResponse {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        start_time: row.get(2)?,
+        end_time: row.get(3)?,
+        description: row.get(4)?,
+        tags: Vec::new(),
+        gross_seconds,
+        net_seconds: gross_seconds - paused_seconds,
+    })
+}
+
+fn get_finished_session(
+    conn: &Connection,
+    id: i64,
+) -> AppResult<FinishedSessionResponse> {
+
+    let sql = format!(
+        "SELECT s.id, s.project_id, s.start_time, s.end_time, s.description,
+                {gross}, {paused}
+         FROM sessions s
+         WHERE s.id = ?1 AND s.status = 'finished'",
+        gross = GROSS_SECONDS_SQL,
+        paused = PAUSED_SECONDS_SQL,
+    );
+
+    let mut session = conn
+        .query_row(&sql, params![id], finished_row)
+        .map_err(|_| AppError::NotFound("Session"))?;
+
+    session.tags = load_tags(conn, &[id])?
+        .remove(&id)
+        .unwrap_or_default();
+
+    Ok(session)
+}
+
 fn get_session_by_id(
     conn: &Connection,
     id: i64,
-) -> Result<DbSession, String> {
+) -> AppResult<DbSession> {
 
     conn.query_row(
-        "SELECT id, user_id, project_id, start_time, end_time, description, status
+        "SELECT id, user_id, project_id, start_time, end_time, description, status, invoice_id
          FROM sessions WHERE id = ?1",
         params![id],
         |row| {
@@ -241,8 +863,79 @@ fn get_session_by_id(
                 end_time: row.get(4)?,
                 description: row.get(5)?,
                 status: row.get(6)?,
+                invoice_id: row.get(7)?,
             })
         },
     )
-    .map_err(|_| "Session not found".into())
-}
\ No newline at end of file
+    .map_err(|_| AppError::NotFound("Session"))
+}
+
+/// Like `get_session_by_id`, but reports sessions belonging to someone
+/// else as missing rather than leaking their existence.
+fn get_owned_session(
+    conn: &Connection,
+    user_id: i64,
+    id: i64,
+) -> AppResult<DbSession> {
+
+    let session = get_session_by_id(conn, id)?;
+
+    if session.user_id != user_id {
+        return Err(AppError::NotFound("Session"));
+    }
+
+    Ok(session)
+}
+
+/// Billed sessions are frozen so invoices keep matching their sessions.
+fn ensure_not_invoiced(session: &DbSession) -> AppResult<()> {
+    if session.invoice_id.is_some() {
+        return Err(AppError::Conflict("Session is on an invoice".into()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::open_in_memory;
+    use crate::services::user_service;
+
+    #[test]
+    fn tag_filters_ignore_case_and_repeats() {
+        let conn = open_in_memory();
+        let user = user_service::create_user(&conn, "a@example.com", "Old-passphrase-42").unwrap();
+
+        for (day, tags) in [("05", vec!["bug", "API"]), ("06", vec!["bug"]), ("07", vec!["docs"])] {
+            conn.execute(
+                "INSERT INTO sessions (user_id, start_time, end_time, status)
+                 VALUES (?1, ?2, ?3, 'finished')",
+                params![
+                    user.id,
+                    format!("2026-01-{}T09:00:00+00:00", day),
+                    format!("2026-01-{}T10:00:00+00:00", day),
+                ],
+            )
+            .unwrap();
+
+            let tags: Vec<String> = tags.into_iter().map(String::from).collect();
+            replace_tags(&conn, conn.last_insert_rowid(), &tags).unwrap();
+        }
+
+        let count = |tags: &[&str], tag_match: TagMatch| {
+            let query = SessionQuery {
+                tags: tags.iter().map(|t| t.to_string()).collect(),
+                tag_match,
+                ..Default::default()
+            };
+
+            query_sessions(&conn, user.id, &query).unwrap().items.len()
+        };
+
+        assert_eq!(count(&["bug", "bug"], TagMatch::All), 2);
+        assert_eq!(count(&[" Bug ", "api"], TagMatch::All), 1);
+        assert_eq!(count(&["BUG", "Docs"], TagMatch::Any), 3);
+        assert_eq!(count(&["", "  "], TagMatch::All), 3);
+    }
+}
@@ -0,0 +1,392 @@
+use rusqlite::{params, Connection};
+
+use crate::error::{AppError, AppResult};
+use crate::models::stats::{
+    ClientDuration,
+    DurationTotal,
+    Granularity,
+    PeriodDuration,
+    PeriodEarnings,
+    ProjectDuration,
+    ProjectEarnings,
+    StatsFilter,
+    TagDuration,
+};
+use crate::services::rate_service::{amount_cents_sql, rate_for_session_sql, BILLABLE_SQL};
+use crate::services::session_service::{net_seconds_sql, parse_time};
+
+/* ===========================
+   FILTER
+=========================== */
+
+// Every query binds ?1 user_id, ?2 from, ?3 to, ?4 project_id,
+// ?5 client_id. Bounds are normalized to the same UTC RFC 3339 form
+// sessions are stored in, so plain string comparison can use the
+// start_time index. Projects nest one level deep and only top-level
+// projects carry a client.
+const FILTER_SQL: &str =
+    "s.user_id = ?1
+     AND s.status = 'finished'
+     AND (?2 IS NULL OR s.start_time >= ?2)
+     AND (?3 IS NULL OR s.start_time < ?3)
+     AND (?4 IS NULL OR s.project_id IN (
+             SELECT id FROM projects WHERE id = ?4 OR parent_id = ?4
+         ))
+     AND (?5 IS NULL OR s.project_id IN (
+             SELECT p.id FROM projects p
+             JOIN projects r ON r.id = COALESCE(p.parent_id, p.id)
+             WHERE r.client_id = ?5
+         ))";
+
+// Joins `p`, the session's project, and `r`, its top-level project.
+pub(crate) const TOP_LEVEL_JOIN_SQL: &str =
+    "LEFT JOIN projects p ON p.id = s.project_id
+     LEFT JOIN projects r ON r.id = COALESCE(p.parent_id, p.id)";
+
+struct Bounds {
+    from: Option<String>,
+    to: Option<String>,
+}
+
+fn bounds(filter: &StatsFilter) -> AppResult<Bounds> {
+    let normalize = |value: &Option<String>| -> AppResult<Option<String>> {
+        value
+            .as_deref()
+            .map(|v| parse_time(v).map(|t| t.to_rfc3339()))
+            .transpose()
+    };
+
+    Ok(Bounds {
+        from: normalize(&filter.from)?,
+        to: normalize(&filter.to)?,
+    })
+}
+
+/* ===========================
+   TOTAL
+=========================== */
+
+pub fn get_total_duration(
+    conn: &Connection,
+    user_id: i64,
+    filter: &StatsFilter,
+) -> AppResult<DurationTotal> {
+
+    let b = bounds(filter)?;
+
+    let sql = format!(
+        "SELECT COALESCE(SUM({net}), 0), COUNT(*)
+         FROM sessions s
+         WHERE {filter}",
+        net = net_seconds_sql(),
+        filter = FILTER_SQL,
+    );
+
+    conn.query_row(
+        &sql,
+        params![user_id, b.from, b.to, filter.project_id, filter.client_id],
+        |row| {
+            Ok(DurationTotal {
+                total_seconds: row.get(0)?,
+                session_count: row.get(1)?,
+            })
+        },
+    )
+    .map_err(AppError::from)
+}
+
+/* ===========================
+   BY PROJECT
+=========================== */
+
+/// With `roll_up`, sub-project time counts toward the top-level project.
+pub fn get_duration_by_project(
+    conn: &Connection,
+    user_id: i64,
+    filter: &StatsFilter,
+    roll_up: bool,
+) -> AppResult<Vec<ProjectDuration>> {
+
+    let b = bounds(filter)?;
+
+    let project = if roll_up { "r" } else { "p" };
+
+    let sql = format!(
+        "SELECT {project}.id, {project}.name, {project}.color, SUM({net}) AS seconds
+         FROM sessions s
+         {join}
+         WHERE {filter}
+         GROUP BY {project}.id
+         ORDER BY seconds DESC",
+        project = project,
+        join = TOP_LEVEL_JOIN_SQL,
+        net = net_seconds_sql(),
+        filter = FILTER_SQL,
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+
+    let rows = stmt
+        .query_map(
+            params![user_id, b.from, b.to, filter.project_id, filter.client_id],
+            |row| {
+                Ok(ProjectDuration {
+                    project_id: row.get(0)?,
+                    project_name: row.get(1)?,
+                    color: row.get(2)?,
+                    seconds: row.get(3)?,
+                })
+            },
+        )?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(AppError::from)
+}
+
+/* ===========================
+   BY CLIENT
+=========================== */
+
+/// Sessions count toward the client of their top-level project.
+pub fn get_duration_by_client(
+    conn: &Connection,
+    user_id: i64,
+    filter: &StatsFilter,
+) -> AppResult<Vec<ClientDuration>> {
+
+    let b = bounds(filter)?;
+
+    let sql = format!(
+        "SELECT c.id, c.name, c.color, SUM({net}) AS seconds
+         FROM sessions s
+         {join}
+         LEFT JOIN clients c ON c.id = r.client_id
+         WHERE {filter}
+         GROUP BY c.id
+         ORDER BY seconds DESC",
+        join = TOP_LEVEL_JOIN_SQL,
+        net = net_seconds_sql(),
+        filter = FILTER_SQL,
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+
+    let rows = stmt
+        .query_map(
+            params![user_id, b.from, b.to, filter.project_id, filter.client_id],
+            |row| {
+                Ok(ClientDuration {
+                    client_id: row.get(0)?,
+                    client_name: row.get(1)?,
+                    color: row.get(2)?,
+                    seconds: row.get(3)?,
+                })
+            },
+        )?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(AppError::from)
+}
+
+/* ===========================
+   BY TAG
+=========================== */
+
+pub fn get_duration_by_tag(
+    conn: &Connection,
+    user_id: i64,
+    filter: &StatsFilter,
+) -> AppResult<Vec<TagDuration>> {
+
+    let b = bounds(filter)?;
+
+    // A session with several tags counts fully toward each of them.
+    let sql = format!(
+        "SELECT t.tag, SUM({net}) AS seconds
+         FROM sessions s
+         JOIN session_tags t ON t.session_id = s.id
+         WHERE {filter}
+         GROUP BY t.tag
+         ORDER BY seconds DESC",
+        net = net_seconds_sql(),
+        filter = FILTER_SQL,
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+
+    let rows = stmt
+        .query_map(
+            params![user_id, b.from, b.to, filter.project_id, filter.client_id],
+            |row| {
+                Ok(TagDuration {
+                    tag: row.get(0)?,
+                    seconds: row.get(1)?,
+                })
+            },
+        )?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(AppError::from)
+}
+
+/* ===========================
+   BY PERIOD
+=========================== */
+
+pub fn get_duration_by_period(
+    conn: &Connection,
+    user_id: i64,
+    filter: &StatsFilter,
+    granularity: Granularity,
+) -> AppResult<Vec<PeriodDuration>> {
+
+    let b = bounds(filter)?;
+
+    let sql = format!(
+        "SELECT {bucket} AS period, SUM({net})
+         FROM sessions s
+         WHERE {filter}
+         GROUP BY period
+         ORDER BY period ASC",
+        bucket = period_bucket_sql(granularity),
+        net = net_seconds_sql(),
+        filter = FILTER_SQL,
+    );
+
+    let offset = format!("{:+} minutes", filter.utc_offset_minutes);
+
+    let mut stmt = conn.prepare(&sql)?;
+
+    let rows = stmt
+        .query_map(
+            params![user_id, b.from, b.to, filter.project_id, filter.client_id, offset],
+            |row| {
+                Ok(PeriodDuration {
+                    period: row.get(0)?,
+                    seconds: row.get(1)?,
+                })
+            },
+        )?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(AppError::from)
+}
+
+/* ===========================
+   EARNINGS
+=========================== */
+
+fn earnings_sql(group_key: &str) -> String {
+    format!(
+        "SELECT {key}, hr.currency,
+                SUM({net}) AS seconds,
+                COALESCE(SUM({amount}), 0)
+         FROM sessions s
+         {join}
+         LEFT JOIN hourly_rates hr ON hr.id = {rate}
+         WHERE {filter}
+           AND {billable}",
+        key = group_key,
+        net = net_seconds_sql(),
+        amount = amount_cents_sql(&net_seconds_sql(), "hr.amount_cents"),
+        join = TOP_LEVEL_JOIN_SQL,
+        rate = rate_for_session_sql(),
+        filter = FILTER_SQL,
+        billable = BILLABLE_SQL,
+    )
+}
+
+/// Billable time and its value per project and currency. With `roll_up`,
+/// sub-projects count toward the top-level project.
+pub fn get_earnings_by_project(
+    conn: &Connection,
+    user_id: i64,
+    filter: &StatsFilter,
+    roll_up: bool,
+) -> AppResult<Vec<ProjectEarnings>> {
+
+    let b = bounds(filter)?;
+
+    let project = if roll_up { "r" } else { "p" };
+
+    let sql = format!(
+        "{select}
+         GROUP BY {project}.id, hr.currency
+         ORDER BY seconds DESC",
+        select = earnings_sql(&format!(
+            "{project}.id, {project}.name, {project}.color",
+            project = project
+        )),
+        project = project,
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+
+    let rows = stmt
+        .query_map(
+            params![user_id, b.from, b.to, filter.project_id, filter.client_id],
+            |row| {
+                Ok(ProjectEarnings {
+                    project_id: row.get(0)?,
+                    project_name: row.get(1)?,
+                    color: row.get(2)?,
+                    currency: row.get(3)?,
+                    billable_seconds: row.get(4)?,
+                    amount_cents: row.get(5)?,
+                })
+            },
+        )?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(AppError::from)
+}
+
+pub fn get_earnings_by_period(
+    conn: &Connection,
+    user_id: i64,
+    filter: &StatsFilter,
+    granularity: Granularity,
+) -> AppResult<Vec<PeriodEarnings>> {
+
+    let b = bounds(filter)?;
+
+    let sql = format!(
+        "{select}
+         GROUP BY period, hr.currency
+         ORDER BY period ASC",
+        select = earnings_sql(&format!("{} AS period", period_bucket_sql(granularity))),
+    );
+
+    let offset = format!("{:+} minutes", filter.utc_offset_minutes);
+
+    let mut stmt = conn.prepare(&sql)?;
+
+    let rows = stmt
+        .query_map(
+            params![user_id, b.from, b.to, filter.project_id, filter.client_id, offset],
+            |row| {
+                Ok(PeriodEarnings {
+                    period: row.get(0)?,
+                    currency: row.get(1)?,
+                    billable_seconds: row.get(2)?,
+                    amount_cents: row.get(3)?,
+                })
+            },
+        )?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(AppError::from)
+}
+
+/* ===========================
+   HELPERS
+=========================== */
+
+// ?6 is an SQLite date modifier such as '+120 minutes'.
+fn period_bucket_sql(granularity: Granularity) -> &'static str {
+    match granularity {
+        Granularity::Day => "date(s.start_time, ?6)",
+        Granularity::Week => "date(s.start_time, ?6, 'weekday 0', '-6 days')",
+        Granularity::Month => "strftime('%Y-%m', s.start_time, ?6)",
+    }
+}
@@ -8,41 +8,55 @@ use tauri::AppHandle;
 use tauri::Manager;
 use uuid::Uuid;
 use image::ImageFormat;
+use once_cell::sync::Lazy;
+use std::path::{Path, PathBuf};
 
-use crate::models::user::{DbUser, PublicUser};
+use crate::error::{AppError, AppResult};
+use crate::models::user::{DbUser, LoginOutcome, PublicUser};
+use crate::services::{account_service, backup_service, invoice_service, login_attempt_service};
+use crate::services::password_policy_service::PasswordPolicy;
 
 /* ===========================
    PASSWORD
 =========================== */
 
-fn hash_password(password: &str) -> Result<String, String> {
+pub(crate) fn hash_password(password: &str) -> AppResult<String> {
     let salt = SaltString::generate(&mut OsRng);
     let argon2 = Argon2::default();
 
     argon2
         .hash_password(password.as_bytes(), &salt)
-        .map_err(|e| e.to_string())
+        .map_err(|e| AppError::Internal(e.to_string()))
         .map(|hash| hash.to_string())
 }
 
-fn verify_password(hash: &str, password: &str) -> bool {
-    println!("[VERIFY] Incoming password length: {}", password.len());
-    println!("[VERIFY] Hash starts with: {}", &hash[..20.min(hash.len())]);
-
+pub(crate) fn verify_password(hash: &str, password: &str) -> bool {
     match PasswordHash::new(hash) {
-        Ok(parsed) => {
-            let result = Argon2::default()
-                .verify_password(password.as_bytes(), &parsed)
-                .is_ok();
+        Ok(parsed) => Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_ok(),
+        Err(_) => false,
+    }
+}
 
-            println!("[VERIFY] Verification result: {}", result);
-            result
-        }
-        Err(e) => {
-            println!("[VERIFY] Failed to parse hash: {:?}", e);
-            false
-        }
+// Verified against when the email is unknown, so a miss costs the same
+// Argon2 work as a wrong password.
+pub(crate) static DUMMY_HASH: Lazy<Option<String>> = Lazy::new(|| hash_password("trackly-dummy").ok());
+
+/// Re-checks the account password before a sensitive change.
+pub(crate) fn confirm_password(
+    conn: &Connection,
+    user_id: i64,
+    password: &str,
+) -> AppResult<()> {
+
+    let user = get_user_by_id_internal(conn, user_id)?;
+
+    if !verify_password(&user.password_hash, password) {
+        return Err(AppError::field("current_password", "Current password incorrect"));
     }
+
+    Ok(())
 }
 
 /* ===========================
@@ -53,7 +67,7 @@ pub fn create_user(
     conn: &Connection,
     email: &str,
     password: &str,
-) -> Result<PublicUser, String> {
+) -> AppResult<PublicUser> {
 
     let password_hash = hash_password(password)?;
 
@@ -63,12 +77,15 @@ pub fn create_user(
         .unwrap_or("user")
         .to_string();
 
+    if get_user_by_email(conn, email).is_some() {
+        return Err(AppError::Conflict("User already exists".into()));
+    }
+
     conn.execute(
         "INSERT INTO users (email, password_hash, display_name)
          VALUES (?1, ?2, ?3)",
         params![email, password_hash, display_name],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
 
     let id = conn.last_insert_rowid();
 
@@ -85,50 +102,44 @@ pub fn create_user(
    LOGIN
 =========================== */
 
+/// Verifies credentials only; see `account_service::issue_login` for
+/// persisting the login.
 pub fn login_user(
     conn: &Connection,
     email: &str,
     password: &str,
-) -> Result<DbUser, String> {
+) -> AppResult<DbUser> {
 
-    println!("[LOGIN] Attempt for email: {}", email);
+    let user = get_user_by_email(conn, email);
+    let user_id = user.as_ref().map(|u| u.id);
 
-    let user = match get_user_by_email(conn, email) {
-        Some(u) => {
-            println!("[LOGIN] User found: id={}", u.id);
-            println!("[LOGIN] Stored hash: {}", u.password_hash);
-            u
-        }
+    // Same checks and errors whether or not the account exists.
+    if let Err(e) = login_attempt_service::check_throttle(conn, email) {
+        login_attempt_service::record_attempt(conn, email, user_id, LoginOutcome::Throttled)?;
+        return Err(e);
+    }
+
+    let password_ok = match user {
+        Some(ref u) => verify_password(&u.password_hash, password),
         None => {
-            println!("[LOGIN] User NOT found for email: {}", email);
-            return Err("Invalid email or password".into());
+            if let Some(ref hash) = *DUMMY_HASH {
+                verify_password(hash, password);
+            }
+            false
         }
     };
 
-    let password_ok = verify_password(&user.password_hash, password);
-
-    println!("[LOGIN] Password verification result: {}", password_ok);
-
-    if !password_ok {
-        println!("[LOGIN] Password mismatch");
-        return Err("Invalid email or password".into());
-    }
-
-    println!("[LOGIN] Writing app_session for user_id={}", user.id);
-
-    match conn.execute(
-        "INSERT OR REPLACE INTO app_session (id, user_id)
-         VALUES (1, ?1)",
-        params![user.id],
-    ) {
-        Ok(_) => println!("[LOGIN] app_session written successfully"),
-        Err(e) => {
-            println!("[LOGIN] app_session write FAILED: {:?}", e);
-            return Err(e.to_string());
-        }
-    }
+    let Some(user) = user.filter(|_| password_ok) else {
+        login_attempt_service::record_attempt(
+            conn,
+            email,
+            user_id,
+            LoginOutcome::InvalidCredentials,
+        )?;
+        return Err(AppError::invalid("Invalid email or password"));
+    };
 
-    println!("[LOGIN] SUCCESS");
+    login_attempt_service::record_attempt(conn, email, user_id, LoginOutcome::Succeeded)?;
 
     Ok(user)
 }
@@ -140,7 +151,7 @@ pub fn login_user(
 pub fn get_user_by_id(
     conn: &Connection,
     id: i64,
-) -> Result<PublicUser, String> {
+) -> AppResult<PublicUser> {
 
     let user = get_user_by_id_internal(conn, id)?;
     Ok(user.into())
@@ -152,12 +163,13 @@ pub fn get_user_by_id(
 
 pub fn update_user_profile(
     conn: &Connection,
+    policy: &PasswordPolicy,
     user_id: i64,
     display_name: Option<String>,
     email: Option<String>,
     password: Option<String>,
     current_password: Option<String>,
-) -> Result<PublicUser, String> {
+) -> AppResult<PublicUser> {
 
     let mut user = get_user_by_id_internal(conn, user_id)?;
 
@@ -166,17 +178,28 @@ pub fn update_user_profile(
     }
 
     if let Some(new_email) = email {
+        if get_user_by_email(conn, &new_email).is_some_and(|other| other.id != user_id) {
+            return Err(AppError::Conflict("User already exists".into()));
+        }
+
+        // A new address has to be verified again.
+        if new_email != user.email {
+            user.email_verified = 0;
+        }
+
         user.email = new_email;
     }
 
     if let Some(new_password) = password {
         let current = current_password
-            .ok_or("Current password required")?;
+            .ok_or_else(|| AppError::field("current_password", "Current password required"))?;
 
         if !verify_password(&user.password_hash, &current) {
-            return Err("Current password incorrect".into());
+            return Err(AppError::field("current_password", "Current password incorrect"));
         }
 
+        policy.enforce("password", &new_password, Some(&user.email))?;
+
         user.password_hash = hash_password(&new_password)?;
     }
 
@@ -184,16 +207,17 @@ pub fn update_user_profile(
         "UPDATE users
          SET email = ?1,
              password_hash = ?2,
-             display_name = ?3
-         WHERE id = ?4",
+             display_name = ?3,
+             email_verified = ?4
+         WHERE id = ?5",
         params![
             user.email,
             user.password_hash,
             user.display_name,
+            user.email_verified,
             user_id
         ],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
 
     Ok(user.into())
 }
@@ -204,20 +228,90 @@ pub fn update_user_profile(
 
 pub fn logout_user(
     conn: &Connection,
-) -> Result<(), String> {
+    user_id: i64,
+) -> AppResult<()> {
 
-    println!("[LOGOUT] Deleting app_session...");
+    log::debug!("Forgetting account {}", user_id);
 
-    let result = conn.execute(
-        "DELETE FROM app_session WHERE id = 1",
-        []
-    );
+    account_service::forget_account(conn, user_id)?;
 
-    println!("[LOGOUT] DB result: {:?}", result);
+    Ok(())
+}
 
-    result.map_err(|e| e.to_string())?;
+/* ===========================
+   DELETE ACCOUNT
+=========================== */
 
-    println!("[LOGOUT] SUCCESS");
+/// Permanently removes the account and everything it owns. With
+/// `export_path`, a full backup is written there first and the deletion
+/// only goes ahead if that succeeds.
+pub fn delete_account(
+    conn: &Connection,
+    app: &AppHandle,
+    user_id: i64,
+    current_password: &str,
+    export_path: Option<&Path>,
+) -> AppResult<()> {
+
+    confirm_password(conn, user_id, current_password)?;
+
+    let user = get_user_by_id_internal(conn, user_id)?;
+
+    if let Some(path) = export_path {
+        let backup = backup_service::export_account_backup(conn, user_id)?;
+        backup_service::write_backup(&backup, path)?;
+    }
+
+    let tx = conn.unchecked_transaction()?;
+
+    for sql in [
+        "DELETE FROM session_tags
+         WHERE session_id IN (SELECT id FROM sessions WHERE user_id = ?1)",
+        "DELETE FROM session_pauses
+         WHERE session_id IN (SELECT id FROM sessions WHERE user_id = ?1)",
+        "DELETE FROM sessions WHERE user_id = ?1",
+        "DELETE FROM invoice_lines
+         WHERE invoice_id IN (SELECT id FROM invoices WHERE user_id = ?1)",
+        "DELETE FROM invoices WHERE user_id = ?1",
+        "DELETE FROM hourly_rates WHERE user_id = ?1",
+        "DELETE FROM projects WHERE user_id = ?1",
+        "DELETE FROM clients WHERE user_id = ?1",
+        "DELETE FROM app_session WHERE user_id = ?1",
+        "DELETE FROM secrets.login_tokens WHERE user_id = ?1",
+        "DELETE FROM login_sessions WHERE user_id = ?1",
+        "DELETE FROM login_attempts WHERE user_id = ?1",
+        "DELETE FROM app_lock WHERE user_id = ?1",
+        "DELETE FROM recovery_codes WHERE user_id = ?1",
+        "DELETE FROM users WHERE id = ?1",
+    ] {
+        tx.execute(sql, params![user_id])?;
+    }
+
+    // Attempts against an unknown email carry no user_id.
+    tx.execute(
+        "DELETE FROM login_attempts WHERE email = ?1",
+        params![user.email],
+    )?;
+
+    tx.execute(
+        "DELETE FROM login_throttle WHERE email = ?1",
+        params![user.email],
+    )?;
+
+    tx.commit()?;
+
+    // Files can't join the transaction; remove them once the rows are gone.
+    if let Some(path) = user.avatar_url {
+        remove_avatar(app, Path::new(&path))?;
+    }
+
+    // Invoices saved elsewhere were put there on purpose; only the
+    // default folder goes.
+    match std::fs::remove_dir_all(invoice_service::invoice_dir(app, user_id)?) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
 
     Ok(())
 }
@@ -231,10 +325,10 @@ pub fn upload_avatar(
     app: &AppHandle,
     user_id: i64,
     bytes: Vec<u8>,
-) -> Result<String, String> {
+) -> AppResult<String> {
 
     let img = image::load_from_memory(&bytes)
-        .map_err(|_| "Invalid image".to_string())?;
+        .map_err(|_| AppError::field("bytes", "Invalid image"))?;
 
     let resized = img.resize(
         256,
@@ -242,30 +336,31 @@ pub fn upload_avatar(
         image::imageops::FilterType::Lanczos3,
     );
 
-    let app_dir = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| e.to_string())?;
+    let avatar_dir = avatar_dir(app)?;
 
-    let avatar_dir = app_dir.join("avatars");
-
-    std::fs::create_dir_all(&avatar_dir)
-        .map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&avatar_dir)?;
 
     let filename = format!("{}.webp", Uuid::new_v4());
     let full_path = avatar_dir.join(&filename);
 
     resized
         .save_with_format(&full_path, ImageFormat::WebP)
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| AppError::Internal(e.to_string()))?;
 
     let absolute_path = full_path.to_string_lossy().to_string();
 
+    let previous = get_user_by_id_internal(conn, user_id)?.avatar_url;
+
     conn.execute(
         "UPDATE users SET avatar_url = ?1 WHERE id = ?2",
         rusqlite::params![absolute_path, user_id],
-    )
-    .map_err(|e| e.to_string())?;
+    )?;
+
+    // Don't leave replaced avatars behind in the data folder. The new
+    // one is already saved, so a failure here isn't worth reporting.
+    if let Some(path) = previous {
+        remove_avatar(app, Path::new(&path)).ok();
+    }
 
     Ok(absolute_path)
 }
@@ -274,10 +369,32 @@ pub fn upload_avatar(
    INTERNAL HELPERS
 =========================== */
 
+fn avatar_dir(app: &AppHandle) -> AppResult<PathBuf> {
+    let app_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(app_dir.join("avatars"))
+}
+
+/// Deletes an avatar file, but only from Trackly's own avatar folder.
+fn remove_avatar(app: &AppHandle, path: &Path) -> AppResult<()> {
+    if !path.starts_with(avatar_dir(app)?) {
+        return Ok(());
+    }
+
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
 fn get_user_by_id_internal(
     conn: &Connection,
     id: i64,
-) -> Result<DbUser, String> {
+) -> AppResult<DbUser> {
 
     conn.query_row(
         "SELECT id, email, password_hash, display_name, avatar_url, email_verified
@@ -294,7 +411,7 @@ fn get_user_by_id_internal(
             })
         },
     )
-    .map_err(|_| "User not found".into())
+    .map_err(|_| AppError::NotFound("User"))
 }
 
 pub fn get_user_by_email(
@@ -302,9 +419,7 @@ pub fn get_user_by_email(
     email: &str,
 ) -> Option<DbUser> {
 
-    println!("[DB] Searching user by email: {}", email);
-
-    match conn.query_row(
+    conn.query_row(
         "SELECT id, email, password_hash, display_name, avatar_url, email_verified
          FROM users WHERE email = ?1",
         params![email],
@@ -318,14 +433,6 @@ pub fn get_user_by_email(
                 email_verified: row.get(5)?,
             })
         },
-    ) {
-        Ok(user) => {
-            println!("[DB] User found with id={}", user.id);
-            Some(user)
-        }
-        Err(e) => {
-            println!("[DB] User lookup failed: {:?}", e);
-            None
-        }
-    }
+    )
+    .ok()
 }
\ No newline at end of file
@@ -0,0 +1,153 @@
+use rusqlite::{params, Connection};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{Duration, Utc};
+use hmac::{Hmac, Mac};
+use rand_core::{OsRng, RngCore};
+use sha2::Sha256;
+
+use crate::error::{AppError, AppResult};
+use crate::mail::OutgoingMail;
+use crate::models::user::PublicUser;
+use crate::services::user_service;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const TOKEN_TTL_HOURS: i64 = 24;
+const SIGNING_KEY_NAME: &str = "email_verification";
+
+/* ===========================
+   SEND
+=========================== */
+
+/// Builds the mail carrying a fresh verification token for the user's
+/// current address. Sending is left to the caller so it can release the
+/// database first; SMTP can take seconds.
+pub fn verification_email(
+    conn: &Connection,
+    user_id: i64,
+) -> AppResult<OutgoingMail> {
+
+    let user = user_service::get_user_by_id(conn, user_id)?;
+
+    if user.email_verified {
+        return Err(AppError::invalid("Email already verified"));
+    }
+
+    let token = issue_token(conn, user.id, &user.email)?;
+
+    Ok(OutgoingMail {
+        to: user.email,
+        subject: "Verify your Trackly email".into(),
+        body: format!(
+            "Paste this code in Trackly to verify your email address:\n\n{}\n\n\
+             It expires in {} hours. If you didn't create a Trackly account, \
+             you can ignore this message.",
+            token, TOKEN_TTL_HOURS
+        ),
+    })
+}
+
+/* ===========================
+   VERIFY
+=========================== */
+
+/// Marks the email as verified if the token is authentic, unexpired and
+/// was issued for the account's current address.
+pub fn verify_email(
+    conn: &Connection,
+    token: &str,
+) -> AppResult<PublicUser> {
+
+    let invalid = || AppError::field("token", "Invalid verification token");
+
+    let (payload, signature) = token.trim().split_once('.').ok_or_else(invalid)?;
+
+    let payload = URL_SAFE_NO_PAD
+        .decode(payload)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or_else(invalid)?;
+
+    let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| invalid())?;
+
+    let (user_id, expires_at) = payload
+        .split_once(':')
+        .and_then(|(id, exp)| Some((id.parse::<i64>().ok()?, exp.parse::<i64>().ok()?)))
+        .ok_or_else(invalid)?;
+
+    // Unknown users fail like a bad signature.
+    let user = user_service::get_user_by_id(conn, user_id).map_err(|_| invalid())?;
+
+    signer(conn, user_id, expires_at, &user.email)?
+        .verify_slice(&signature)
+        .map_err(|_| invalid())?;
+
+    if expires_at <= Utc::now().timestamp() {
+        return Err(AppError::field("token", "Verification token expired"));
+    }
+
+    conn.execute(
+        "UPDATE users SET email_verified = 1 WHERE id = ?1",
+        params![user_id],
+    )?;
+
+    user_service::get_user_by_id(conn, user_id)
+}
+
+/* ===========================
+   HELPERS
+=========================== */
+
+/// `base64(user_id:expires_at).base64(hmac)`. The MAC also covers the
+/// email, so changing the address invalidates outstanding tokens.
+fn issue_token(
+    conn: &Connection,
+    user_id: i64,
+    email: &str,
+) -> AppResult<String> {
+
+    let expires_at = (Utc::now() + Duration::hours(TOKEN_TTL_HOURS)).timestamp();
+
+    let signature = signer(conn, user_id, expires_at, email)?
+        .finalize()
+        .into_bytes();
+
+    Ok(format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(format!("{}:{}", user_id, expires_at)),
+        URL_SAFE_NO_PAD.encode(signature)
+    ))
+}
+
+fn signer(
+    conn: &Connection,
+    user_id: i64,
+    expires_at: i64,
+    email: &str,
+) -> AppResult<HmacSha256> {
+
+    let mut mac = HmacSha256::new_from_slice(&signing_key(conn)?)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    mac.update(format!("{}:{}:{}", user_id, expires_at, email).as_bytes());
+
+    Ok(mac)
+}
+
+/// Per-install key, created on first use.
+fn signing_key(conn: &Connection) -> AppResult<Vec<u8>> {
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+
+    conn.execute(
+        "INSERT OR IGNORE INTO app_secrets (name, value) VALUES (?1, ?2)",
+        params![SIGNING_KEY_NAME, key.to_vec()],
+    )?;
+
+    conn.query_row(
+        "SELECT value FROM app_secrets WHERE name = ?1",
+        params![SIGNING_KEY_NAME],
+        |row| row.get(0),
+    )
+    .map_err(AppError::from)
+}
@@ -1,6 +1,234 @@
-use std::sync::Mutex;
+use chrono::{DateTime, Utc};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use tauri::ipc::{CommandArg, CommandItem, InvokeError};
+use tauri::{Manager, Runtime};
+
+use crate::error::{AppError, AppResult};
+
+/* ===========================
+   APP STATE
+=========================== */
+
+#[derive(Clone, Copy)]
+pub struct Login {
+    pub user_id: i64,
+    /// `None` keeps the login until an explicit logout.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// App-lock state for the signed-in account. `idle_timeout` is only set
+/// when the account has a PIN and an idle timeout configured.
+struct AppLock {
+    enabled: bool,
+    locked: bool,
+    idle_timeout: Option<Duration>,
+    last_activity: Instant,
+}
+
+impl Default for AppLock {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            locked: false,
+            idle_timeout: None,
+            last_activity: Instant::now(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    login: Option<Login>,
+    lock: AppLock,
+}
 
 #[derive(Default)]
 pub struct AppState {
-    pub current_user_id: Mutex<Option<i64>>,
-}
\ No newline at end of file
+    inner: Mutex<Inner>,
+}
+
+impl AppState {
+    /// Signs the user in with the app lock off; call `configure_lock`
+    /// afterwards if the account has one.
+    pub fn sign_in(&self, user_id: i64, expires_at: Option<DateTime<Utc>>) {
+        let mut inner = self.lock_inner();
+        inner.login = Some(Login { user_id, expires_at });
+        inner.lock = AppLock::default();
+    }
+
+    pub fn sign_out(&self) {
+        let mut inner = self.lock_inner();
+        inner.login = None;
+        inner.lock = AppLock::default();
+    }
+
+    /// Returns the signed-in user, dropping the login if it has expired.
+    /// Ignores the app lock; use `current_user` for data access.
+    pub fn signed_in_user(&self) -> AppResult<AuthedUser> {
+        let mut inner = self.lock_inner();
+        Self::check_login(&mut inner)
+    }
+
+    /// Returns the signed-in user if the app is unlocked, and counts the
+    /// call as activity for the idle timeout.
+    pub fn current_user(&self) -> AppResult<AuthedUser> {
+        let mut inner = self.lock_inner();
+        let user = Self::check_login(&mut inner)?;
+        Self::check_unlocked(&mut inner)?;
+        Ok(user)
+    }
+
+    /// For commands that don't need a user but must not run while locked.
+    pub fn ensure_unlocked(&self) -> AppResult<()> {
+        Self::check_unlocked(&mut self.lock_inner())
+    }
+
+    /* ---------- App lock ---------- */
+
+    /// `None` disables the lock. `Some(0)` locks only on startup, switch
+    /// or an explicit `lock_app`.
+    pub fn configure_lock(&self, idle_timeout_minutes: Option<u32>, locked: bool) {
+        let mut inner = self.lock_inner();
+
+        inner.lock = AppLock {
+            enabled: idle_timeout_minutes.is_some(),
+            locked: locked && idle_timeout_minutes.is_some(),
+            idle_timeout: idle_timeout_minutes
+                .filter(|m| *m > 0)
+                .map(|m| Duration::from_secs(u64::from(m) * 60)),
+            last_activity: Instant::now(),
+        };
+    }
+
+    pub fn lock(&self) -> AppResult<()> {
+        let mut inner = self.lock_inner();
+
+        if !inner.lock.enabled {
+            return Err(AppError::invalid("App lock is not enabled"));
+        }
+
+        inner.lock.locked = true;
+        Ok(())
+    }
+
+    pub fn unlock(&self) {
+        let mut inner = self.lock_inner();
+        inner.lock.locked = false;
+        inner.lock.last_activity = Instant::now();
+    }
+
+    /// `(enabled, locked)`, applying the idle timeout first.
+    pub fn lock_status(&self) -> (bool, bool) {
+        let mut inner = self.lock_inner();
+        let locked = Self::check_unlocked(&mut inner).is_err();
+        (inner.lock.enabled, locked)
+    }
+
+    /* ---------- Helpers ---------- */
+
+    fn check_login(inner: &mut Inner) -> AppResult<AuthedUser> {
+        let current = inner.login.ok_or(AppError::NotAuthenticated)?;
+
+        if current.expires_at.is_some_and(|t| t <= Utc::now()) {
+            inner.login = None;
+            inner.lock = AppLock::default();
+            return Err(AppError::NotAuthenticated);
+        }
+
+        Ok(AuthedUser { id: current.user_id })
+    }
+
+    fn check_unlocked(inner: &mut Inner) -> AppResult<()> {
+        let lock = &mut inner.lock;
+
+        if lock
+            .idle_timeout
+            .is_some_and(|timeout| lock.last_activity.elapsed() >= timeout)
+        {
+            lock.locked = true;
+        }
+
+        if lock.locked {
+            return Err(AppError::Locked);
+        }
+
+        lock.last_activity = Instant::now();
+        Ok(())
+    }
+
+    // The guarded values are plain data, so a panic elsewhere can't leave
+    // them half-written; recover instead of failing every command.
+    fn lock_inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/* ===========================
+   AUTHED USER
+=========================== */
+
+/// Command argument that resolves to the signed-in user, or rejects the
+/// call with `NotAuthenticated` or `Locked` before the command body runs.
+#[derive(Clone, Copy)]
+pub struct AuthedUser {
+    pub id: i64,
+}
+
+impl<'de, R: Runtime> CommandArg<'de, R> for AuthedUser {
+    fn from_command(command: CommandItem<'de, R>) -> Result<Self, InvokeError> {
+        let webview = command.message.webview();
+        let state = webview.state::<AppState>();
+
+        state.current_user().map_err(InvokeError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_calls_without_a_login() {
+        let state = AppState::default();
+
+        assert!(matches!(state.current_user(), Err(AppError::NotAuthenticated)));
+    }
+
+    #[test]
+    fn drops_an_expired_login() {
+        let state = AppState::default();
+        state.sign_in(1, Some(Utc::now() - chrono::Duration::seconds(1)));
+
+        assert!(matches!(state.signed_in_user(), Err(AppError::NotAuthenticated)));
+
+        state.sign_in(1, Some(Utc::now() + chrono::Duration::hours(1)));
+
+        assert_eq!(state.current_user().map(|u| u.id).ok(), Some(1));
+    }
+
+    #[test]
+    fn locked_app_only_rejects_data_access() {
+        let state = AppState::default();
+        state.sign_in(7, None);
+        state.configure_lock(Some(0), true);
+
+        assert!(matches!(state.current_user(), Err(AppError::Locked)));
+        assert!(matches!(state.ensure_unlocked(), Err(AppError::Locked)));
+        assert_eq!(state.signed_in_user().map(|u| u.id).ok(), Some(7));
+        assert_eq!(state.lock_status(), (true, true));
+
+        state.unlock();
+
+        assert_eq!(state.current_user().map(|u| u.id).ok(), Some(7));
+    }
+
+    #[test]
+    fn lock_requires_an_enabled_app_lock() {
+        let state = AppState::default();
+        state.sign_in(1, None);
+
+        assert!(state.lock().is_err());
+        assert!(state.current_user().is_ok());
+    }
+}
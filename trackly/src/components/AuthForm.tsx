@@ -1,6 +1,15 @@
 import { useState, useEffect } from "react";
 import "./AuthForm.css";
-import { login, register, getCurrentUser } from "../services/authService";
+import {
+  login,
+  register,
+  getCurrentUser,
+  resetPasswordWithRecoveryCode,
+} from "../services/authService";
+import { isAppError } from "../types/error.dto";
+import PasswordStrength from "./PasswordStrength";
+
+const REMEMBER_ME_DAYS = 30;
 
 interface AuthFormProps {
   mode: "login" | "register";
@@ -11,6 +20,11 @@ export default function AuthForm({ mode }: AuthFormProps) {
   const [password, setPassword] = useState("");
   const [error, setError] = useState<string | null>(null);
   const [loading, setLoading] = useState(false);
+  const [remember, setRemember] = useState(false);
+  const [resetting, setResetting] = useState(false);
+  const [recoveryCode, setRecoveryCode] = useState("");
+  const [notice, setNotice] = useState<string | null>(null);
+  const [recoveryCodes, setRecoveryCodes] = useState<string[] | null>(null);
 
   const isLogin = mode === "login";
 
@@ -29,48 +43,84 @@ export default function AuthForm({ mode }: AuthFormProps) {
   const submit = async (e: React.FormEvent) => {
     e.preventDefault();
     setError(null);
+    setNotice(null);
     setLoading(true);
 
     try {
       console.log("Auth attempt:", { email, mode });
 
+      if (isLogin && resetting) {
+        await resetPasswordWithRecoveryCode(email, recoveryCode, password);
+        setResetting(false);
+        setRecoveryCode("");
+        setPassword("");
+        setNotice("Password updated, sign in with the new one");
+        return;
+      }
+
       if (isLogin) {
-        await login(email, password);
+        await login(email, password, remember ? REMEMBER_ME_DAYS : undefined);
       } else {
-        await register(email, password);
+        // Codes are shown once; the user continues after saving them.
+        const { recovery_codes } = await register(email, password);
+        setRecoveryCodes(recovery_codes);
+        return;
       }
 
       window.location.href = "/";
-    } catch (err: any) {
+    } catch (err: unknown) {
       console.error("AUTH ERROR:", err);
 
-      const message =
-        typeof err === "string"
-          ? err
-          : err?.message
-          ? err.message
-          : JSON.stringify(err);
-
-      switch (message) {
-        case "Invalid email or password":
-        case "INVALID_CREDENTIALS":
-          setError("Invalid credentials");
-          break;
-
-        case "User already exists":
-        case "USER_EXISTS":
-          setError("User already exists");
-          break;
-
-        default:
-          // 🔥 Mostrar error real en fase debugging
-          setError(message || "Unexpected error");
+      if (isAppError(err)) {
+        switch (err.code) {
+          case "conflict":
+            setError("User already exists");
+            break;
+
+          case "validation":
+            setError(
+              err.details?.field ? err.message : "Invalid credentials"
+            );
+            break;
+
+          default:
+            setError(err.message || "Unexpected error");
+        }
+      } else {
+        setError(typeof err === "string" ? err : "Unexpected error");
       }
     } finally {
       setLoading(false);
     }
   };
 
+  if (recoveryCodes) {
+    return (
+      <div className="login-card">
+        <h2 className="login-title">Recovery codes</h2>
+
+        <p className="login-subtitle">
+          Save these codes somewhere safe. Each one can reset your
+          password once, and they won't be shown again.
+        </p>
+
+        <ul className="login-recovery-codes">
+          {recoveryCodes.map(code => (
+            <li key={code}>{code}</li>
+          ))}
+        </ul>
+
+        <button
+          className="login-button"
+          type="button"
+          onClick={() => (window.location.href = "/")}
+        >
+          I saved them
+        </button>
+      </div>
+    );
+  }
+
   return (
     <form className="login-card" onSubmit={submit}>
       <h2 className="login-title">
@@ -93,8 +143,20 @@ export default function AuthForm({ mode }: AuthFormProps) {
         />
       </div>
 
+      {isLogin && resetting && (
+        <div className="login-field">
+          <label>Recovery code</label>
+          <input
+            type="text"
+            value={recoveryCode}
+            onChange={e => setRecoveryCode(e.target.value)}
+            required
+          />
+        </div>
+      )}
+
       <div className="login-field">
-        <label>Password</label>
+        <label>{resetting ? "New password" : "Password"}</label>
         <input
           type="password"
           value={password}
@@ -103,7 +165,23 @@ export default function AuthForm({ mode }: AuthFormProps) {
         />
       </div>
 
+      {(!isLogin || resetting) && (
+        <PasswordStrength password={password} email={email} />
+      )}
+
+      {isLogin && !resetting && (
+        <label className="login-remember">
+          <input
+            type="checkbox"
+            checked={remember}
+            onChange={e => setRemember(e.target.checked)}
+          />
+          Remember me for {REMEMBER_ME_DAYS} days
+        </label>
+      )}
+
       {error && <p className="login-error">{error}</p>}
+      {notice && <p className="login-notice">{notice}</p>}
 
       <button
         className="login-button"
@@ -112,11 +190,26 @@ export default function AuthForm({ mode }: AuthFormProps) {
       >
         {loading
           ? "Loading..."
+          : resetting
+          ? "Reset password"
           : isLogin
           ? "Login"
           : "Register"}
       </button>
 
+      {isLogin && (
+        <button
+          type="button"
+          className="login-link"
+          onClick={() => {
+            setResetting(!resetting);
+            setError(null);
+          }}
+        >
+          {resetting ? "Back to sign in" : "Forgot password?"}
+        </button>
+      )}
+
       <a
         href={isLogin ? "/register" : "/login"}
         style={{ textAlign: "center", fontSize: "0.75rem" }}
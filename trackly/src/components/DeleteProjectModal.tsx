@@ -0,0 +1,138 @@
+import { useState } from "react";
+import ReactDOM from "react-dom";
+import type {
+  Project,
+  ProjectSessionsOnDelete,
+} from "../types/project";
+import { isAppError } from "../types/error.dto";
+import "./ConfirmModal.css";
+import "./DeleteProjectModal.css";
+
+interface Props {
+  project: Project;
+  /** Candidates for moving the project's sessions. */
+  otherProjects: Project[];
+  onConfirm: (
+    confirmName: string,
+    sessions: ProjectSessionsOnDelete
+  ) => Promise<void>;
+  onCancel: () => void;
+}
+
+export default function DeleteProjectModal({
+  project,
+  otherProjects,
+  onConfirm,
+  onCancel,
+}: Props) {
+  const [mode, setMode] =
+    useState<ProjectSessionsOnDelete["mode"]>("unassign");
+  const [targetId, setTargetId] = useState<number | null>(
+    otherProjects[0]?.id ?? null
+  );
+  const [confirmName, setConfirmName] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  const sessions = (): ProjectSessionsOnDelete =>
+    mode === "reassign" && targetId !== null
+      ? { mode, project_id: targetId }
+      : mode === "delete"
+      ? { mode }
+      : { mode: "unassign" };
+
+  const canConfirm =
+    confirmName === project.name &&
+    (mode !== "reassign" || targetId !== null);
+
+  const submit = async () => {
+    try {
+      setError(null);
+      await onConfirm(confirmName, sessions());
+    } catch (err: unknown) {
+      setError(isAppError(err) ? err.message : "Could not delete project");
+    }
+  };
+
+  return ReactDOM.createPortal(
+    <div className="confirm-overlay overlay-in" onClick={onCancel}>
+      <div
+        className="confirm-modal modal-in delete-project-modal"
+        onClick={(e) => e.stopPropagation()}
+      >
+        <h3>Delete "{project.name}"</h3>
+
+        <p>
+          This permanently removes the project. Archive it instead to
+          keep it out of the way without losing anything.
+        </p>
+
+        <fieldset>
+          <legend>Its sessions</legend>
+
+          <label>
+            <input
+              type="radio"
+              checked={mode === "unassign"}
+              onChange={() => setMode("unassign")}
+            />
+            Keep them without a project
+          </label>
+
+          {otherProjects.length > 0 && (
+            <label>
+              <input
+                type="radio"
+                checked={mode === "reassign"}
+                onChange={() => setMode("reassign")}
+              />
+              Move them to
+              <select
+                value={targetId ?? ""}
+                disabled={mode !== "reassign"}
+                onChange={(e) => setTargetId(Number(e.target.value))}
+              >
+                {otherProjects.map((p) => (
+                  <option key={p.id} value={p.id}>
+                    {p.name}
+                  </option>
+                ))}
+              </select>
+            </label>
+          )}
+
+          <label>
+            <input
+              type="radio"
+              checked={mode === "delete"}
+              onChange={() => setMode("delete")}
+            />
+            Delete them too
+          </label>
+        </fieldset>
+
+        <label className="delete-project-confirm">
+          Type <strong>{project.name}</strong> to confirm
+          <input
+            value={confirmName}
+            onChange={(e) => setConfirmName(e.target.value)}
+          />
+        </label>
+
+        {error && <p className="delete-project-error">{error}</p>}
+
+        <div className="confirm-actions">
+          <button onClick={onCancel}>Cancel</button>
+
+          <button
+            className="danger"
+            disabled={!canConfirm}
+            onClick={submit}
+          >
+            Delete
+          </button>
+        </div>
+      </div>
+    </div>,
+    document.body
+  );
+}
@@ -5,9 +5,25 @@ import {
   updateProfile,
   uploadAvatar,
   logout,
+  listLoginAttempts,
+  generateRecoveryCodes,
+  getRecoveryCodesRemaining,
+  sendVerificationEmail,
+  verifyEmail,
+  deleteAccount,
 } from "../services/authService";
+import type { LoginAttemptDTO } from "../types/user.dto";
+import { isAppError } from "../types/error.dto";
 import "./EditProfileModal.css";
 
+const RECENT_ATTEMPTS = 10;
+
+const OUTCOME_LABELS: Record<LoginAttemptDTO["outcome"], string> = {
+  succeeded: "Inicio correcto",
+  invalid_credentials: "Contraseña incorrecta",
+  throttled: "Bloqueado por intentos",
+};
+
 type EditableProfile = {
   displayName: string;
   email: string;
@@ -27,6 +43,17 @@ export default function EditProfileModal({
   const [error, setError] = useState<string | null>(null);
   const [avatarPreview, setAvatarPreview] = useState<string | null>(null);
   const [selectedFile, setSelectedFile] = useState<File | null>(null);
+  const [attempts, setAttempts] = useState<LoginAttemptDTO[]>([]);
+  const [codesRemaining, setCodesRemaining] = useState<number | null>(null);
+  const [newCodes, setNewCodes] = useState<string[] | null>(null);
+  const [codesPassword, setCodesPassword] = useState("");
+  const [codesError, setCodesError] = useState<string | null>(null);
+  const [verifyToken, setVerifyToken] = useState("");
+  const [verifyMessage, setVerifyMessage] = useState<string | null>(null);
+  const [deletePassword, setDeletePassword] = useState("");
+  const [deleteExportPath, setDeleteExportPath] = useState("");
+  const [confirmDelete, setConfirmDelete] = useState(false);
+  const [deleteError, setDeleteError] = useState<string | null>(null);
 
   const [profile, setProfile] = useState<EditableProfile>({
     displayName: "",
@@ -58,6 +85,22 @@ export default function EditProfileModal({
     setInitialProfile(data);
   }, [user, initialProfile]);
 
+  /* ===========================
+     LOGIN ACTIVITY
+  =========================== */
+
+  useEffect(() => {
+    if (!user) return;
+
+    listLoginAttempts(RECENT_ATTEMPTS)
+      .then(setAttempts)
+      .catch(() => setAttempts([]));
+
+    getRecoveryCodesRemaining()
+      .then(setCodesRemaining)
+      .catch(() => setCodesRemaining(null));
+  }, [user]);
+
   useEffect(() => {
     return () => {
       if (avatarPreview) {
@@ -134,13 +177,86 @@ export default function EditProfileModal({
 
       onClose();
       window.dispatchEvent(new Event("trackly:user-updated"));
-    } catch {
-      setError("No se pudo guardar el perfil");
+    } catch (err: unknown) {
+      // Password policy messages are specific enough to show as-is.
+      setError(
+        isAppError(err) && err.details?.violations
+          ? err.message
+          : "No se pudo guardar el perfil"
+      );
     } finally {
       setSaving(false);
     }
   };
 
+  /* ===========================
+     EMAIL VERIFICATION
+  =========================== */
+
+  const handleResendVerification = async () => {
+    try {
+      await sendVerificationEmail();
+      setVerifyMessage("Te enviamos un código de verificación");
+    } catch {
+      setVerifyMessage("No se pudo enviar el correo");
+    }
+  };
+
+  const handleVerifyEmail = async () => {
+    try {
+      await verifyEmail(verifyToken);
+      setVerifyToken("");
+      setVerifyMessage(null);
+      window.dispatchEvent(new Event("trackly:user-updated"));
+    } catch (err: unknown) {
+      setVerifyMessage(
+        isAppError(err) ? err.message : "Código inválido"
+      );
+    }
+  };
+
+  /* ===========================
+     RECOVERY CODES
+  =========================== */
+
+  const handleRegenerateCodes = async () => {
+    try {
+      setCodesError(null);
+      const codes = await generateRecoveryCodes(codesPassword);
+      setNewCodes(codes);
+      setCodesRemaining(codes.length);
+      setCodesPassword("");
+    } catch {
+      setCodesError("Contraseña incorrecta");
+    }
+  };
+
+  /* ===========================
+     DELETE ACCOUNT
+  =========================== */
+
+  const handleDeleteAccount = async () => {
+    // First click arms the button, the second one deletes.
+    if (!confirmDelete) {
+      setConfirmDelete(true);
+      return;
+    }
+
+    try {
+      setDeleteError(null);
+      await deleteAccount(
+        deletePassword,
+        deleteExportPath.trim() || undefined
+      );
+      window.location.href = "/login";
+    } catch (err: unknown) {
+      setConfirmDelete(false);
+      setDeleteError(
+        isAppError(err) ? err.message : "No se pudo eliminar la cuenta"
+      );
+    }
+  };
+
   /* ===========================
      UI
   =========================== */
@@ -232,6 +348,108 @@ export default function EditProfileModal({
           )}
         </div>
 
+        {!user.emailVerified && (
+          <div className="email-verification">
+            <h4>Email sin verificar</h4>
+            <div className="recovery-codes-form">
+              <input
+                type="text"
+                placeholder="Código de verificación"
+                value={verifyToken}
+                onChange={(e) => setVerifyToken(e.target.value)}
+              />
+              <button
+                disabled={verifyToken.trim().length === 0}
+                onClick={handleVerifyEmail}
+              >
+                Verificar
+              </button>
+              <button onClick={handleResendVerification}>Reenviar</button>
+            </div>
+            {verifyMessage && <p>{verifyMessage}</p>}
+          </div>
+        )}
+
+        <div className="recovery-codes">
+          <h4>Códigos de recuperación</h4>
+
+          {newCodes ? (
+            <>
+              <p>Guárdalos en un lugar seguro, no se volverán a mostrar.</p>
+              <ul>
+                {newCodes.map((code) => (
+                  <li key={code}>{code}</li>
+                ))}
+              </ul>
+            </>
+          ) : (
+            <>
+              {codesRemaining !== null && (
+                <p>Quedan {codesRemaining} códigos sin usar.</p>
+              )}
+              <div className="recovery-codes-form">
+                <input
+                  type="password"
+                  autoComplete="current-password"
+                  placeholder="Contraseña actual"
+                  value={codesPassword}
+                  onChange={(e) => setCodesPassword(e.target.value)}
+                />
+                <button
+                  disabled={codesPassword.length === 0}
+                  onClick={handleRegenerateCodes}
+                >
+                  Regenerar
+                </button>
+              </div>
+              {codesError && <p className="error">{codesError}</p>}
+            </>
+          )}
+        </div>
+
+        {attempts.length > 0 && (
+          <div className="login-activity">
+            <h4>Actividad de inicio de sesión</h4>
+            <ul>
+              {attempts.map((a) => (
+                <li key={a.id} className={a.outcome}>
+                  <span>{OUTCOME_LABELS[a.outcome]}</span>
+                  <time>{new Date(a.attempted_at).toLocaleString()}</time>
+                </li>
+              ))}
+            </ul>
+          </div>
+        )}
+
+        <div className="delete-account">
+          <h4>Eliminar cuenta</h4>
+          <p>
+            Borra de forma permanente tus proyectos, sesiones y avatar de
+            este equipo.
+          </p>
+          <input
+            type="password"
+            autoComplete="current-password"
+            placeholder="Contraseña actual"
+            value={deletePassword}
+            onChange={(e) => setDeletePassword(e.target.value)}
+          />
+          <input
+            type="text"
+            placeholder="Exportar copia antes a… (ruta absoluta, opcional)"
+            value={deleteExportPath}
+            onChange={(e) => setDeleteExportPath(e.target.value)}
+          />
+          <button
+            className="danger"
+            disabled={deletePassword.length === 0}
+            onClick={handleDeleteAccount}
+          >
+            {confirmDelete ? "Pulsa otra vez para confirmar" : "Eliminar cuenta"}
+          </button>
+          {deleteError && <p className="error">{deleteError}</p>}
+        </div>
+
         {error && (
           <div style={{ color: "#ff6b6b", fontSize: "0.75rem" }}>
             {error}
@@ -0,0 +1,72 @@
+import { useState } from "react";
+import "./AuthForm.css";
+import { unlockApp } from "../services/appLockService";
+import { isAppError } from "../types/error.dto";
+
+interface Props {
+  onUnlocked: () => void;
+}
+
+export default function LockScreen({ onUnlocked }: Props) {
+  const [pin, setPin] = useState("");
+  const [error, setError] = useState<string | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  const submit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    setError(null);
+    setLoading(true);
+
+    try {
+      await unlockApp(pin);
+      onUnlocked();
+    } catch (err: unknown) {
+      setPin("");
+
+      if (isAppError(err) && err.code === "not_authenticated") {
+        window.location.href = "/login";
+        return;
+      }
+
+      setError(isAppError(err) ? err.message : "Unexpected error");
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  return (
+    <form className="login-card" onSubmit={submit}>
+      <h2 className="login-title">Locked</h2>
+
+      <p className="login-subtitle">Enter your PIN to continue</p>
+
+      <div className="login-field">
+        <label>PIN</label>
+        <input
+          type="password"
+          value={pin}
+          onChange={e => setPin(e.target.value)}
+          autoFocus
+          required
+        />
+      </div>
+
+      {error && <p className="login-error">{error}</p>}
+
+      <button
+        className="login-button"
+        type="submit"
+        disabled={loading}
+      >
+        {loading ? "Loading..." : "Unlock"}
+      </button>
+
+      <a
+        href="/login"
+        style={{ textAlign: "center", fontSize: "0.75rem" }}
+      >
+        Sign in with password instead
+      </a>
+    </form>
+  );
+}
@@ -0,0 +1,74 @@
+import { useEffect, useState } from "react";
+import { checkPasswordStrength } from "../services/authService";
+import type {
+  PasswordReportDTO,
+  PasswordViolationDTO,
+} from "../types/password.dto";
+import "./PasswordStrength.css";
+
+const SCORE_LABELS = ["Very weak", "Weak", "Fair", "Good", "Strong"];
+
+function describe(v: PasswordViolationDTO): string {
+  switch (v.code) {
+    case "too_short":
+      return `At least ${v.min_length} characters`;
+    case "too_long":
+      return `At most ${v.max_length} characters`;
+    case "too_few_character_classes":
+      return `Mix at least ${v.required} of: lowercase, uppercase, digits, symbols`;
+    case "common":
+      return "Too common";
+    case "contains_email":
+      return "Must not contain your email";
+    case "too_weak":
+      return "Too easy to guess";
+  }
+}
+
+interface PasswordStrengthProps {
+  password: string;
+  email?: string;
+}
+
+export default function PasswordStrength({
+  password,
+  email,
+}: PasswordStrengthProps) {
+  const [report, setReport] = useState<PasswordReportDTO | null>(null);
+
+  useEffect(() => {
+    if (!password) {
+      setReport(null);
+      return;
+    }
+
+    let cancelled = false;
+
+    checkPasswordStrength(password, email || undefined)
+      .then(r => !cancelled && setReport(r))
+      .catch(() => !cancelled && setReport(null));
+
+    return () => {
+      cancelled = true;
+    };
+  }, [password, email]);
+
+  if (!report) return null;
+
+  return (
+    <div className="password-strength">
+      <div className={`password-strength-bar score-${report.score}`}>
+        <span style={{ width: `${(report.score + 1) * 20}%` }} />
+      </div>
+      <small>{SCORE_LABELS[report.score]}</small>
+
+      {report.violations.length > 0 && (
+        <ul>
+          {report.violations.map(v => (
+            <li key={v.code}>{describe(v)}</li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+}
@@ -8,15 +8,21 @@ import {
   getProjects,
   createProject as apiCreate,
   updateProject as apiUpdate,
-  deleteProject as apiDelete
+  deleteProject as apiDelete,
+  archiveProject as apiArchive,
+  unarchiveProject as apiUnarchive,
 } from "../services/projectService";
-import type { Project } from "../types/project";
+import type {
+  Project,
+  ProjectSessionsOnDelete,
+} from "../types/project";
 
 const ACTIVE_KEY = "trackly:activeProjectId";
 const DEFAULT_COLOR = "#2e86ab";
 
 interface ProjectsContextType {
   projects: Project[];
+  archivedProjects: Project[];
   activeProject: Project | null;
   activeProjectId: number | null;
   selectProject: (id: number | null) => void;
@@ -25,7 +31,13 @@ interface ProjectsContextType {
     id: number,
     data: { name: string; color: string }
   ) => Promise<void>;
-  deleteProject: (id: number) => Promise<void>;
+  archiveProject: (id: number) => Promise<void>;
+  unarchiveProject: (id: number) => Promise<void>;
+  deleteProject: (
+    id: number,
+    confirmName: string,
+    sessions: ProjectSessionsOnDelete
+  ) => Promise<void>;
   reload: () => Promise<void>;
 }
 
@@ -45,7 +57,10 @@ export function ProjectsProvider({
 }: {
   children: React.ReactNode;
 }) {
-  const [projects, setProjects] = useState<Project[]>([]);
+  // Archived projects are loaded too, but kept out of `projects`.
+  const [allProjects, setAllProjects] = useState<Project[]>([]);
+  const projects = allProjects.filter((p) => !p.archived_at);
+  const archivedProjects = allProjects.filter((p) => p.archived_at);
   const [activeProjectId, setActiveProjectId] =
     useState<number | null>(null);
 
@@ -55,8 +70,8 @@ export function ProjectsProvider({
 
   useEffect(() => {
     const load = async () => {
-      const data = await getProjects();
-      setProjects(data);
+      const data = await getProjects(true);
+      setAllProjects(data);
 
       const saved = localStorage.getItem(ACTIVE_KEY);
       if (saved) {
@@ -98,8 +113,8 @@ export function ProjectsProvider({
   =========================== */
 
   const reload = async () => {
-    const data = await getProjects();
-    setProjects(data);
+    const data = await getProjects(true);
+    setAllProjects(data);
   };
 
   const createProject = async (
@@ -119,8 +134,26 @@ export function ProjectsProvider({
     await reload();
   };
 
-  const deleteProject = async (id: number) => {
-    await apiDelete(id);
+  const archiveProject = async (id: number) => {
+    await apiArchive(id);
+    await reload();
+
+    if (activeProjectId === id) {
+      setActiveProjectId(null);
+    }
+  };
+
+  const unarchiveProject = async (id: number) => {
+    await apiUnarchive(id);
+    await reload();
+  };
+
+  const deleteProject = async (
+    id: number,
+    confirmName: string,
+    sessions: ProjectSessionsOnDelete
+  ) => {
+    await apiDelete(id, confirmName, sessions);
     await reload();
 
     if (activeProjectId === id) {
@@ -132,11 +165,14 @@ export function ProjectsProvider({
     <ProjectsContext.Provider
       value={{
         projects,
+        archivedProjects,
         activeProject,
         activeProjectId,
         selectProject,
         createProject,
         updateProject,
+        archiveProject,
+        unarchiveProject,
         deleteProject,
         reload,
       }}
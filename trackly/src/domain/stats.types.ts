@@ -1,8 +0,0 @@
-/**
- * Minimal contract required for stats calculations
- */
-export interface SessionForStats {
-  startTime: Date;
-  endTime: Date;
-  tags: string[];
-}
\ No newline at end of file
@@ -33,6 +33,8 @@ export function adaptActiveSessionFromApi(
     endTime: parseDate(dto.end_time),
     description: dto.description ?? null,
     status: dto.status,
+    grossSeconds: dto.gross_seconds ?? 0,
+    netSeconds: dto.net_seconds ?? 0,
   };
 }
 
@@ -50,5 +52,7 @@ export function adaptFinishedSessionsFromApi(
     endTime: parseDate(dto.end_time) ?? new Date(0),
     description: dto.description ?? null,
     tags: dto.tags ?? [],
+    grossSeconds: dto.gross_seconds ?? 0,
+    netSeconds: dto.net_seconds ?? 0,
   }));
 }
\ No newline at end of file
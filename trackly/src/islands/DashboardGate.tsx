@@ -1,21 +1,45 @@
-import { useEffect } from "react";
+import { useEffect, useState } from "react";
 import { useAuth } from "../hooks/useAuth";
+import { getAppLockStatus } from "../services/appLockService";
+import LockScreen from "../components/LockScreen";
 import StartStopButton from "./StartStopButton";
 import StatsSummary from "./StatsSummary";
 
 export default function DashboardGate() {
   const { user, loading } = useAuth();
+  const [locked, setLocked] = useState(false);
 
   useEffect(() => {
-    if (!loading && !user) {
-      window.location.href = "/login";
-    }
+    if (loading || user) return;
+
+    // A locked app still has a signed-in user; only redirect when the
+    // login itself is gone.
+    getAppLockStatus().then(status => {
+      if (status?.locked) {
+        setLocked(true);
+      } else {
+        window.location.href = "/login";
+      }
+    });
   }, [loading, user]);
 
   if (loading) {
     return <div style={{ padding: "2rem" }}>Loading...</div>;
   }
 
+  if (locked) {
+    return (
+      <div className="center">
+        <LockScreen
+          onUnlocked={() => {
+            setLocked(false);
+            window.dispatchEvent(new Event("trackly:user-updated"));
+          }}
+        />
+      </div>
+    );
+  }
+
   if (!user) return null;
 
   return (
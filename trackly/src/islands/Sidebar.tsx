@@ -3,6 +3,7 @@ import { createPortal } from "react-dom";
 import { useProjects } from "../context/ProjectsContext";
 import ProjectModal from "../islands/ProjectModal";
 import ConfirmModal from "../components/ConfirmModal";
+import DeleteProjectModal from "../components/DeleteProjectModal";
 import "./Sidebar.css";
 
 export default function Sidebar({
@@ -14,14 +15,28 @@ export default function Sidebar({
 }) {
   const {
     projects,
+    archivedProjects,
     activeProjectId,
     selectProject,
     updateProject,
+    archiveProject,
+    unarchiveProject,
     deleteProject,
   } = useProjects();
 
   const [editing, setEditing] = useState<typeof projects[number] | null>(null);
+  const [toArchive, setToArchive] = useState<typeof projects[number] | null>(null);
   const [toDelete, setToDelete] = useState<typeof projects[number] | null>(null);
+  const [showArchived, setShowArchived] = useState(false);
+
+  // Sub-projects follow their parent; one whose parent is archived
+  // shows at the top level.
+  const isNested = (p: typeof projects[number]) =>
+    projects.some((q) => q.id === p.parent_id);
+
+  const orderedProjects = projects
+    .filter((p) => !isNested(p))
+    .flatMap((p) => [p, ...projects.filter((c) => c.parent_id === p.id)]);
 
   const handleSelectProject = (id: number | null) => {
     selectProject(id);
@@ -53,9 +68,15 @@ export default function Sidebar({
             </li>
 
             {/* PROJECTS */}
-            {projects.map((p) => (
+            {orderedProjects.map((p) => (
               <li key={p.id}>
-                <div className="project-item">
+                <div
+                  className={
+                    isNested(p)
+                      ? "project-item sub-project"
+                      : "project-item"
+                  }
+                >
                   <button
                     className={
                       activeProjectId === p.id
@@ -80,14 +101,62 @@ export default function Sidebar({
                       ✏
                     </button>
 
-                    <button onClick={() => setToDelete(p)}>
-                      🗑
+                    <button
+                      title="Archive"
+                      onClick={() => setToArchive(p)}
+                    >
+                      📦
                     </button>
                   </div>
                 </div>
               </li>
             ))}
           </ul>
+
+          {archivedProjects.length > 0 && (
+            <div className="archived-projects">
+              <button
+                className="archived-toggle"
+                onClick={() => setShowArchived(!showArchived)}
+              >
+                {showArchived ? "▾" : "▸"} Archived ({archivedProjects.length})
+              </button>
+
+              {showArchived && (
+                <ul className="project-list">
+                  {archivedProjects.map((p) => (
+                    <li key={p.id}>
+                      <div className="project-item archived">
+                        <div className="project-row">
+                          <span
+                            className="project-dot"
+                            style={{ backgroundColor: p.color }}
+                          />
+                          {p.name}
+                        </div>
+
+                        <div className="project-actions">
+                          <button
+                            title="Restore"
+                            onClick={() => unarchiveProject(p.id)}
+                          >
+                            ↩
+                          </button>
+
+                          <button
+                            title="Delete permanently"
+                            onClick={() => setToDelete(p)}
+                          >
+                            🗑
+                          </button>
+                        </div>
+                      </div>
+                    </li>
+                  ))}
+                </ul>
+              )}
+            </div>
+          )}
         </div>
 
         <div className="sidebar-footer">
@@ -120,16 +189,31 @@ export default function Sidebar({
           document.body
         )}
 
-      {/* DELETE CONFIRM */}
-      {toDelete && (
+      {/* ARCHIVE CONFIRM */}
+      {toArchive && (
         <ConfirmModal
-          title="Delete project"
-          message={`Are you sure you want to delete "${toDelete.name}"? This cannot be undone.`}
-          confirmLabel="Delete"
+          title="Archive project"
+          message={`Archive "${toArchive.name}"? Its sessions are kept and you can restore it later.`}
+          confirmLabel="Archive"
           cancelLabel="Cancel"
-          onCancel={() => setToDelete(null)}
+          onCancel={() => setToArchive(null)}
           onConfirm={async () => {
-            await deleteProject(toDelete.id);
+            await archiveProject(toArchive.id);
+            setToArchive(null);
+          }}
+        />
+      )}
+
+      {/* DELETE CONFIRM */}
+      {toDelete && (
+        <DeleteProjectModal
+          project={toDelete}
+          otherProjects={[...projects, ...archivedProjects].filter(
+            (p) => p.id !== toDelete.id
+          )}
+          onCancel={() => setToDelete(null)}
+          onConfirm={async (confirmName, sessions) => {
+            await deleteProject(toDelete.id, confirmName, sessions);
             setToDelete(null);
           }}
         />
@@ -1,18 +1,40 @@
 import "./StatsSummary.css";
 import { useEffect, useState } from "react";
-import { getFinishedSessions } from "../services/sessionService";
-import { adaptFinishedSessionsFromApi } from "../infra/adapters/sessionAdapter";
 import {
-  getSessionsInLastDays,
-  getTotalDurationMs,
-} from "../domain/stats";
+  getDurationByTag,
+  getTotalDuration,
+  lastDaysFilter,
+} from "../services/statsService";
 import TagBarChart from "./TagBarChart";
 import { useProjects } from "../context/ProjectsContext";
-import type { FinishedSession } from "../types/session";
+import type {
+  DurationTotalDTO,
+  TagDurationDTO,
+} from "../types/stats.dto";
 import { useStatsInvalidation } from "../hooks/useStatsInvalidation";
 
+interface PeriodStats {
+  total: DurationTotalDTO;
+  tags: TagDurationDTO[];
+}
+
+async function loadPeriod(
+  days: number,
+  projectId: number | null
+): Promise<PeriodStats> {
+  const filter = lastDaysFilter(days, projectId);
+
+  const [total, tags] = await Promise.all([
+    getTotalDuration(filter),
+    getDurationByTag(filter),
+  ]);
+
+  return { total, tags };
+}
+
 export default function StatsSummary() {
-  const [sessions, setSessions] = useState<FinishedSession[]>([]);
+  const [last7, setLast7] = useState<PeriodStats | null>(null);
+  const [last30, setLast30] = useState<PeriodStats | null>(null);
   const [loading, setLoading] = useState(true);
 
   const { activeProjectId } = useProjects();
@@ -23,20 +45,17 @@ export default function StatsSummary() {
       setLoading(true);
 
       try {
-        const all = await getFinishedSessions()
-          .then(adaptFinishedSessionsFromApi);
-
-        const filtered =
-          activeProjectId == null
-            ? all
-            : all.filter(
-                (s) => s.projectId === activeProjectId
-              );
+        const [week, month] = await Promise.all([
+          loadPeriod(7, activeProjectId),
+          loadPeriod(30, activeProjectId),
+        ]);
 
-        setSessions(filtered);
+        setLast7(week);
+        setLast30(month);
       } catch (e) {
         console.error("STATS_LOAD_ERROR", e);
-        setSessions([]);
+        setLast7(null);
+        setLast30(null);
       } finally {
         setLoading(false);
       }
@@ -46,15 +65,14 @@ export default function StatsSummary() {
   }, [activeProjectId, statsVersion]);
 
   if (loading) return <p>Loading stats…</p>;
-  if (sessions.length === 0) return <p>No data yet.</p>;
-
-  const last7 = getSessionsInLastDays(sessions, 7);
-  const last30 = getSessionsInLastDays(sessions, 30);
+  if (!last7 || !last30 || last30.total.session_count === 0) {
+    return <p>No data yet.</p>;
+  }
 
   return (
     <div className="stats-grid">
-      <StatCard title="Last 7 days" sessions={last7} />
-      <StatCard title="Last 30 days" sessions={last30} />
+      <StatCard title="Last 7 days" stats={last7} />
+      <StatCard title="Last 30 days" stats={last30} />
     </div>
   );
 }
@@ -63,29 +81,29 @@ export default function StatsSummary() {
 
 function StatCard({
   title,
-  sessions,
+  stats,
 }: {
   title: string;
-  sessions: FinishedSession[];
+  stats: PeriodStats;
 }) {
-  const totalMs = getTotalDurationMs(sessions);
+  const count = stats.total.session_count;
 
   return (
     <section className="stat-card">
       <header className="stat-header">
         <h3>{title}</h3>
         <span className="stat-total">
-          {formatTotal(totalMs)}
+          {formatTotal(stats.total.total_seconds * 1000)}
         </span>
       </header>
 
       <p className="stat-meta">
-        {sessions.length} session
-        {sessions.length !== 1 ? "s" : ""}
+        {count} session
+        {count !== 1 ? "s" : ""}
       </p>
 
-      {sessions.length > 0 && (
-        <TagBarChart sessions={sessions} />
+      {count > 0 && (
+        <TagBarChart tags={stats.tags} />
       )}
     </section>
   );
@@ -105,4 +123,4 @@ function formatTotal(ms: number): string {
       ? `${hours}h ${mins}m`
       : `${hours}h`
     : `${mins}m`;
-}
\ No newline at end of file
+}
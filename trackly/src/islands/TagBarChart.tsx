@@ -1,22 +1,19 @@
 import { useState } from "react";
-import { getDurationByTag } from "../domain/stats";
 import AllTagsModal from "../components/AllTagsModal";
 import "./TagBarChart.css";
-import type { SessionForStats } from "../domain/stats.types";
+import type { TagDurationDTO } from "../types/stats.dto";
 
 interface Props {
-  sessions: SessionForStats[];
+  tags: TagDurationDTO[];
 }
 
-export default function TagBarChart({ sessions }: Props) {
+export default function TagBarChart({ tags }: Props) {
   const [showAll, setShowAll] = useState(false);
 
-  const byTag = getDurationByTag(sessions);
-
-  const entries = Object.entries(byTag)
-    .map(([tag, ms]) => ({
+  const entries = tags
+    .map(({ tag, seconds }) => ({
       tag,
-      minutes: ms / 60000,
+      minutes: seconds / 60,
     }))
     .sort((a, b) => b.minutes - a.minutes);
 
@@ -0,0 +1,47 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/* =========================
+   APP LOCK
+========================= */
+
+export interface AppLockStatus {
+  enabled: boolean;
+  locked: boolean;
+  idle_timeout_minutes: number | null;
+}
+
+export async function getAppLockStatus(): Promise<AppLockStatus | null> {
+  try {
+    return await invoke<AppLockStatus>("get_app_lock_status");
+  } catch {
+    return null;
+  }
+}
+
+export async function enableAppLock(
+  pin: string,
+  currentPassword: string,
+  idleTimeoutMinutes: number
+): Promise<void> {
+  await invoke("enable_app_lock", {
+    input: {
+      pin,
+      current_password: currentPassword,
+      idle_timeout_minutes: idleTimeoutMinutes,
+    },
+  });
+}
+
+export async function disableAppLock(
+  currentPassword: string
+): Promise<void> {
+  await invoke("disable_app_lock", { currentPassword });
+}
+
+export async function lockApp(): Promise<void> {
+  await invoke("lock_app");
+}
+
+export async function unlockApp(pin: string): Promise<void> {
+  await invoke("unlock_app", { pin });
+}
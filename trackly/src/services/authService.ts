@@ -1,5 +1,12 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { PublicUserDTO } from "../types/user.dto";
+import type {
+  AccountSummaryDTO,
+  LoginAttemptDTO,
+  LoginSessionDTO,
+  PublicUserDTO,
+  RegisterResponseDTO,
+} from "../types/user.dto";
+import type { PasswordReportDTO } from "../types/password.dto";
 
 /* =========================
    AUTH (DTO ONLY)
@@ -7,22 +14,33 @@ import type { PublicUserDTO } from "../types/user.dto";
 
 export async function login(
   email: string,
-  password: string
+  password: string,
+  rememberMeDays?: number
 ): Promise<PublicUserDTO> {
   return await invoke<PublicUserDTO>("login", {
-    input: { email, password },
+    input: { email, password, remember_me_days: rememberMeDays },
   });
 }
 
 export async function register(
   email: string,
   password: string
-): Promise<PublicUserDTO> {
-  return await invoke<PublicUserDTO>("register_user", {
+): Promise<RegisterResponseDTO> {
+  return await invoke<RegisterResponseDTO>("register_user", {
     input: { email, password },
   });
 }
 
+export async function checkPasswordStrength(
+  password: string,
+  email?: string
+): Promise<PasswordReportDTO> {
+  return await invoke<PasswordReportDTO>("check_password_strength", {
+    password,
+    email,
+  });
+}
+
 export async function getCurrentUser(): Promise<PublicUserDTO | null> {
   try {
     return await invoke<PublicUserDTO>("get_current_user");
@@ -35,6 +53,23 @@ export async function logout(): Promise<void> {
   await invoke("logout_user_command");
 }
 
+/* =========================
+   DELETE ACCOUNT
+========================= */
+
+/**
+ * Permanently deletes the account. With `exportPath` (absolute), a final
+ * backup is written there first.
+ */
+export async function deleteAccount(
+  currentPassword: string,
+  exportPath?: string
+): Promise<void> {
+  await invoke("delete_account", {
+    input: { current_password: currentPassword, export_path: exportPath },
+  });
+}
+
 /* =========================
    UPDATE PROFILE
 ========================= */
@@ -69,4 +104,74 @@ export async function uploadAvatar(
   return await invoke<string>("upload_avatar", {
     bytes: Array.from(new Uint8Array(bytes)),
   });
-}
\ No newline at end of file
+}
+
+/* =========================
+   ACCOUNTS
+========================= */
+
+export async function listAccounts(): Promise<AccountSummaryDTO[]> {
+  return await invoke<AccountSummaryDTO[]>("list_accounts");
+}
+
+export async function switchAccount(
+  userId: number
+): Promise<PublicUserDTO> {
+  return await invoke<PublicUserDTO>("switch_account", { userId });
+}
+
+/* =========================
+   LOGIN SESSIONS
+========================= */
+
+export async function listLoginSessions(): Promise<LoginSessionDTO[]> {
+  return await invoke<LoginSessionDTO[]>("list_login_sessions");
+}
+
+export async function revokeLoginSession(id: number): Promise<void> {
+  await invoke("revoke_login_session", { id });
+}
+
+export async function listLoginAttempts(
+  limit?: number
+): Promise<LoginAttemptDTO[]> {
+  return await invoke<LoginAttemptDTO[]>("list_login_attempts", { limit });
+}
+
+/* =========================
+   RECOVERY CODES
+========================= */
+
+export async function generateRecoveryCodes(
+  currentPassword: string
+): Promise<string[]> {
+  return await invoke<string[]>("generate_recovery_codes", {
+    currentPassword,
+  });
+}
+
+export async function getRecoveryCodesRemaining(): Promise<number> {
+  return await invoke<number>("get_recovery_codes_remaining");
+}
+
+export async function resetPasswordWithRecoveryCode(
+  email: string,
+  code: string,
+  newPassword: string
+): Promise<void> {
+  await invoke("reset_password_with_recovery_code", {
+    input: { email, code, new_password: newPassword },
+  });
+}
+
+/* =========================
+   EMAIL VERIFICATION
+========================= */
+
+export async function sendVerificationEmail(): Promise<void> {
+  await invoke("send_verification_email");
+}
+
+export async function verifyEmail(token: string): Promise<PublicUserDTO> {
+  return await invoke<PublicUserDTO>("verify_email", { token });
+}
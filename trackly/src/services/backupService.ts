@@ -0,0 +1,42 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/* =========================
+   ACCOUNT BACKUP
+========================= */
+
+export type RestoreMode = "merge" | "replace";
+
+export interface RestoreConflict {
+  start_time: string;
+  end_time: string;
+  session_id: number;
+}
+
+export interface RestoreSummary {
+  clients_created: number;
+  clients_matched: number;
+  projects_created: number;
+  projects_matched: number;
+  sessions_imported: number;
+  sessions_skipped: number;
+  sessions_conflicting: RestoreConflict[];
+  rates_imported: number;
+  invoices_imported: number;
+  invoices_matched: number;
+}
+
+export async function exportAccountBackup(
+  path: string
+): Promise<string> {
+  return await invoke<string>("export_account_backup", { path });
+}
+
+export async function importAccountBackup(
+  path: string,
+  mode: RestoreMode
+): Promise<RestoreSummary> {
+  return await invoke<RestoreSummary>("import_account_backup", {
+    path,
+    mode,
+  });
+}
@@ -0,0 +1,48 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { Client } from "../types/client";
+
+/* =========================
+   GET
+========================= */
+
+export async function getClients(): Promise<Client[]> {
+  return await invoke<Client[]>("get_clients");
+}
+
+/* =========================
+   CREATE
+========================= */
+
+export async function createClient(
+  name: string,
+  color?: string
+): Promise<Client> {
+  return await invoke<Client>("create_client", {
+    input: { name, color },
+  });
+}
+
+/* =========================
+   UPDATE
+========================= */
+
+export async function updateClient(
+  id: number,
+  updates: {
+    name?: string;
+    color?: string;
+  }
+): Promise<Client> {
+  return await invoke<Client>("update_client", {
+    input: { id, ...updates },
+  });
+}
+
+/* =========================
+   DELETE
+========================= */
+
+/** The client's projects are kept without a client. */
+export async function deleteClient(id: number): Promise<void> {
+  await invoke("delete_client", { id });
+}
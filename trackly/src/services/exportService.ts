@@ -0,0 +1,46 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/* =========================
+   CSV EXPORT
+========================= */
+
+export type CsvColumn =
+  | "project"
+  | "client"
+  | "start"
+  | "end"
+  | "duration"
+  | "description"
+  | "tags";
+
+export interface CsvExportOptions {
+  from?: string;
+  to?: string;
+  project_ids?: number[];
+  tags?: string[];
+  columns?: CsvColumn[];
+  tag_separator?: string;
+  timezone?: string;
+  date_format?: string;
+  duration_format?: "decimal_hours" | "hours_minutes";
+  /** Report sub-project sessions under their top-level project. */
+  roll_up?: boolean;
+}
+
+export interface ExportSummary {
+  path: string;
+  rows: number;
+}
+
+export async function exportSessionsCsv(
+  path: string,
+  options: CsvExportOptions = {}
+): Promise<ExportSummary> {
+  return await invoke<ExportSummary>("export_sessions_csv", {
+    path,
+    options: {
+      timezone: Intl.DateTimeFormat().resolvedOptions().timeZone,
+      ...options,
+    },
+  });
+}
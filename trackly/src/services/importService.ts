@@ -0,0 +1,53 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/* =========================
+   IMPORT
+========================= */
+
+export interface ColumnMapping {
+  project?: string | null;
+  start: string;
+  end: string;
+  description?: string | null;
+  tags?: string | null;
+}
+
+export interface ImportOptions {
+  format: "csv" | "json";
+  mapping?: ColumnMapping;
+  tag_separator?: string;
+  timezone?: string;
+  date_format?: string;
+  create_missing_projects?: boolean;
+  allow_overlap?: boolean;
+  dry_run?: boolean;
+}
+
+export interface RowIssue {
+  row: number;
+  message: string;
+}
+
+export interface ImportReport {
+  dry_run: boolean;
+  total_rows: number;
+  valid_rows: number;
+  imported: number;
+  projects_to_create: string[];
+  errors: RowIssue[];
+  overlaps: RowIssue[];
+  duplicates: RowIssue[];
+}
+
+export async function importSessions(
+  path: string,
+  options: ImportOptions
+): Promise<ImportReport> {
+  return await invoke<ImportReport>("import_sessions", {
+    path,
+    options: {
+      timezone: Intl.DateTimeFormat().resolvedOptions().timeZone,
+      ...options,
+    },
+  });
+}
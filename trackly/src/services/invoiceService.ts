@@ -0,0 +1,59 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { Invoice, InvoiceDraft, InvoiceRequest } from "../types/invoice";
+
+function withTimezone(request: InvoiceRequest): InvoiceRequest {
+  return {
+    timezone: Intl.DateTimeFormat().resolvedOptions().timeZone,
+    ...request,
+  };
+}
+
+/* =========================
+   PREVIEW / CREATE
+========================= */
+
+/** What the invoice would contain; nothing is stored. */
+export async function previewInvoice(
+  request: InvoiceRequest
+): Promise<InvoiceDraft> {
+  return await invoke<InvoiceDraft>("preview_invoice", {
+    request: withTimezone(request),
+  });
+}
+
+/**
+ * Issues the next invoice number, marks the sessions as billed and
+ * writes the HTML and PDF files, to `outputDir` if given.
+ */
+export async function createInvoice(
+  request: InvoiceRequest,
+  outputDir?: string
+): Promise<Invoice> {
+  return await invoke<Invoice>("create_invoice", {
+    input: {
+      ...withTimezone(request),
+      output_dir: outputDir,
+    },
+  });
+}
+
+/* =========================
+   GET
+========================= */
+
+export async function getInvoices(): Promise<Invoice[]> {
+  return await invoke<Invoice[]>("get_invoices");
+}
+
+export async function getInvoice(id: number): Promise<Invoice> {
+  return await invoke<Invoice>("get_invoice", { id });
+}
+
+/* =========================
+   VOID
+========================= */
+
+/** Keeps the number but frees the sessions for another invoice. */
+export async function voidInvoice(id: number): Promise<Invoice> {
+  return await invoke<Invoice>("void_invoice", { id });
+}
@@ -1,12 +1,28 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { Project } from "../types/project";
+import type {
+  Project,
+  ProjectGroup,
+  ProjectSessionsOnDelete,
+} from "../types/project";
 
 /* =========================
    GET
 ========================= */
 
-export async function getProjects(): Promise<Project[]> {
-  return await invoke<Project[]>("get_projects");
+export async function getProjects(
+  includeArchived = false
+): Promise<Project[]> {
+  return await invoke<Project[]>("get_projects", { includeArchived });
+}
+
+/** Projects grouped by client, each with its sub-projects. */
+export async function getProjectTree(
+  includeArchived = false
+): Promise<ProjectGroup[]> {
+  return await invoke<ProjectGroup[]>("get_projects", {
+    includeArchived,
+    tree: true,
+  });
 }
 
 /* =========================
@@ -15,10 +31,14 @@ export async function getProjects(): Promise<Project[]> {
 
 export async function createProject(
   name: string,
-  color: string
+  color: string,
+  placement: {
+    parent_id?: number | null;
+    client_id?: number | null;
+  } = {}
 ): Promise<Project> {
   return await invoke<Project>("create_project", {
-    input: { name, color },
+    input: { name, color, ...placement },
   });
 }
 
@@ -31,6 +51,7 @@ export async function updateProject(
   updates: {
     name?: string;
     color?: string;
+    billable?: boolean;
   }
 ): Promise<void> {
   await invoke("update_project", {
@@ -38,12 +59,49 @@ export async function updateProject(
   });
 }
 
+/* =========================
+   HIERARCHY
+========================= */
+
+/** `null` moves the project back to the top level. */
+export async function setProjectParent(
+  id: number,
+  parentId: number | null
+): Promise<Project> {
+  return await invoke<Project>("set_project_parent", { id, parentId });
+}
+
+/** Top-level projects only; sub-projects use their parent's client. */
+export async function setProjectClient(
+  id: number,
+  clientId: number | null
+): Promise<Project> {
+  return await invoke<Project>("set_project_client", { id, clientId });
+}
+
+/* =========================
+   ARCHIVE
+========================= */
+
+export async function archiveProject(id: number): Promise<Project> {
+  return await invoke<Project>("archive_project", { id });
+}
+
+export async function unarchiveProject(id: number): Promise<Project> {
+  return await invoke<Project>("unarchive_project", { id });
+}
+
 /* =========================
    DELETE
 ========================= */
 
+/** Permanent; `confirmName` must repeat the project's name. */
 export async function deleteProject(
-  id: number
+  id: number,
+  confirmName: string,
+  sessions: ProjectSessionsOnDelete
 ): Promise<void> {
-  await invoke("delete_project", { id });
+  await invoke("delete_project", {
+    input: { id, confirm_name: confirmName, sessions },
+  });
 }
\ No newline at end of file
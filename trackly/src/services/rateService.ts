@@ -0,0 +1,43 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { HourlyRate, RateScope } from "../types/rate";
+
+/* =========================
+   GET
+========================= */
+
+/** Every version of every rate. */
+export async function getRates(): Promise<HourlyRate[]> {
+  return await invoke<HourlyRate[]>("get_rates");
+}
+
+/* =========================
+   SET
+========================= */
+
+/**
+ * Adds a rate version; sessions that started before `effectiveFrom`
+ * (now by default) keep their old rate.
+ */
+export async function setRate(
+  scope: RateScope,
+  amountCents: number,
+  currency: string,
+  effectiveFrom?: string
+): Promise<HourlyRate> {
+  return await invoke<HourlyRate>("set_rate", {
+    input: {
+      ...scope,
+      amount_cents: amountCents,
+      currency,
+      effective_from: effectiveFrom,
+    },
+  });
+}
+
+/* =========================
+   DELETE
+========================= */
+
+export async function deleteRate(id: number): Promise<void> {
+  await invoke("delete_rate", { id });
+}
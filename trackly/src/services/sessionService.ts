@@ -2,6 +2,9 @@ import { invoke } from "@tauri-apps/api/core";
 import type {
   ActiveSessionDTO,
   FinishedSessionDTO,
+  SearchHitDTO,
+  SessionPageDTO,
+  SessionQueryDTO,
 } from "../types/session.dto";
 
 /* =========================
@@ -44,6 +47,18 @@ export async function finalizeSession(
   });
 }
 
+/* =========================
+   PAUSE / RESUME
+========================= */
+
+export async function pauseSession(): Promise<ActiveSessionDTO> {
+  return await invoke<ActiveSessionDTO>("pause_session");
+}
+
+export async function resumeSession(): Promise<ActiveSessionDTO> {
+  return await invoke<ActiveSessionDTO>("resume_session");
+}
+
 /* =========================
    CANCEL
 ========================= */
@@ -58,4 +73,100 @@ export async function cancelSession(): Promise<void> {
 
 export async function getFinishedSessions(): Promise<FinishedSessionDTO[]> {
   return await invoke<FinishedSessionDTO[]>("get_finished_sessions");
+}
+
+/* =========================
+   QUERY (DTO)
+========================= */
+
+export async function querySessions(
+  query: SessionQueryDTO
+): Promise<SessionPageDTO> {
+  return await invoke<SessionPageDTO>("query_sessions", { query });
+}
+
+/* =========================
+   SEARCH (DTO)
+========================= */
+
+export async function searchSessions(
+  query: string,
+  limit?: number
+): Promise<SearchHitDTO[]> {
+  return await invoke<SearchHitDTO[]>("search_sessions", {
+    query,
+    limit: limit ?? null,
+  });
+}
+
+/* =========================
+   MANUAL ENTRY
+========================= */
+
+export async function createManualSession(input: {
+  projectId?: number;
+  startTime: Date;
+  endTime: Date;
+  description: string;
+  tags: string[];
+  allowOverlap?: boolean;
+}): Promise<FinishedSessionDTO> {
+  return await invoke<FinishedSessionDTO>("create_manual_session", {
+    input: {
+      project_id: input.projectId ?? null,
+      start_time: input.startTime.toISOString(),
+      end_time: input.endTime.toISOString(),
+      description: input.description,
+      tags: input.tags,
+      allow_overlap: input.allowOverlap ?? false,
+    },
+  });
+}
+
+/* =========================
+   UPDATE
+========================= */
+
+export async function updateSession(input: {
+  id: number;
+  projectId?: number;
+  startTime: Date;
+  endTime: Date;
+  description: string;
+  tags: string[];
+  allowOverlap?: boolean;
+}): Promise<FinishedSessionDTO> {
+  return await invoke<FinishedSessionDTO>("update_session", {
+    input: {
+      id: input.id,
+      project_id: input.projectId ?? null,
+      start_time: input.startTime.toISOString(),
+      end_time: input.endTime.toISOString(),
+      description: input.description,
+      tags: input.tags,
+      allow_overlap: input.allowOverlap ?? false,
+    },
+  });
+}
+
+/* =========================
+   BILLABLE
+========================= */
+
+/** `null` makes the session follow its project again. */
+export async function setSessionBillable(
+  sessionId: number,
+  billable: boolean | null
+): Promise<void> {
+  await invoke("set_session_billable", { sessionId, billable });
+}
+
+/* =========================
+   DELETE
+========================= */
+
+export async function deleteSession(
+  sessionId: number
+): Promise<void> {
+  await invoke("delete_session", { sessionId });
 }
\ No newline at end of file
@@ -0,0 +1,98 @@
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  ClientDurationDTO,
+  DurationTotalDTO,
+  Granularity,
+  PeriodDurationDTO,
+  PeriodEarningsDTO,
+  ProjectDurationDTO,
+  ProjectEarningsDTO,
+  StatsFilterDTO,
+  TagDurationDTO,
+} from "../types/stats.dto";
+
+/* =========================
+   FILTER
+========================= */
+
+export function lastDaysFilter(
+  days: number,
+  projectId: number | null
+): StatsFilterDTO {
+  const from = new Date(Date.now() - days * 24 * 60 * 60 * 1000);
+
+  return {
+    from: from.toISOString(),
+    project_id: projectId,
+    utc_offset_minutes: -new Date().getTimezoneOffset(),
+  };
+}
+
+/* =========================
+   AGGREGATES (DTO)
+========================= */
+
+export async function getTotalDuration(
+  filter: StatsFilterDTO
+): Promise<DurationTotalDTO> {
+  return await invoke<DurationTotalDTO>("get_total_duration", { filter });
+}
+
+/** `rollUp` counts sub-project time toward the top-level project. */
+export async function getDurationByProject(
+  filter: StatsFilterDTO,
+  rollUp = false
+): Promise<ProjectDurationDTO[]> {
+  return await invoke<ProjectDurationDTO[]>("get_duration_by_project", {
+    filter,
+    rollUp,
+  });
+}
+
+export async function getDurationByClient(
+  filter: StatsFilterDTO
+): Promise<ClientDurationDTO[]> {
+  return await invoke<ClientDurationDTO[]>("get_duration_by_client", {
+    filter,
+  });
+}
+
+export async function getDurationByTag(
+  filter: StatsFilterDTO
+): Promise<TagDurationDTO[]> {
+  return await invoke<TagDurationDTO[]>("get_duration_by_tag", { filter });
+}
+
+export async function getDurationByPeriod(
+  filter: StatsFilterDTO,
+  granularity: Granularity
+): Promise<PeriodDurationDTO[]> {
+  return await invoke<PeriodDurationDTO[]>("get_duration_by_period", {
+    filter,
+    granularity,
+  });
+}
+
+/* =========================
+   EARNINGS (DTO)
+========================= */
+
+export async function getEarningsByProject(
+  filter: StatsFilterDTO,
+  rollUp = false
+): Promise<ProjectEarningsDTO[]> {
+  return await invoke<ProjectEarningsDTO[]>("get_earnings_by_project", {
+    filter,
+    rollUp,
+  });
+}
+
+export async function getEarningsByPeriod(
+  filter: StatsFilterDTO,
+  granularity: Granularity
+): Promise<PeriodEarningsDTO[]> {
+  return await invoke<PeriodEarningsDTO[]>("get_earnings_by_period", {
+    filter,
+    granularity,
+  });
+}
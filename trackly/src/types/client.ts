@@ -0,0 +1,5 @@
+export interface Client {
+  id: number;
+  name: string;
+  color: string;
+}
@@ -0,0 +1,32 @@
+import type { PasswordViolationDTO } from "./password.dto";
+
+export type AppErrorCode =
+  | "not_authenticated"
+  | "locked"
+  | "rate_limited"
+  | "not_found"
+  | "validation"
+  | "conflict"
+  | "database"
+  | "io"
+  | "internal";
+
+export interface AppErrorDTO {
+  code: AppErrorCode;
+  message: string;
+  details: {
+    field?: string | null;
+    entity?: string;
+    retry_after_seconds?: number;
+    violations?: PasswordViolationDTO[];
+  } | null;
+}
+
+export function isAppError(err: unknown): err is AppErrorDTO {
+  return (
+    typeof err === "object" &&
+    err !== null &&
+    "code" in err &&
+    "message" in err
+  );
+}
@@ -0,0 +1,44 @@
+/** "tag" bills each session under its alphabetically first tag. */
+export type InvoiceGrouping = "project" | "tag";
+
+export interface InvoiceRequest {
+  client_id: number;
+  /** Inclusive, RFC 3339. */
+  from: string;
+  /** Exclusive, RFC 3339. */
+  to: string;
+  group_by?: InvoiceGrouping;
+  notes?: string;
+  /** IANA name for the dates printed on the invoice. */
+  timezone?: string;
+}
+
+export interface InvoiceLine {
+  description: string;
+  session_count: number;
+  seconds: number;
+  /** Per hour, in the currency's minor unit. */
+  rate_cents: number;
+  amount_cents: number;
+}
+
+export interface InvoiceDraft {
+  client_id: number | null;
+  client_name: string;
+  period_from: string;
+  period_to: string;
+  currency: string;
+  lines: InvoiceLine[];
+  total_cents: number;
+}
+
+export interface Invoice extends InvoiceDraft {
+  id: number;
+  /** e.g. "INV-0007" */
+  number: string;
+  issued_at: string;
+  notes: string | null;
+  voided_at: string | null;
+  html_path: string | null;
+  pdf_path: string | null;
+}
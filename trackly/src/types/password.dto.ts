@@ -0,0 +1,13 @@
+export type PasswordViolationDTO =
+  | { code: "too_short"; min_length: number }
+  | { code: "too_long"; max_length: number }
+  | { code: "too_few_character_classes"; required: number; found: number }
+  | { code: "common" }
+  | { code: "contains_email" }
+  | { code: "too_weak"; score: number; min_score: number };
+
+export interface PasswordReportDTO {
+  score: number;
+  entropy_bits: number;
+  violations: PasswordViolationDTO[];
+}
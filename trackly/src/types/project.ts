@@ -1,5 +1,29 @@
+import type { Client } from "./client";
+
 export interface Project {
   id: number;
   name: string;
   color: string;
-}
\ No newline at end of file
+  archived_at: string | null;
+  /** Set on top-level projects only; sub-projects use their parent's. */
+  client_id: number | null;
+  parent_id: number | null;
+  /** Default for the project's sessions. */
+  billable: boolean;
+}
+
+export interface ProjectNode extends Project {
+  children: Project[];
+}
+
+/** Top-level projects of a client, or of no client when `client` is null. */
+export interface ProjectGroup {
+  client: Client | null;
+  projects: ProjectNode[];
+}
+
+/** What a hard delete does with the project's sessions. */
+export type ProjectSessionsOnDelete =
+  | { mode: "unassign" }
+  | { mode: "delete" }
+  | { mode: "reassign"; project_id: number };
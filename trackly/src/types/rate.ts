@@ -0,0 +1,18 @@
+/**
+ * What a rate applies to. The most specific one wins: project, then
+ * parent project, then client, then the whole account.
+ */
+export type RateScope =
+  | { level: "user" }
+  | { level: "client"; client_id: number }
+  | { level: "project"; project_id: number };
+
+export type HourlyRate = RateScope & {
+  id: number;
+  /** Per hour, in the currency's minor unit. */
+  amount_cents: number;
+  /** ISO 4217 code such as "EUR". */
+  currency: string;
+  /** Applies to sessions starting from this time on. */
+  effective_from: string;
+};
@@ -4,7 +4,9 @@ export interface ActiveSessionDTO {
   start_time: string;
   end_time: string | null;
   description: string | null;
-  status: "running" | "finished" | "cancelled";
+  status: "running" | "paused" | "finished" | "cancelled";
+  gross_seconds: number;
+  net_seconds: number;
 }
 
 export interface FinishedSessionDTO {
@@ -14,4 +16,49 @@ export interface FinishedSessionDTO {
   end_time: string;
   description: string | null;
   tags: string[];
+  gross_seconds: number;
+  net_seconds: number;
+}
+
+export type SessionStatusDTO = "running" | "paused" | "finished" | "cancelled";
+
+export interface SessionListItemDTO {
+  id: number;
+  project_id: number | null;
+  start_time: string;
+  end_time: string | null;
+  description: string | null;
+  status: SessionStatusDTO;
+  tags: string[];
+  gross_seconds: number;
+  net_seconds: number;
+  /** null when the session follows its project. */
+  billable: boolean | null;
+  /** Set once the session is billed; it can't be edited until voided. */
+  invoice_id: number | null;
+}
+
+export interface SessionPageDTO {
+  items: SessionListItemDTO[];
+  next_cursor: string | null;
+}
+
+export interface SearchHitDTO {
+  session: SessionListItemDTO;
+  description_snippet: string;
+  tags_snippet: string;
+  rank: number;
+}
+
+export interface SessionQueryDTO {
+  from?: string;
+  to?: string;
+  project_ids?: number[];
+  tags?: string[];
+  tag_match?: "any" | "all";
+  text?: string;
+  statuses?: SessionStatusDTO[];
+  sort?: "asc" | "desc";
+  limit?: number;
+  cursor?: string | null;
 }
\ No newline at end of file
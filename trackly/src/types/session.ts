@@ -4,7 +4,9 @@ export interface ActiveSession {
   startTime: Date;
   endTime: Date | null;
   description: string | null;
-  status: "running" | "finished" | "cancelled";
+  status: "running" | "paused" | "finished" | "cancelled";
+  grossSeconds: number;
+  netSeconds: number;
 }
 
 export interface FinishedSession {
@@ -14,4 +16,6 @@ export interface FinishedSession {
   endTime: Date;
   description: string | null;
   tags: string[];
+  grossSeconds: number;
+  netSeconds: number;
 }
\ No newline at end of file
@@ -0,0 +1,56 @@
+export type Granularity = "day" | "week" | "month";
+
+export interface StatsFilterDTO {
+  from?: string | null;
+  to?: string | null;
+  /** Also matches the project's sub-projects. */
+  project_id?: number | null;
+  client_id?: number | null;
+  utc_offset_minutes?: number;
+}
+
+export interface DurationTotalDTO {
+  total_seconds: number;
+  session_count: number;
+}
+
+export interface ProjectDurationDTO {
+  project_id: number | null;
+  project_name: string | null;
+  color: string | null;
+  seconds: number;
+}
+
+export interface ClientDurationDTO {
+  client_id: number | null;
+  client_name: string | null;
+  color: string | null;
+  seconds: number;
+}
+
+export interface TagDurationDTO {
+  tag: string;
+  seconds: number;
+}
+
+export interface PeriodDurationDTO {
+  period: string;
+  seconds: number;
+}
+
+/** One row per currency; `currency` is null for time no rate covers. */
+export interface ProjectEarningsDTO {
+  project_id: number | null;
+  project_name: string | null;
+  color: string | null;
+  currency: string | null;
+  billable_seconds: number;
+  amount_cents: number;
+}
+
+export interface PeriodEarningsDTO {
+  period: string;
+  currency: string | null;
+  billable_seconds: number;
+  amount_cents: number;
+}
@@ -4,4 +4,28 @@ export interface PublicUserDTO {
   display_name: string | null;
   avatar_url: string | null;
   email_verified?: boolean;
-}
\ No newline at end of file
+}
+export interface AccountSummaryDTO {
+  user: PublicUserDTO;
+  is_current: boolean;
+  last_used_at: string;
+}
+
+export interface LoginSessionDTO {
+  id: number;
+  created_at: string;
+  last_used_at: string;
+  expires_at: string;
+  is_current: boolean;
+}
+
+export interface LoginAttemptDTO {
+  id: number;
+  outcome: "succeeded" | "invalid_credentials" | "throttled";
+  attempted_at: string;
+}
+
+export interface RegisterResponseDTO {
+  user: PublicUserDTO;
+  recovery_codes: string[];
+}